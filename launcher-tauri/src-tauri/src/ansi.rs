@@ -0,0 +1,151 @@
+/// Foreground colours of SGR codes 30-37, and of their bright variants 90-97.
+const COLORS: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// A line of child output with its terminal control codes removed.
+pub struct Sanitized {
+    pub text: String,
+    /// First foreground colour any of the line's visible text was printed in.
+    pub color: Option<&'static str>,
+}
+
+/// Applies the parameters of an SGR (`ESC [ ... m`) sequence to the current colour.
+fn apply_sgr(params: &str, color: &mut Option<&'static str>) {
+    let mut codes = params.split(';').map(|code| code.parse::<u16>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 | 39 => *color = None,
+            30..=37 => *color = Some(COLORS[usize::from(code - 30)]),
+            90..=97 => *color = Some(COLORS[usize::from(code - 90)]),
+            // 256-colour and truecolour foregrounds; only the basic palette has a name
+            38 => {
+                *color = match codes.next() {
+                    Some(5) => codes.next().filter(|n| *n < 16).map(|n| COLORS[usize::from(n % 8)]),
+                    Some(2) => {
+                        codes.by_ref().take(3).for_each(drop);
+                        None
+                    }
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+/// One carriage-return segment: escapes dropped, SGR colour tracked.
+fn sanitize_segment(segment: &str) -> Sanitized {
+    let mut text = String::with_capacity(segment.len());
+    let mut current = None;
+    let mut color = None;
+    let mut chars = segment.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameter bytes up to a final byte in @..~
+                Some('[') => {
+                    let mut params = String::new();
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            if c == 'm' {
+                                apply_sgr(&params, &mut current);
+                            }
+                            break;
+                        }
+                        params.push(c);
+                    }
+                }
+                // OSC, e.g. a window title: runs to BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                // Two-byte sequences such as ESC 7 / ESC 8 (save/restore cursor)
+                _ => {}
+            },
+            '\t' => text.push(' '),
+            c if c.is_control() => {}
+            c => {
+                if color.is_none() && !c.is_whitespace() {
+                    color = current;
+                }
+                text.push(c);
+            }
+        }
+    }
+    Sanitized { text, color }
+}
+
+/// Cleans a raw line of child output for the console.
+///
+/// Cursor movement, erase and title sequences are dropped outright. A line that
+/// redraws itself with carriage returns (a pull or download bar) is collapsed
+/// to the last state it drew, so only that one is emitted.
+pub fn sanitize(raw: &str) -> Sanitized {
+    raw.rsplit('\r')
+        .map(sanitize_segment)
+        .find(|segment| !segment.text.trim().is_empty())
+        .unwrap_or(Sanitized { text: String::new(), color: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(raw: &str) -> String {
+        sanitize(raw).text
+    }
+
+    fn color(raw: &str) -> Option<&'static str> {
+        sanitize(raw).color
+    }
+
+    #[test]
+    fn carriage_returns_collapse_to_the_last_state_drawn() {
+        assert_eq!(text("Downloading  10%\rDownloading  55%\rDownloading 100%"), "Downloading 100%");
+        // A redraw ending the line with a bare \r keeps what it drew last
+        assert_eq!(text("layer 1/3\rlayer 2/3\r"), "layer 2/3");
+        assert_eq!(text("pulling\r   \r"), "pulling");
+        assert_eq!(text("\r\r"), "");
+    }
+
+    #[test]
+    fn sgr_codes_set_the_colour_of_the_first_visible_text() {
+        assert_eq!(color("\x1b[31mERROR\x1b[0m failed"), Some("red"));
+        assert_eq!(color("\x1b[1;32mok\x1b[0m"), Some("green"));
+        assert_eq!(color("\x1b[93mwarning"), Some("yellow"));
+        assert_eq!(color("\x1b[38;5;4mblue"), Some("blue"));
+        assert_eq!(color("\x1b[38;5;12mbright blue"), Some("blue"));
+        assert_eq!(color("\x1b[38;5;200mpink"), None);
+        assert_eq!(color("\x1b[38;2;255;0;0mtruecolour"), None);
+        // Uncoloured text first leaves the line to the first colour that shows up
+        assert_eq!(color("plain \x1b[31mred"), Some("red"));
+        assert_eq!(color("\x1b[38;2;255;0;0mtruecolour\x1b[36m cyan"), Some("cyan"));
+        // Colour set and reset before any text, or applied only to spaces, does not count
+        assert_eq!(color("\x1b[31m\x1b[0mplain"), None);
+        assert_eq!(color("\x1b[35m  \x1b[39mplain"), None);
+        assert_eq!(text("\x1b[1;32mok\x1b[0m done"), "ok done");
+    }
+
+    #[test]
+    fn each_redraw_starts_uncoloured() {
+        assert_eq!(color("\x1b[31mold\rnew"), None);
+        assert_eq!(color("old\r\x1b[34mnew"), Some("blue"));
+    }
+
+    #[test]
+    fn cursor_and_erase_sequences_are_stripped() {
+        assert_eq!(text("\x1b[1A\x1b[2Kbackend Pulled"), "backend Pulled");
+        assert_eq!(text("\x1b[2K\x1b[1G\x1b[?25lstep\x1b[?25h"), "step");
+        assert_eq!(text("\x1b7saved\x1b8"), "saved");
+    }
+
+    #[test]
+    fn titles_and_control_characters_are_stripped() {
+        assert_eq!(text("\x1b]0;docker pull\x07pulling"), "pulling");
+        assert_eq!(text("\x1b]2;title\x1b\\done"), "done");
+        assert_eq!(text("a\tb\x08c\x07"), "a bc");
+    }
+}
@@ -0,0 +1,186 @@
+use crate::compose::{run_compose, Project};
+use crate::logs::LogSource;
+use crate::messages::{Message, MessageKey};
+use crate::notify::{self, Notice};
+use crate::state::Operation;
+use crate::{emit_log_from, emit_status, observe_status, wait_for_service, OperationGuard, Phase, StatusEvent};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::AppHandle;
+use tauri::Manager;
+
+/// Attempts allowed per service within [`ATTEMPT_WINDOW`] before giving up on it.
+const MAX_ATTEMPTS: usize = 3;
+const ATTEMPT_WINDOW: Duration = Duration::from_secs(10 * 60);
+/// Delay before the first attempt; each further attempt in the window doubles it.
+const BASE_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct ServiceRestarts {
+    /// When each attempt in the current window was scheduled.
+    attempts: Vec<Instant>,
+    /// Local time of every restart that brought the service back.
+    restarts: Vec<chrono::DateTime<chrono::Local>>,
+    gave_up: bool,
+}
+
+struct Inner {
+    /// Cleared by a manual stop so a pending attempt does not bring the stack back
+    /// against the user; set again by the next boot.
+    armed: bool,
+    services: HashMap<String, ServiceRestarts>,
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        // A stack that was already up when the launcher opened is watched too
+        Self { armed: true, services: HashMap::new() }
+    }
+}
+
+/// Restart bookkeeping, managed as state.
+#[derive(Default)]
+pub struct AutoRestart(Mutex<Inner>);
+
+/// Counters for one service, as returned by `get_restart_stats`.
+#[derive(Clone, Serialize)]
+pub struct RestartStats {
+    pub service: String,
+    /// Attempts within the current window.
+    pub recent_attempts: u32,
+    pub restarts_today: u32,
+    pub restarts_total: u32,
+    /// Out of attempts until the window clears or the stack is booted again.
+    pub gave_up: bool,
+}
+
+impl AutoRestart {
+    /// Allows restarts again, forgetting earlier give-ups.
+    pub fn arm(&self) {
+        let mut inner = self.0.lock().unwrap();
+        inner.armed = true;
+        for restarts in inner.services.values_mut() {
+            restarts.attempts.clear();
+            restarts.gave_up = false;
+        }
+    }
+
+    pub fn disarm(&self) {
+        self.0.lock().unwrap().armed = false;
+    }
+
+    fn is_armed(&self) -> bool {
+        self.0.lock().unwrap().armed
+    }
+
+    /// Books an attempt for `service`, returning how long to wait before it along with
+    /// its number. Fails once the service is out of attempts, with true the first time.
+    fn book(&self, service: &str) -> Result<(Duration, usize), bool> {
+        let mut inner = self.0.lock().unwrap();
+        let restarts = inner.services.entry(service.to_string()).or_default();
+        restarts.attempts.retain(|at| at.elapsed() < ATTEMPT_WINDOW);
+        if restarts.attempts.len() >= MAX_ATTEMPTS {
+            // Reported once, not on every crash after that
+            let first = !restarts.gave_up;
+            restarts.gave_up = true;
+            return Err(first);
+        }
+        restarts.gave_up = false;
+        restarts.attempts.push(Instant::now());
+        let attempt = restarts.attempts.len();
+        Ok((BASE_DELAY * 2u32.pow(attempt as u32 - 1), attempt))
+    }
+
+    fn record_restart(&self, service: &str) {
+        let mut inner = self.0.lock().unwrap();
+        inner.services.entry(service.to_string()).or_default().restarts.push(chrono::Local::now());
+    }
+
+    pub fn stats(&self) -> Vec<RestartStats> {
+        let inner = self.0.lock().unwrap();
+        let today = chrono::Local::now().date_naive();
+        let mut stats: Vec<RestartStats> = inner
+            .services
+            .iter()
+            .map(|(service, restarts)| RestartStats {
+                service: service.clone(),
+                recent_attempts: restarts.attempts.iter().filter(|at| at.elapsed() < ATTEMPT_WINDOW).count() as u32,
+                restarts_today: restarts.restarts.iter().filter(|at| at.date_naive() == today).count() as u32,
+                restarts_total: restarts.restarts.len() as u32,
+                gave_up: restarts.gave_up,
+            })
+            .collect();
+        stats.sort_by(|a, b| a.service.cmp(&b.service));
+        stats
+    }
+}
+
+fn log(app: &AppHandle, service: &str, level: &str, message: Message) {
+    emit_log_from(app, LogSource::Launcher, Some(service), level, message);
+}
+
+/// Brings a crashed service back with `up -d`, after a backoff, unless it has
+/// crashed too often lately or the user stopped the stack in the meantime.
+pub fn schedule(app: &AppHandle, project: Project, service: String) {
+    let tracker = app.state::<AutoRestart>();
+    if !tracker.is_armed() {
+        return;
+    }
+    let (delay, attempt) = match tracker.book(&service) {
+        Ok(booked) => booked,
+        Err(first) => {
+            if first {
+                let params = json!({ "service": service, "crashes": MAX_ATTEMPTS });
+                let message = MessageKey::AutoRestartGaveUp.with(params);
+                log(app, &service, "error", message);
+                let body = format!("{} keeps crashing and will not be restarted again.", service);
+                notify::send(app, Notice::Unexpected, "A.B.E.L. auto-restart gave up", body);
+            }
+            return;
+        }
+    };
+    let params = json!({ "service": service, "secs": delay.as_secs(), "attempt": attempt, "max": MAX_ATTEMPTS });
+    log(app, &service, "warning", MessageKey::AutoRestartScheduled.with(params));
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        let tracker = app.state::<AutoRestart>();
+        if !tracker.is_armed() {
+            log(&app, &service, "info", MessageKey::AutoRestartCancelled.with(json!({ "service": service })));
+            return;
+        }
+        let Ok(guard) = OperationGuard::acquire(&app, Operation::Updating) else {
+            log(&app, &service, "warning", MessageKey::AutoRestartSkipped.with(json!({ "service": service })));
+            return;
+        };
+        emit_status(&app, StatusEvent::new(Phase::Degraded).with_operation("auto_restarting"));
+
+        let exit = run_compose(&app, &project, &["up", "-d", &service]).await;
+        let error = match exit {
+            Ok(exit) if exit.success => None,
+            Ok(exit) => Some(exit.describe()),
+            Err(e) => Some(e.to_string()),
+        };
+        let back = error.is_none() && wait_for_service(&app, &project, &service).await;
+        drop(guard);
+
+        if back {
+            tracker.record_restart(&service);
+            log(&app, &service, "success", MessageKey::AutoRestarted.with(json!({ "service": service })));
+        } else {
+            let reason = error.unwrap_or_else(|| "not healthy after restart".to_string());
+            let params = json!({ "service": service, "error": reason });
+            log(&app, &service, "error", MessageKey::AutoRestartFailed.with(params));
+        }
+        let status = observe_status(&project).await.unwrap_or_else(|_| StatusEvent::new(Phase::Degraded));
+        emit_status(&app, status);
+        // The poller takes a fresh baseline after an operation, so it will not see this crash again
+        if !back {
+            schedule(&app, project, service);
+        }
+    });
+}
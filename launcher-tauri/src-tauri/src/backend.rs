@@ -0,0 +1,121 @@
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Per-request limits for talking to the A.B.E.L. core. A probe that hangs is as
+/// good as a failed one, so these stay short.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// A manual ping should answer quickly even when the readiness wait would keep trying.
+const PING_TIMEOUT: Duration = Duration::from_secs(3);
+/// Longest response body returned from a ping.
+const BODY_LIMIT: usize = 2048;
+
+/// Shared client for the core's HTTP endpoints. The core runs on this machine, so
+/// system proxies are bypassed rather than routing localhost through them.
+fn client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .no_proxy()
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// True once `url` answers 200. Anything else, a refused connection included, means
+/// the API is not serving yet.
+pub async fn is_ready(url: &str) -> bool {
+    match client().get(url).send().await {
+        Ok(response) => response.status() == StatusCode::OK,
+        Err(_) => false,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// Answered with a 2xx.
+    Ok,
+    /// Answered, with any other status.
+    HttpError,
+    /// Nothing listens on the port: the container is down or the API hasn't bound yet.
+    Refused,
+    /// Connected or tried to, but no answer within the ping timeout.
+    Timeout,
+    /// Any other transport failure, e.g. a malformed URL or an unresolvable host.
+    Unreachable,
+}
+
+/// Result of [`ping`]. Failures are described here instead of returned as errors so
+/// the frontend can tell a refused connection from a slow or unhappy API.
+#[derive(Clone, Serialize)]
+pub struct BackendHealth {
+    pub outcome: Outcome,
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    /// The response body when it is JSON, cut to [`BODY_LIMIT`] bytes.
+    pub body: Option<String>,
+    pub error: Option<String>,
+}
+
+fn truncate(mut body: String) -> String {
+    if body.len() > BODY_LIMIT {
+        let mut end = BODY_LIMIT;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push('…');
+    }
+    body
+}
+
+/// One GET against `url` with the short ping timeout.
+pub async fn ping(url: &str) -> BackendHealth {
+    let started = Instant::now();
+    let result = client().get(url).timeout(PING_TIMEOUT).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            let outcome = if e.is_timeout() {
+                Outcome::Timeout
+            } else if e.is_connect() {
+                Outcome::Refused
+            } else {
+                Outcome::Unreachable
+            };
+            return BackendHealth {
+                outcome,
+                reachable: false,
+                status: None,
+                latency_ms,
+                body: None,
+                error: Some(e.to_string()),
+            };
+        }
+    };
+
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("json"));
+    let body = if is_json { response.text().await.ok().map(truncate) } else { None };
+    BackendHealth {
+        outcome: if status.is_success() { Outcome::Ok } else { Outcome::HttpError },
+        reachable: true,
+        status: Some(status.as_u16()),
+        latency_ms,
+        body,
+        error: (!status.is_success()).then(|| format!("HTTP {}", status)),
+    }
+}
@@ -0,0 +1,234 @@
+use crate::compose::{self, Project};
+use crate::docker;
+use crate::error::LauncherError;
+use crate::logs::LogSource;
+use crate::messages::MessageKey;
+use crate::{emit_log, emit_output};
+use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use crate::AppHandle;
+
+/// Small image with `tar` and `gzip`, pulled on first use.
+const HELPER_IMAGE: &str = "alpine:3";
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const ARCHIVE_SUFFIX: &str = ".tar.gz";
+
+/// A volume the compose config declares.
+#[derive(Clone)]
+struct ProjectVolume {
+    /// Key under `volumes:` in the compose file.
+    key: String,
+    /// Name of the volume on the daemon.
+    name: String,
+    external: bool,
+}
+
+/// One archive written by `backup_volumes` or read by `restore_volumes`.
+#[derive(Clone, Serialize)]
+pub struct VolumeArchive {
+    pub volume: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// Compose names volumes `<project>_<key>` unless the config gives a name; `config`
+/// fills the name in itself on compose v2.
+fn project_volumes(config: &Value, project_name: &str) -> Vec<ProjectVolume> {
+    let Some(volumes) = config.get("volumes").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    volumes
+        .iter()
+        .map(|(key, definition)| {
+            let external = definition.get("external").is_some_and(|external| match external {
+                Value::Bool(external) => *external,
+                // v1 spells out `external: {name: ...}`
+                Value::Object(_) => true,
+                _ => false,
+            });
+            let name = definition
+                .get("name")
+                .or_else(|| definition.pointer("/external/name"))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| if external { key.clone() } else { format!("{}_{}", project_name, key) });
+            ProjectVolume { key: key.clone(), name, external }
+        })
+        .collect()
+}
+
+/// `abel_memory--20240611T142501Z.tar.gz`. Parsed from the right, so a volume name
+/// that contains `--` itself still comes back whole.
+fn archive_name(volume: &str) -> String {
+    format!("{}--{}{}", volume, Utc::now().format(TIMESTAMP_FORMAT), ARCHIVE_SUFFIX)
+}
+
+fn parse_archive_name(file_name: &str) -> Option<(&str, NaiveDateTime)> {
+    let (volume, timestamp) = file_name.strip_suffix(ARCHIVE_SUFFIX)?.rsplit_once("--")?;
+    let taken = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    (!volume.is_empty()).then_some((volume, taken))
+}
+
+async fn volumes_of(project: &Project) -> Result<Vec<ProjectVolume>, LauncherError> {
+    let config = compose::config_json(project).await?;
+    Ok(project_volumes(&config, &compose::project_name(&config, project)))
+}
+
+/// Bind mounts need an absolute host path. Not canonicalized, since Windows would
+/// turn it into a `\\?\` path Docker doesn't accept.
+fn absolute(dir: &Path) -> Result<&Path, LauncherError> {
+    if dir.is_absolute() {
+        Ok(dir)
+    } else {
+        Err(LauncherError::invalid(format!("{} is not an absolute path", dir.display())))
+    }
+}
+
+/// Runs a throwaway container, mirroring what it prints into the console.
+async fn run_helper(app: &AppHandle, args: &[&str]) -> Result<(), LauncherError> {
+    let output = docker::command()
+        .args(["run", "--rm"])
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| LauncherError::spawn("docker", e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in stderr.lines().filter(|line| !line.trim().is_empty()) {
+        emit_output(app, LogSource::Docker, None, "info", line.trim());
+    }
+    if !output.status.success() {
+        return Err(LauncherError::from_failure(output.status.code(), &stderr));
+    }
+    Ok(())
+}
+
+async fn volume_exists(name: &str) -> bool {
+    docker::command()
+        .args(["volume", "inspect", name])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Writes one archive per project volume that exists into `dest_dir`.
+pub async fn backup(app: &AppHandle, project: &Project, dest_dir: &Path) -> Result<Vec<VolumeArchive>, LauncherError> {
+    let dest = absolute(dest_dir)?;
+    fs::create_dir_all(dest)?;
+    let mount = format!("{}:/backup", dest.to_string_lossy());
+
+    let mut existing = Vec::new();
+    for volume in volumes_of(project).await? {
+        if volume_exists(&volume.name).await {
+            existing.push(volume);
+        } else {
+            emit_log(app, "info", MessageKey::VolumeSkipped.with(json!({ "volume": volume.name })));
+        }
+    }
+
+    let mut archives = Vec::new();
+    for (index, volume) in existing.iter().enumerate() {
+        let params = json!({ "volume": volume.name, "index": index + 1, "total": existing.len() });
+        emit_log(app, "info", MessageKey::VolumeBackingUp.with(params));
+        let file_name = archive_name(&volume.name);
+        let source = format!("{}:/volume:ro", volume.name);
+        let target = format!("/backup/{}", file_name);
+        run_helper(app, &["-v", &source, "-v", &mount, HELPER_IMAGE, "tar", "czf", &target, "-C", "/volume", "."]).await?;
+
+        let path = dest.join(&file_name);
+        let size_bytes = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+        let size_mb = format!("{:.1}", size_bytes as f64 / 1e6);
+        emit_log(app, "info", MessageKey::VolumeSaved.with(json!({ "volume": volume.name, "size_mb": size_mb })));
+        archives.push(VolumeArchive { volume: volume.name.clone(), path: path.to_string_lossy().to_string(), size_bytes });
+    }
+    Ok(archives)
+}
+
+/// The newest archive in `src_dir` for each volume. Every archive must belong to a
+/// volume of the current config, so a backup of another project is never applied.
+fn plan_restore(src_dir: &Path, volumes: &[ProjectVolume]) -> Result<Vec<(ProjectVolume, VolumeArchive)>, LauncherError> {
+    let mut newest: Vec<(ProjectVolume, NaiveDateTime, VolumeArchive)> = Vec::new();
+    let mut unknown = Vec::new();
+    for entry in fs::read_dir(src_dir)?.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some((name, taken)) = parse_archive_name(&file_name) else {
+            continue;
+        };
+        let Some(volume) = volumes.iter().find(|volume| volume.name == name) else {
+            unknown.push(name.to_string());
+            continue;
+        };
+        let archive = VolumeArchive {
+            volume: volume.name.clone(),
+            path: entry.path().to_string_lossy().to_string(),
+            size_bytes: entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+        };
+        match newest.iter_mut().find(|(planned, _, _)| planned.name == volume.name) {
+            Some(planned) if planned.1 < taken => *planned = (volume.clone(), taken, archive),
+            Some(_) => {}
+            None => newest.push((volume.clone(), taken, archive)),
+        }
+    }
+
+    if !unknown.is_empty() {
+        unknown.sort();
+        unknown.dedup();
+        return Err(LauncherError::invalid(format!(
+            "archives for volumes the compose file does not define: {}",
+            unknown.join(", ")
+        )));
+    }
+    if newest.is_empty() {
+        return Err(LauncherError::invalid(format!("no volume archives found in {}", src_dir.display())));
+    }
+    Ok(newest.into_iter().map(|(volume, _, archive)| (volume, archive)).collect())
+}
+
+/// Replaces each volume's contents with its newest archive in `src_dir`. Missing
+/// volumes are created with compose's labels so a later `up` adopts them.
+pub async fn restore(app: &AppHandle, project: &Project, src_dir: &Path) -> Result<Vec<VolumeArchive>, LauncherError> {
+    let config = compose::config_json(project).await?;
+    let project_name = compose::project_name(&config, project);
+    let src = absolute(src_dir)?;
+    let plan = plan_restore(src, &project_volumes(&config, &project_name))?;
+    let mount = format!("{}:/backup:ro", src.to_string_lossy());
+
+    let total = plan.len();
+    let mut restored = Vec::new();
+    for (index, (volume, archive)) in plan.into_iter().enumerate() {
+        let params = json!({ "volume": volume.name, "index": index + 1, "total": total });
+        emit_log(app, "info", MessageKey::VolumeRestoring.with(params));
+        if !volume_exists(&volume.name).await {
+            if volume.external {
+                return Err(LauncherError::invalid(format!(
+                    "external volume {} does not exist; create it first",
+                    volume.name
+                )));
+            }
+            let project_label = format!("com.docker.compose.project={}", project_name);
+            let volume_label = format!("com.docker.compose.volume={}", volume.key);
+            let output = docker::command()
+                .args(["volume", "create", "--label", &project_label, "--label", &volume_label, &volume.name])
+                .kill_on_drop(true)
+                .output()
+                .await
+                .map_err(|e| LauncherError::spawn("docker", e))?;
+            if !output.status.success() {
+                return Err(LauncherError::from_failure(output.status.code(), &String::from_utf8_lossy(&output.stderr)));
+            }
+        }
+
+        let file_name = Path::new(&archive.path).file_name().unwrap_or_default().to_string_lossy().to_string();
+        let target = format!("{}:/volume", volume.name);
+        // Empties the volume first so files deleted since the backup don't linger
+        let script = format!("find /volume -mindepth 1 -delete && tar xzf '/backup/{}' -C /volume", file_name);
+        run_helper(app, &["-v", &target, "-v", &mount, HELPER_IMAGE, "sh", "-c", &script]).await?;
+        emit_log(app, "info", MessageKey::VolumeRestored.with(json!({ "volume": volume.name, "file": file_name })));
+        restored.push(archive);
+    }
+    Ok(restored)
+}
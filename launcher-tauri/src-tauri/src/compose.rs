@@ -0,0 +1,664 @@
+use crate::docker;
+use crate::env::{self, EnvLine};
+use crate::error::LauncherError;
+use crate::limits;
+use crate::process::{RunOptions, StreamedExit};
+use crate::proxy;
+use crate::runtime::{self, stdout_of, ContainerRuntime, RuntimeKind};
+use crate::secrets;
+use crate::settings;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::process::Command;
+use crate::AppHandle;
+
+/// How Compose is invoked on this machine.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComposeFlavor {
+    /// The v2 `docker compose` CLI plugin.
+    Plugin,
+    /// A standalone `docker-compose` binary.
+    Standalone,
+    /// `podman compose`, which hands off to whichever compose provider is installed.
+    PodmanPlugin,
+    /// The standalone `podman-compose` script.
+    PodmanCompose,
+}
+
+impl ComposeFlavor {
+    fn program(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            ComposeFlavor::Plugin => ("docker", &["compose"]),
+            ComposeFlavor::Standalone => ("docker-compose", &[]),
+            ComposeFlavor::PodmanPlugin => ("podman", &["compose"]),
+            ComposeFlavor::PodmanCompose => ("podman-compose", &[]),
+        }
+    }
+
+    /// The flavors that drive `runtime`, in order of preference.
+    fn for_runtime(runtime: RuntimeKind) -> [ComposeFlavor; 2] {
+        match runtime {
+            RuntimeKind::Docker => [ComposeFlavor::Plugin, ComposeFlavor::Standalone],
+            RuntimeKind::Podman => [ComposeFlavor::PodmanPlugin, ComposeFlavor::PodmanCompose],
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct ComposeInfo {
+    pub flavor: ComposeFlavor,
+    pub version: String,
+}
+
+// Only successful probes are cached so installing Compose later is picked up without a restart.
+static DETECTED: Mutex<Option<ComposeInfo>> = Mutex::new(None);
+
+async fn probe(flavor: ComposeFlavor) -> Option<ComposeInfo> {
+    let (program, prefix) = flavor.program();
+    let output = docker::apply_environment(&mut Command::new(program))
+        .args(prefix)
+        .args(["version", "--short"])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(ComposeInfo {
+        flavor,
+        version: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+    })
+}
+
+/// Detects the Compose flavor for the runtime in use, preferring its plugin.
+pub async fn detect() -> Result<ComposeInfo, LauncherError> {
+    detect_for(runtime::detect().await).await
+}
+
+/// Detects the Compose flavor for `runtime`, preferring its plugin.
+pub async fn detect_for(runtime: RuntimeKind) -> Result<ComposeInfo, LauncherError> {
+    let flavors = ComposeFlavor::for_runtime(runtime);
+    // A flavor of the other runtime is left over from before the setting changed
+    if let Some(info) = DETECTED.lock().unwrap().as_ref().filter(|info| flavors.contains(&info.flavor)) {
+        return Ok(info.clone());
+    }
+
+    // Concurrent first calls may both probe; they find the same answer
+    for flavor in flavors {
+        if let Some(info) = probe(flavor).await {
+            *DETECTED.lock().unwrap() = Some(info.clone());
+            return Ok(info);
+        }
+    }
+    Err(LauncherError::compose_missing(runtime))
+}
+
+/// Healthcheck state; services without a healthcheck carry none.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Health {
+    Starting,
+    Healthy,
+    Unhealthy,
+}
+
+impl Health {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "starting" => Some(Health::Starting),
+            "healthy" => Some(Health::Healthy),
+            "unhealthy" => Some(Health::Unhealthy),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct ServiceStatus {
+    pub service: String,
+    pub container_id: String,
+    pub state: String,
+    pub health: Option<Health>,
+}
+
+impl ServiceStatus {
+    fn from_ps_entry(entry: &Value) -> Option<Self> {
+        let field = |key: &str| entry.get(key).and_then(Value::as_str).unwrap_or_default();
+
+        let service = field("Service");
+        if service.is_empty() {
+            return None;
+        }
+        Some(Self {
+            service: service.to_string(),
+            container_id: field("ID").to_string(),
+            state: field("State").to_lowercase(),
+            health: Health::parse(field("Health")),
+        })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+}
+
+/// Override files compose's own lookup adds on top of the base file.
+const OVERRIDE_FILE_NAMES: [&str; 4] = [
+    "compose.override.yaml",
+    "compose.override.yml",
+    "docker-compose.override.yaml",
+    "docker-compose.override.yml",
+];
+
+/// Where, with which files and on which runtime compose runs for the A.B.E.L. stack.
+#[derive(Clone)]
+pub struct Project {
+    pub runtime: Arc<dyn ContainerRuntime>,
+    pub dir: String,
+    /// Passed as `-f`, relative to `dir`. Empty leaves file lookup to compose.
+    pub files: Vec<String>,
+    /// Passed as `--profile`, so services of inactive profiles are invisible to
+    /// every command, `ps` and `config --services` included.
+    pub profiles: Vec<String>,
+}
+
+impl Project {
+    /// Compose's own "no configuration file provided" is buried in stderr; failing before
+    /// the spawn lets the frontend ask for the right directory instead.
+    fn ensure_compose_file(&self) -> Result<(), LauncherError> {
+        let dir = Path::new(&self.dir);
+        let found = if self.files.is_empty() {
+            settings::has_compose_file(dir)
+        } else {
+            self.files.iter().all(|file| dir.join(file).is_file())
+        };
+        if found {
+            Ok(())
+        } else {
+            Err(LauncherError::ComposeFileNotFound { searched_dir: self.dir.clone() })
+        }
+    }
+
+    /// The compose files this project reads: the configured ones, or the first name
+    /// of compose's own lookup that exists.
+    pub fn compose_file_paths(&self) -> Vec<PathBuf> {
+        let dir = Path::new(&self.dir);
+        if !self.files.is_empty() {
+            return self.files.iter().map(|file| dir.join(file)).collect();
+        }
+        settings::COMPOSE_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+            .into_iter()
+            .collect()
+    }
+
+    /// The files passed as `-f`. With the launcher's override present, the lookup compose
+    /// would otherwise do is spelled out, since any `-f` turns it off, and the override
+    /// goes last so its limits win.
+    fn file_args(&self) -> Vec<String> {
+        let dir = Path::new(&self.dir);
+        if !dir.join(limits::OVERRIDE_FILE).is_file() {
+            return self.files.clone();
+        }
+        let mut files = self.files.clone();
+        if files.is_empty() {
+            if let Some(base) = settings::COMPOSE_FILE_NAMES.iter().find(|name| dir.join(name).is_file()) {
+                files.push(base.to_string());
+            }
+            if let Some(user_override) = OVERRIDE_FILE_NAMES.iter().find(|name| dir.join(name).is_file()) {
+                files.push(user_override.to_string());
+            }
+        }
+        files.push(limits::OVERRIDE_FILE.to_string());
+        files
+    }
+
+    /// Full argument list for `args`: the flavor prefix, then the project-wide flags.
+    /// Every compose invocation goes through here so no flag is forgotten somewhere.
+    async fn command_line(
+        &self,
+        runtime: RuntimeKind,
+        args: &[&str],
+    ) -> Result<(&'static str, Vec<String>), LauncherError> {
+        self.ensure_compose_file()?;
+        let (program, prefix) = detect_for(runtime).await?.flavor.program();
+
+        let mut line: Vec<String> = prefix.iter().map(|arg| arg.to_string()).collect();
+        for file in self.file_args() {
+            line.push("-f".to_string());
+            line.push(file);
+        }
+        for profile in &self.profiles {
+            line.push("--profile".to_string());
+            line.push(profile.clone());
+        }
+        line.extend(args.iter().map(|arg| arg.to_string()));
+        if args.first() == Some(&"build") {
+            for arg in proxy::build_args() {
+                line.push("--build-arg".to_string());
+                line.push(arg);
+            }
+        }
+        Ok((program, line))
+    }
+}
+
+/// Pull policies `up --pull` accepts.
+pub const PULL_POLICIES: [&str; 3] = ["always", "missing", "never"];
+
+/// How a boot runs `up`. The default is the launcher's original `up -d --build`.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StartOptions {
+    /// Rebuild images before starting. Off, compose only builds images that are missing.
+    pub build: bool,
+    /// Build without the layer cache. Needs `build`.
+    pub no_cache: bool,
+    pub force_recreate: bool,
+    /// One of [`PULL_POLICIES`]. None leaves it to compose.
+    pub pull: Option<String>,
+}
+
+impl Default for StartOptions {
+    fn default() -> Self {
+        Self { build: true, no_cache: false, force_recreate: false, pull: None }
+    }
+}
+
+impl StartOptions {
+    pub fn validate(&self) -> Result<(), LauncherError> {
+        if self.no_cache && !self.build {
+            return Err(LauncherError::invalid("no_cache needs build: there is nothing to build without the cache"));
+        }
+        if let Some(pull) = self.pull.as_deref().filter(|pull| !PULL_POLICIES.contains(pull)) {
+            let message = format!("unknown pull policy: {} (use {})", pull, PULL_POLICIES.join(", "));
+            return Err(LauncherError::invalid(message));
+        }
+        Ok(())
+    }
+
+    /// `up` takes neither `--no-cache` nor build args, so with either the images are
+    /// built by a separate `build` ahead of it.
+    pub fn separate_build(&self) -> bool {
+        self.build && (self.no_cache || !proxy::build_args().is_empty())
+    }
+
+    pub fn build_args(&self) -> Vec<&'static str> {
+        if self.no_cache {
+            vec!["build", "--no-cache"]
+        } else {
+            vec!["build"]
+        }
+    }
+
+    pub fn up_args(&self) -> Vec<&str> {
+        let mut args = vec!["up", "-d"];
+        if self.build && !self.separate_build() {
+            args.push("--build");
+        }
+        if self.force_recreate {
+            args.push("--force-recreate");
+        }
+        if let Some(pull) = &self.pull {
+            args.extend(["--pull", pull.as_str()]);
+        }
+        args
+    }
+
+    /// For the boot log, e.g. `BUILD, NO CACHE, PULL ALWAYS`.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.build {
+            parts.push("BUILD".to_string());
+        }
+        if self.no_cache {
+            parts.push("NO CACHE".to_string());
+        }
+        if self.force_recreate {
+            parts.push("FORCE RECREATE".to_string());
+        }
+        if let Some(pull) = &self.pull {
+            parts.push(format!("PULL {}", pull.to_uppercase()));
+        }
+        if parts.is_empty() {
+            "NO BUILD".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// A compose command for `project` on its runtime, killed if its future is dropped
+/// before it exits.
+pub async fn compose_command(project: &Project, args: &[&str]) -> Result<Command, LauncherError> {
+    project.runtime.compose_command(project, args).await
+}
+
+/// The compose process of `runtime` for `project`, which the CLI runtimes run.
+pub async fn command_for(runtime: RuntimeKind, project: &Project, args: &[&str]) -> Result<Command, LauncherError> {
+    let (program, line) = project.command_line(runtime, args).await?;
+    let mut command = Command::new(program);
+    docker::apply_environment(&mut command).args(line).current_dir(&project.dir).kill_on_drop(true);
+    // Only `up` creates containers, so only it is handed the secrets `.env` keeps in the keyring
+    if args.first() == Some(&"up") {
+        command.envs(secrets::resolve(&project.dir)?);
+    }
+    Ok(command)
+}
+
+/// Runs a compose subcommand, streaming its output to the console.
+pub async fn run_compose(app: &AppHandle, project: &Project, args: &[&str]) -> Result<StreamedExit, LauncherError> {
+    run_compose_with(app, project, args, RunOptions::default()).await
+}
+
+/// Like [`run_compose`], with the extra process controls in `options`.
+pub async fn run_compose_with(
+    app: &AppHandle,
+    project: &Project,
+    args: &[&str],
+    options: RunOptions<'_>,
+) -> Result<StreamedExit, LauncherError> {
+    project.runtime.compose_streamed(app, project, args, options).await
+}
+
+/// Runs a compose subcommand and returns its output, or an error carrying its stderr.
+pub async fn compose_output(project: &Project, args: &[&str]) -> Result<String, LauncherError> {
+    stdout_of(project.runtime.compose(project, args).await?)
+}
+
+/// Compose project names are lowercase letters, digits, `-` and `_`, starting with a
+/// letter or digit. Directory names are cut down to that the way compose does it.
+fn normalize_project_name(name: &str) -> String {
+    let name: String = name
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .collect();
+    name.trim_start_matches(['-', '_']).to_string()
+}
+
+/// Picks the project name by compose's precedence: `COMPOSE_PROJECT_NAME`, then the
+/// config's top-level `name`, then the project directory's name.
+fn resolve_project_name(env_override: Option<&str>, config_name: Option<&str>, dir: &str) -> String {
+    let dir_name = || {
+        Path::new(dir)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let chosen = [env_override, config_name]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|name| !name.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(dir_name);
+    normalize_project_name(&chosen)
+}
+
+/// Name compose runs the project under, which prefixes its containers, volumes and
+/// networks and fills the `com.docker.compose.project` label. The override is read
+/// from the launcher's environment, which compose inherits, or the project's `.env`.
+pub fn project_name(config: &Value, project: &Project) -> String {
+    let from_env = std::env::var("COMPOSE_PROJECT_NAME").ok().or_else(|| {
+        env::read(&project.dir).ok()?.into_iter().find_map(|line| match line {
+            EnvLine::Entry { key, value } if key == "COMPOSE_PROJECT_NAME" => Some(value),
+            _ => None,
+        })
+    });
+    let config_name = config.get("name").and_then(Value::as_str);
+    resolve_project_name(from_env.as_deref(), config_name, &project.dir)
+}
+
+/// A project's dir and files, which its name is cached by.
+type NameKey = (String, Vec<String>);
+
+/// The last name [`cached_project_name`] read from a valid config.
+static PROJECT_NAME: Mutex<Option<(NameKey, String)>> = Mutex::new(None);
+
+/// [`project_name`] without running `config` on every status poll. A `name:` or
+/// `COMPOSE_PROJECT_NAME` edited afterwards shows once the project is switched.
+pub async fn cached_project_name(project: &Project) -> String {
+    let key = (project.dir.clone(), project.files.clone());
+    if let Some((cached, name)) = PROJECT_NAME.lock().unwrap().as_ref() {
+        if *cached == key {
+            return name.clone();
+        }
+    }
+    match config_json(project).await {
+        Ok(config) => {
+            let name = project_name(&config, project);
+            *PROJECT_NAME.lock().unwrap() = Some((key, name.clone()));
+            name
+        }
+        // The directory still names the project; a fixed file is read next time
+        Err(_) => project_name(&Value::Null, project),
+    }
+}
+
+/// One problem `config --quiet` found, located as precisely as its message allows.
+#[derive(Clone, Debug, Serialize)]
+pub struct ComposeIssue {
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+/// Result of `validate_compose`.
+#[derive(Clone, Serialize)]
+pub struct ComposeValidation {
+    pub valid: bool,
+    pub issues: Vec<ComposeIssue>,
+}
+
+fn location_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"line (?P<line>\d+)(?:, column (?P<column>\d+))?").unwrap())
+}
+
+fn file_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // v2: `parsing C:\dir\compose.yml: ...`, `validating ...: ...`; v1: `in "./docker-compose.yml"`
+    RE.get_or_init(|| Regex::new(r#"(?:(?:parsing|validating) (?P<file>.+?): |in "(?P<quoted>[^"]+)")"#).unwrap())
+}
+
+/// Each non-empty stderr line is an issue. Compose v1 spreads one YAML error over
+/// several lines, so its location lines are folded into the issue above them.
+fn parse_config_errors(stderr: &str) -> Vec<ComposeIssue> {
+    let mut issues: Vec<ComposeIssue> = Vec::new();
+    for line in stderr.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let message = line.strip_prefix("ERROR:").unwrap_or(line).trim();
+        let location = location_pattern().captures(message);
+        let number = |name: &str| location.as_ref()?.name(name)?.as_str().parse().ok();
+        let file = file_pattern()
+            .captures(message)
+            .and_then(|captures| captures.name("file").or(captures.name("quoted")))
+            .map(|file| file.as_str().to_string());
+        let (line, column) = (number("line"), number("column"));
+
+        let continuation = message.starts_with("in \"") && issues.last().is_some_and(|issue| issue.line.is_none());
+        match issues.last_mut() {
+            Some(issue) if continuation => {
+                issue.file = issue.file.take().or(file);
+                issue.line = line;
+                issue.column = column;
+            }
+            _ => issues.push(ComposeIssue { file, line, column, message: message.to_string() }),
+        }
+    }
+    issues
+}
+
+/// Runs `config --quiet`, which parses, interpolates and validates every compose
+/// file without touching a container. Errors only when compose itself can't run.
+pub async fn validate(project: &Project) -> Result<ComposeValidation, LauncherError> {
+    let output = project.runtime.compose(project, &["config", "--quiet"]).await?;
+    if output.status.success() {
+        return Ok(ComposeValidation { valid: true, issues: Vec::new() });
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let mut issues = parse_config_errors(&stderr);
+    if issues.is_empty() {
+        issues.push(ComposeIssue {
+            file: None,
+            line: None,
+            column: None,
+            message: format!("docker compose config exited with {:?}", output.status.code()),
+        });
+    }
+    Ok(ComposeValidation { valid: false, issues })
+}
+
+/// Services declared in the compose file.
+pub async fn expected_services(project: &Project) -> Result<Vec<String>, LauncherError> {
+    project.runtime.services(project).await
+}
+
+/// Every profile the compose file defines, active or not.
+pub async fn profiles(project: &Project) -> Result<Vec<String>, LauncherError> {
+    let stdout = compose_output(project, &["config", "--profiles"]).await?;
+    let mut profiles: Vec<String> = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    profiles.sort();
+    Ok(profiles)
+}
+
+/// The fully resolved compose configuration.
+pub async fn config_json(project: &Project) -> Result<Value, LauncherError> {
+    let stdout = compose_output(project, &["config", "--format", "json"]).await?;
+    serde_json::from_str(&stdout).map_err(|e| e.to_string().into())
+}
+
+/// Image references of services that are pulled rather than built.
+pub async fn pullable_images(project: &Project) -> Result<Vec<String>, LauncherError> {
+    let config = config_json(project).await?;
+    let mut images: Vec<String> = config
+        .get("services")
+        .and_then(Value::as_object)
+        .map(|services| {
+            services
+                .values()
+                .filter(|service| service.get("build").is_none())
+                .filter_map(|service| service.get("image").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    images.sort();
+    images.dedup();
+    Ok(images)
+}
+
+/// Containers of the project, including stopped ones.
+pub async fn service_status(project: &Project) -> Result<Vec<ServiceStatus>, LauncherError> {
+    project.runtime.ps(project).await
+}
+
+/// [`service_status`] through `ps` of the runtime's compose.
+pub async fn ps_cli<R>(runtime: &R, project: &Project) -> Result<Vec<ServiceStatus>, LauncherError>
+where
+    R: ContainerRuntime + ?Sized,
+{
+    let stdout = stdout_of(runtime.compose(project, &["ps", "--all", "--format", "json"]).await?)?;
+    parse_ps_output(&stdout)
+}
+
+/// Compose releases disagree on the shape of `ps --format json`: older v2 builds print
+/// a single JSON array, newer ones print one object per line.
+fn parse_ps_output(stdout: &str) -> Result<Vec<ServiceStatus>, LauncherError> {
+    let trimmed = stdout.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let entries: Vec<Value> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).map_err(|e| e.to_string())?
+    } else {
+        trimmed
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    Ok(entries.iter().filter_map(ServiceStatus::from_ps_entry).collect())
+}
+
+/// The last `lines` lines `service` logged, without compose's prefix.
+pub async fn tail_logs(project: &Project, service: &str, lines: usize) -> Result<Vec<String>, LauncherError> {
+    project.runtime.logs(project, service, lines).await
+}
+
+/// Running only when every service declared in the compose file has a running container.
+pub async fn all_services_running(project: &Project) -> Result<bool, LauncherError> {
+    let expected = expected_services(project).await?;
+    let statuses = service_status(project).await?;
+
+    Ok(!expected.is_empty()
+        && expected.iter().all(|name| {
+            statuses
+                .iter()
+                .any(|status| &status.service == name && status.is_running())
+        }))
+}
+
+/// Running services whose healthcheck has not reported healthy yet.
+pub fn unhealthy_services(statuses: &[ServiceStatus]) -> Vec<String> {
+    statuses
+        .iter()
+        .filter(|status| status.is_running())
+        .filter(|status| matches!(status.health, Some(Health::Starting | Health::Unhealthy)))
+        .map(|status| status.service.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_names_are_normalized_like_compose_does() {
+        for (name, normalized) in [
+            ("abel", "abel"),
+            ("ABEL-Assistant", "abel-assistant"),
+            ("my stack", "mystack"),
+            ("Abel Assistant (dev)", "abelassistantdev"),
+            ("2024_abel", "2024_abel"),
+            ("__abel", "abel"),
+            ("-_-abel_-", "abel_-"),
+            ("été", "t"),
+            ("", ""),
+            ("___", ""),
+        ] {
+            assert_eq!(normalize_project_name(name), normalized, "{name}");
+        }
+    }
+
+    #[test]
+    fn project_names_follow_compose_precedence() {
+        let dir = "/home/abel/ABEL Assistant";
+        assert_eq!(resolve_project_name(Some("Override"), Some("config"), dir), "override");
+        assert_eq!(resolve_project_name(None, Some("Config Name"), dir), "configname");
+        assert_eq!(resolve_project_name(None, None, dir), "abelassistant");
+        // Blank values fall through to the next source
+        assert_eq!(resolve_project_name(Some("  "), Some(""), dir), "abelassistant");
+        assert_eq!(resolve_project_name(Some(" _1abel "), None, dir), "1abel");
+        assert_eq!(resolve_project_name(None, None, "/"), "");
+        assert_eq!(resolve_project_name(None, None, "/srv/_stack/"), "stack");
+    }
+}
@@ -0,0 +1,127 @@
+use crate::compose::{self, Project};
+use crate::docker;
+use crate::error::LauncherError;
+use reqwest::Client;
+use serde::Serialize;
+use std::net::ToSocketAddrs;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Short on purpose: the point is to answer before Docker starts its own long retries.
+const DNS_TIMEOUT: Duration = Duration::from_secs(3);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// How image references without a registry are written, and where they are pulled from.
+const DOCKER_HUB: &str = "docker.io";
+const DOCKER_HUB_HOST: &str = "registry-1.docker.io";
+
+#[derive(Clone, Serialize)]
+pub struct RegistryCheck {
+    /// As written in image references, e.g. `docker.io` or `ghcr.io`.
+    pub registry: String,
+    pub images: Vec<String>,
+    pub dns_resolved: bool,
+    /// Answered the registry API's base endpoint with any HTTP status.
+    pub reachable: bool,
+    pub error: Option<String>,
+}
+
+/// Result of `check_connectivity`.
+#[derive(Clone, Serialize)]
+pub struct ConnectivityReport {
+    pub registries: Vec<RegistryCheck>,
+    pub online: bool,
+}
+
+/// The registry an image reference pulls from. The first path component names one
+/// only when it looks like a host, the way Docker decides it.
+fn registry_of(image: &str) -> String {
+    match image.split_once('/') {
+        Some((first, _)) if first.contains(['.', ':']) || first == "localhost" => first.to_string(),
+        _ => DOCKER_HUB.to_string(),
+    }
+}
+
+/// Images grouped by registry, in the order registries first appear.
+fn by_registry(images: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for image in images {
+        let registry = registry_of(image);
+        match groups.iter_mut().find(|(name, _)| *name == registry) {
+            Some((_, images)) => images.push(image.clone()),
+            None => groups.push((registry, vec![image.clone()])),
+        }
+    }
+    groups
+}
+
+fn client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| Client::builder().timeout(REQUEST_TIMEOUT).build().unwrap_or_default())
+}
+
+async fn resolves(host: &str) -> Result<(), String> {
+    let (name, port) = match host.rsplit_once(':') {
+        Some((name, port)) => (name.to_string(), port.parse().unwrap_or(443)),
+        None => (host.to_string(), 443),
+    };
+    let lookup = tauri::async_runtime::spawn_blocking(move || (name.as_str(), port).to_socket_addrs().map(|_| ()));
+    match tokio::time::timeout(DNS_TIMEOUT, lookup).await {
+        Ok(Ok(Ok(()))) => Ok(()),
+        Ok(Ok(Err(e))) => Err(format!("DNS lookup failed: {}", e)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("DNS lookup timed out".to_string()),
+    }
+}
+
+async fn probe(registry: String, images: Vec<String>) -> RegistryCheck {
+    let host = if registry == DOCKER_HUB { DOCKER_HUB_HOST } else { registry.as_str() };
+    let mut check = RegistryCheck { registry: registry.clone(), images, dns_resolved: false, reachable: false, error: None };
+    if let Err(error) = resolves(host).await {
+        check.error = Some(error);
+        return check;
+    }
+    check.dns_resolved = true;
+    // `/v2/` answers 401 without credentials, which still proves the registry is there
+    match client().head(&format!("https://{}/v2/", host)).send().await {
+        Ok(_) => check.reachable = true,
+        Err(e) if e.is_timeout() => check.error = Some("no answer before the timeout".to_string()),
+        Err(e) => check.error = Some(e.to_string()),
+    }
+    check
+}
+
+async fn check_images(images: &[String]) -> ConnectivityReport {
+    let mut registries = Vec::new();
+    for (registry, images) in by_registry(images) {
+        registries.push(probe(registry, images).await);
+    }
+    ConnectivityReport { online: registries.iter().all(|check| check.reachable), registries }
+}
+
+/// Every registry the project's pulled images come from. Built services are left
+/// out; their base images are only known to the Dockerfile.
+pub async fn check(project: &Project) -> Result<ConnectivityReport, LauncherError> {
+    Ok(check_images(&compose::pullable_images(project).await?).await)
+}
+
+/// Pulled images that aren't present locally, so `up` would have to download them.
+pub async fn missing_images(project: &Project) -> Result<Vec<String>, LauncherError> {
+    let mut missing = Vec::new();
+    for image in compose::pullable_images(project).await? {
+        if docker::image_id(&image).await.is_none() {
+            missing.push(image);
+        }
+    }
+    Ok(missing)
+}
+
+/// Registries the missing images would come from that can't be reached. Empty when
+/// nothing needs pulling, so a fully cached stack boots offline.
+pub async fn unreachable_for_pull(project: &Project) -> Result<Vec<RegistryCheck>, LauncherError> {
+    let missing = missing_images(project).await?;
+    if missing.is_empty() {
+        return Ok(Vec::new());
+    }
+    let report = check_images(&missing).await;
+    Ok(report.registries.into_iter().filter(|check| !check.reachable).collect())
+}
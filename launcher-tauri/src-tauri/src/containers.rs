@@ -0,0 +1,186 @@
+use crate::compose::{self, Health, Project};
+use crate::error::LauncherError;
+use crate::runtime::{stdout_of, ContainerRuntime};
+use serde::Serialize;
+use serde_json::Value;
+
+/// What docker reports for a time that never happened, e.g. a start of a container
+/// that was only created.
+const ZERO_TIME_PREFIX: &str = "0001-01-01";
+
+/// A published port. Ports only exposed to other containers aren't listed.
+#[derive(Clone, PartialEq, Serialize)]
+pub struct PortMapping {
+    pub host_ip: Option<String>,
+    pub host_port: u16,
+    pub container_port: u16,
+    pub protocol: String,
+}
+
+/// One container of the project, as returned by `list_containers`.
+#[derive(Clone, Serialize)]
+pub struct ContainerInfo {
+    pub service: String,
+    pub name: String,
+    pub id: String,
+    pub image: String,
+    pub image_id: Option<String>,
+    pub state: String,
+    pub health: Option<Health>,
+    pub created_at: Option<String>,
+    pub started_at: Option<String>,
+    pub restart_count: u64,
+    pub ports: Vec<PortMapping>,
+}
+
+fn text(entry: &Value, pointer: &str) -> Option<String> {
+    entry
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty() && !value.starts_with(ZERO_TIME_PREFIX))
+        .map(str::to_string)
+}
+
+/// `ps --format json` prints one object per line; some builds print an array instead.
+fn parse_ps(stdout: &str) -> Vec<Value> {
+    let trimmed = stdout.trim();
+    if trimmed.starts_with('[') {
+        return serde_json::from_str(trimmed).unwrap_or_default();
+    }
+    trimmed.lines().filter_map(|line| serde_json::from_str(line.trim()).ok()).collect()
+}
+
+/// `Labels` in `ps` output: a `key=value,key=value` string, though a map is read too.
+fn ps_label(entry: &Value, key: &str) -> Option<String> {
+    match entry.get("Labels")? {
+        Value::String(labels) => labels.split(',').find_map(|label| {
+            let (name, value) = label.split_once('=')?;
+            (name.trim() == key).then(|| value.trim().to_string())
+        }),
+        Value::Object(labels) => labels.get(key).and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    }
+}
+
+/// `NetworkSettings.Ports`: `{"8000/tcp": [{"HostIp": "0.0.0.0", "HostPort": "8000"}]}`,
+/// with null for a port that is exposed but not published. The IPv4 and IPv6
+/// bindings of one port are listed once.
+fn inspect_ports(entry: &Value) -> Vec<PortMapping> {
+    let Some(ports) = entry.pointer("/NetworkSettings/Ports").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut mappings: Vec<PortMapping> = Vec::new();
+    for (key, bindings) in ports {
+        let (port, protocol) = key.split_once('/').unwrap_or((key.as_str(), "tcp"));
+        let Ok(container_port) = port.parse::<u16>() else {
+            continue;
+        };
+        for binding in bindings.as_array().into_iter().flatten() {
+            let host_port = binding.get("HostPort").and_then(|port| match port {
+                Value::String(port) => port.parse::<u16>().ok(),
+                port => port.as_u64().and_then(|port| u16::try_from(port).ok()),
+            });
+            let Some(host_port) = host_port else {
+                continue;
+            };
+            let mapping = PortMapping {
+                host_ip: text(binding, "/HostIp"),
+                host_port,
+                container_port,
+                protocol: protocol.to_string(),
+            };
+            let same_port = |known: &PortMapping| {
+                known.host_port == host_port && known.container_port == container_port && known.protocol == protocol
+            };
+            if !mappings.iter().any(same_port) {
+                mappings.push(mapping);
+            }
+        }
+    }
+    mappings.sort_by_key(|mapping| (mapping.host_port, mapping.container_port));
+    mappings
+}
+
+/// The `ps` fields alone, for when `inspect` printed nothing usable.
+fn from_ps(entry: &Value) -> Option<ContainerInfo> {
+    Some(ContainerInfo {
+        service: ps_label(entry, "com.docker.compose.service").unwrap_or_default(),
+        name: text(entry, "/Names")?,
+        id: text(entry, "/ID")?,
+        image: text(entry, "/Image").unwrap_or_default(),
+        image_id: None,
+        // `State` is missing from releases before 23; the status text starts with it
+        state: text(entry, "/State")
+            .or_else(|| text(entry, "/Status").and_then(|status| status.split_whitespace().next().map(str::to_string)))
+            .unwrap_or_default()
+            .to_lowercase(),
+        health: None,
+        created_at: text(entry, "/CreatedAt"),
+        started_at: None,
+        restart_count: 0,
+        ports: Vec::new(),
+    })
+}
+
+fn from_inspect(entry: &Value) -> Option<ContainerInfo> {
+    Some(ContainerInfo {
+        service: text(entry, "/Config/Labels/com.docker.compose.service").unwrap_or_default(),
+        name: text(entry, "/Name")?.trim_start_matches('/').to_string(),
+        id: text(entry, "/Id")?,
+        image: text(entry, "/Config/Image").unwrap_or_default(),
+        image_id: text(entry, "/Image"),
+        state: text(entry, "/State/Status").unwrap_or_default().to_lowercase(),
+        health: text(entry, "/State/Health/Status").and_then(|health| Health::parse(&health)),
+        created_at: text(entry, "/Created"),
+        started_at: text(entry, "/State/StartedAt"),
+        restart_count: entry.get("RestartCount").and_then(Value::as_u64).unwrap_or(0),
+        ports: inspect_ports(entry),
+    })
+}
+
+/// Every container of the project, running or exited, found by label so ones of
+/// services since removed from the compose file are listed too.
+pub async fn list(project: &Project) -> Result<Vec<ContainerInfo>, LauncherError> {
+    project.runtime.inspect(project).await
+}
+
+/// Reads `container inspect` output, sorted like [`list`].
+pub fn from_inspected(inspected: &[Value]) -> Vec<ContainerInfo> {
+    sorted(inspected.iter().filter_map(from_inspect).collect())
+}
+
+/// [`list`] through the runtime's CLI: `ps` by label, then `container inspect`.
+pub async fn inspect_cli<R>(runtime: &R, project: &Project) -> Result<Vec<ContainerInfo>, LauncherError>
+where
+    R: ContainerRuntime + ?Sized,
+{
+    let program = runtime.kind().await.program();
+    // A broken compose file shouldn't hide the containers; the directory still names the project
+    let config = compose::config_json(project).await.unwrap_or(Value::Null);
+    let label = format!("label=com.docker.compose.project={}", compose::project_name(&config, project));
+    let ps = ["ps", "--all", "--no-trunc", "--filter", &label, "--format", "json"];
+    let stdout = stdout_of(runtime.cli(&ps).await.map_err(|e| LauncherError::spawn(program, e))?)?;
+    let listed = parse_ps(&stdout);
+    if listed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids: Vec<String> = listed.iter().filter_map(|entry| text(entry, "/ID")).collect();
+    let mut args = vec!["container", "inspect"];
+    args.extend(ids.iter().map(String::as_str));
+    // Exits non-zero when a container went away in between, still printing the rest
+    let output = runtime.cli(&args).await.map_err(|e| LauncherError::spawn(program, e))?;
+    let inspected: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+
+    Ok(if inspected.is_empty() {
+        sorted(listed.iter().filter_map(from_ps).collect())
+    } else {
+        from_inspected(&inspected)
+    })
+}
+
+fn sorted(mut containers: Vec<ContainerInfo>) -> Vec<ContainerInfo> {
+    containers.sort_by(|a, b| a.service.cmp(&b.service).then_with(|| a.name.cmp(&b.name)));
+    containers
+}
@@ -0,0 +1,95 @@
+use crate::docker;
+use crate::error::LauncherError;
+use crate::runtime::{self, RuntimeKind};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Mutex;
+use tokio::process::Command;
+
+/// A `docker context`, as `list_contexts` returns it.
+#[derive(Clone, Serialize)]
+pub struct DockerContext {
+    pub name: String,
+    pub description: String,
+    /// The daemon it points at, e.g. `unix:///var/run/docker.sock` or `ssh://user@vm`.
+    pub endpoint: String,
+    /// The one the launcher's commands use, the setting or else the CLI's own choice.
+    pub current: bool,
+    /// Why the CLI could not read the context, when it couldn't.
+    pub error: Option<String>,
+}
+
+static SELECTED: Mutex<Option<String>> = Mutex::new(None);
+
+/// Takes effect for every process spawned from here on. None leaves the choice to
+/// the CLI: `DOCKER_CONTEXT`, `DOCKER_HOST` or `docker context use`.
+pub fn configure(context: Option<&str>) {
+    *SELECTED.lock().unwrap() = context.map(str::to_string);
+}
+
+pub fn selected() -> Option<String> {
+    SELECTED.lock().unwrap().clone()
+}
+
+/// Points a docker or compose command at the configured context. Compose reads the
+/// variable as the CLI does, which `--context` would not reach. Podman has no
+/// contexts, and a compose provider it hands off to mustn't be sent to Docker's.
+pub fn apply(command: &mut Command) -> &mut Command {
+    if runtime::current() != RuntimeKind::Docker {
+        return command;
+    }
+    if let Some(context) = selected() {
+        // DOCKER_HOST wins over any context, so it would quietly undo the choice
+        command.env("DOCKER_CONTEXT", context).env_remove("DOCKER_HOST");
+    }
+    command
+}
+
+fn text(entry: &Value, key: &str) -> String {
+    entry.get(key).and_then(Value::as_str).unwrap_or_default().trim().to_string()
+}
+
+/// `context ls --format json` prints one object per line; releases before 23 print
+/// an array instead.
+fn parse(stdout: &str) -> Vec<DockerContext> {
+    let trimmed = stdout.trim();
+    let entries: Vec<Value> = if trimmed.starts_with('[') {
+        serde_json::from_str(trimmed).unwrap_or_default()
+    } else {
+        trimmed.lines().filter_map(|line| serde_json::from_str(line.trim()).ok()).collect()
+    };
+    entries
+        .iter()
+        .map(|entry| DockerContext {
+            name: text(entry, "Name"),
+            description: text(entry, "Description"),
+            endpoint: text(entry, "DockerEndpoint"),
+            current: entry.get("Current").and_then(Value::as_bool).unwrap_or(false),
+            error: Some(text(entry, "Error")).filter(|error| !error.is_empty()),
+        })
+        .filter(|context| !context.name.is_empty())
+        .collect()
+}
+
+pub async fn list() -> Result<Vec<DockerContext>, LauncherError> {
+    let output = docker::command()
+        .args(["context", "ls", "--format", "json"])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| LauncherError::spawn("docker", e))?;
+    if !output.status.success() {
+        return Err(LauncherError::from_failure(output.status.code(), &String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(parse(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The context the launcher's commands end up on.
+pub async fn active() -> Option<String> {
+    if let Some(context) = selected() {
+        return Some(context);
+    }
+    let output = docker::command().args(["context", "show"]).kill_on_drop(true).output().await.ok()?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !name.is_empty()).then_some(name)
+}
@@ -0,0 +1,108 @@
+use crate::messages::MessageKey;
+use crate::{emit_log, open_web_ui, start_services, stop_services, tray};
+use serde_json::json;
+use crate::AppHandle;
+use tauri::Url;
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// Registered in `tauri.conf.json` under `plugins.deep-link`.
+pub const SCHEME: &str = "abel";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    Start,
+    Stop,
+    /// Opens the web UI in the browser.
+    Open,
+    /// Brings the launcher window forward.
+    Show,
+}
+
+impl Action {
+    fn label(self) -> &'static str {
+        match self {
+            Action::Start => "STARTING",
+            Action::Stop => "STOPPING",
+            Action::Open => "OPENING WEB UI",
+            Action::Show => "SHOWING WINDOW",
+        }
+    }
+}
+
+/// `abel://start` puts the action in the host; `abel:start` and `abel:///start` in
+/// the path. A trailing slash or query string is ignored.
+fn parse(url: &Url) -> Option<Action> {
+    if url.scheme() != SCHEME {
+        return None;
+    }
+    let action = url
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .or_else(|| url.path().split('/').find(|segment| !segment.is_empty()))?;
+    match action.to_lowercase().as_str() {
+        "start" => Some(Action::Start),
+        "stop" => Some(Action::Stop),
+        "open" => Some(Action::Open),
+        "show" => Some(Action::Show),
+        _ => None,
+    }
+}
+
+/// Whether a launch argument is a link for this app, which the deep link handler owns.
+pub fn is_link(arg: &str) -> bool {
+    arg.to_lowercase().starts_with(&format!("{}:", SCHEME))
+}
+
+fn dispatch(app: &AppHandle, url: &Url) {
+    let Some(action) = parse(url) else {
+        emit_log(app, "warning", MessageKey::DeepLinkIgnored.with(json!({ "url": url.to_string() })));
+        return;
+    };
+    let params = json!({ "url": url.to_string(), "action": action.label() });
+    emit_log(app, "info", MessageKey::DeepLinkReceived.with(params));
+
+    let app = app.clone();
+    match action {
+        Action::Show => tray::show_main_window(&app),
+        Action::Start => {
+            tauri::async_runtime::spawn(async move {
+                start_services(app, None).await.ok();
+            });
+        }
+        Action::Stop => {
+            tauri::async_runtime::spawn(async move {
+                stop_services(app).await.ok();
+            });
+        }
+        Action::Open => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(error) = open_web_ui(app.clone()).await {
+                    let params = json!({ "error": error.to_string() });
+                    emit_log(&app, "error", MessageKey::DeepLinkWebUiFailed.with(params));
+                }
+            });
+        }
+    }
+}
+
+/// Handles links opened while the launcher runs, including those a second launch
+/// forwards through the single-instance plugin, and the one it was launched with.
+pub fn init(app: &AppHandle) {
+    // Installers register the scheme; this covers dev builds and portable copies
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        crate::logfile::record_internal(app, format!("deep link scheme not registered: {}", e));
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            dispatch(&handle, &url);
+        }
+    });
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            dispatch(app, &url);
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use crate::compose::{self, Project};
+use crate::doctor;
+use crate::env::{self, EnvLine};
+use crate::error::LauncherError;
+use crate::logfile::LogFile;
+use crate::logs::{self, LogHistory};
+use crate::secrets;
+use crate::zip::ZipWriter;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{BufWriter, ErrorKind};
+use std::path::Path;
+use std::sync::OnceLock;
+use crate::AppHandle;
+use tauri::Manager;
+
+/// Lines taken from each container.
+const CONTAINER_LOG_LINES: usize = 500;
+const REDACTED: &str = "[REDACTED]";
+/// Parts of a variable name that mark its value as a secret.
+const SENSITIVE_WORDS: [&str; 7] = ["KEY", "TOKEN", "SECRET", "PASSWORD", "PASSWD", "CREDENTIAL", "PRIVATE"];
+/// Values shorter than this are too likely to occur by chance to be scrubbed
+/// from free text; they are still masked where their key names them.
+const MIN_SCRUBBED_LEN: usize = 4;
+
+/// Result of `export_diagnostics`.
+#[derive(Clone, Serialize)]
+pub struct DiagnosticsBundle {
+    pub path: String,
+    pub size: u64,
+}
+
+pub fn sensitive_key(key: &str) -> bool {
+    let key = key.to_uppercase();
+    SENSITIVE_WORDS.iter().any(|word| key.contains(word))
+}
+
+/// Masks secrets in everything that goes into a bundle: values whose key looks
+/// sensitive, credentials in URLs, and any known secret value wherever it appears.
+pub struct Redactor {
+    values: Vec<String>,
+}
+
+fn url_credentials() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)\b([a-z][a-z0-9+.-]*://[^/\s:@]*:)[^/\s@]+@").unwrap())
+}
+
+/// `KEY=value` as an environment list entry or a log line prints it.
+fn assignment() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#"\b([A-Za-z_][A-Za-z0-9_]*)(\s*[=:]\s*)("[^"]*"|'[^']*'|[^\s"',]+)"#).unwrap())
+}
+
+impl Redactor {
+    pub fn new(values: impl IntoIterator<Item = String>) -> Self {
+        let mut values: Vec<String> = values
+            .into_iter()
+            .map(|value| value.trim().to_string())
+            .filter(|value| value.len() >= MIN_SCRUBBED_LEN)
+            .collect();
+        // Longest first, so a secret containing another is masked whole
+        values.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        values.dedup();
+        Redactor { values }
+    }
+
+    /// Secrets the project itself knows of: sensitive `.env` values and those the
+    /// keyring holds for it.
+    pub fn for_project(project_dir: &str) -> Self {
+        let mut values = Vec::new();
+        for line in env::read(project_dir).unwrap_or_default() {
+            if let EnvLine::Entry { key, value } = line {
+                if sensitive_key(&key) && value != secrets::PLACEHOLDER {
+                    values.push(value.trim_matches(|c| c == '"' || c == '\'').to_string());
+                }
+            }
+        }
+        for key in secrets::placeholders(project_dir).unwrap_or_default() {
+            values.extend(secrets::get(&key).ok());
+        }
+        Redactor::new(values)
+    }
+
+    pub fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for value in &self.values {
+            text = text.replace(value.as_str(), REDACTED);
+        }
+        let text = url_credentials().replace_all(&text, format!("${{1}}{}@", REDACTED));
+        assignment()
+            .replace_all(&text, |captures: &regex::Captures| {
+                if sensitive_key(&captures[1]) {
+                    format!("{}{}{}", &captures[1], &captures[2], REDACTED)
+                } else {
+                    captures[0].to_string()
+                }
+            })
+            .into_owned()
+    }
+
+    /// Masks the value of every sensitive key, at any depth, then the text of what's left.
+    pub fn json(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if sensitive_key(key) && !value.is_object() && !value.is_array() && !value.is_null() {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.json(value);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.json(item)),
+            Value::String(text) => *text = self.text(text),
+            _ => {}
+        }
+    }
+}
+
+/// A command's output for the bundle, with its error in place of it when it failed.
+fn or_error(result: Result<String, LauncherError>) -> String {
+    result.unwrap_or_else(|error| format!("not collected: {}\n", error))
+}
+
+async fn docker_output(args: &[&str]) -> String {
+    let output = crate::docker::command().args(args).kill_on_drop(true).output().await;
+    match output {
+        Ok(output) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stderr.trim().is_empty() {
+                text.push_str("\n--- stderr ---\n");
+                text.push_str(&stderr);
+            }
+            text
+        }
+        Err(e) => format!("not collected: {}\n", LauncherError::spawn("docker", e)),
+    }
+}
+
+fn pretty(value: &impl Serialize) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|e| format!("not collected: {}", e))
+}
+
+/// The live log file as written so far.
+fn launcher_log(app: &AppHandle) -> String {
+    let Some(file) = app.try_state::<LogFile>() else {
+        return "not collected: the log file is not being written\n".to_string();
+    };
+    crate::logfile::shutdown(app);
+    match fs::read(file.path()) {
+        Ok(contents) => String::from_utf8_lossy(&contents).to_string(),
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => format!("not collected: {}\n", e),
+    }
+}
+
+/// Collects every part of the bundle as `(file name, contents)`, redacted.
+async fn collect(app: &AppHandle, project: &Project, min_free_bytes: u64) -> Vec<(String, String)> {
+    let redactor = Redactor::for_project(&project.dir);
+    let mut parts: Vec<(String, String)> = Vec::new();
+
+    parts.push(("launcher.log".to_string(), launcher_log(app)));
+    let mut history = Vec::new();
+    logs::write_json(&app.state::<LogHistory>(), &mut history, None).ok();
+    parts.push(("events.ndjson".to_string(), String::from_utf8_lossy(&history).to_string()));
+
+    parts.push(("docker-info.txt".to_string(), docker_output(&["info"]).await));
+    parts.push(("docker-version.txt".to_string(), docker_output(&["version"]).await));
+
+    let config = match compose::config_json(project).await {
+        Ok(mut config) => {
+            redactor.json(&mut config);
+            pretty(&config)
+        }
+        Err(error) => or_error(Err(error)),
+    };
+    parts.push(("compose-config.json".to_string(), config));
+    parts.push(("compose-ps.txt".to_string(), or_error(compose::compose_output(project, &["ps", "--all"]).await)));
+
+    for service in compose::expected_services(project).await.unwrap_or_default() {
+        let lines = compose::tail_logs(project, &service, CONTAINER_LOG_LINES)
+            .await
+            .map(|lines| lines.join("\n") + "\n");
+        parts.push((format!("containers/{}.log", service), or_error(lines)));
+    }
+
+    let mut report = serde_json::to_value(doctor::run(project, doctor::Scope::Full, min_free_bytes).await)
+        .unwrap_or(Value::Null);
+    redactor.json(&mut report);
+    parts.push(("doctor.json".to_string(), pretty(&report)));
+
+    // JSON parts were walked already; this catches secrets they print as text
+    parts.into_iter().map(|(name, contents)| (name, redactor.text(&contents))).collect()
+}
+
+fn write(path: &Path, parts: &[(String, String)]) -> Result<u64, LauncherError> {
+    let fail = |e: std::io::Error| LauncherError::Io {
+        message: format!("could not write diagnostics to {}: {}", path.display(), e),
+    };
+    // Staged next to the target so a failed write never leaves half a zip under its name
+    let staged = path.with_extension("zip.tmp");
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(&staged).map_err(fail)?));
+    let written = parts
+        .iter()
+        .try_for_each(|(name, contents)| zip.add(name, contents.as_bytes()))
+        .and_then(|()| zip.finish())
+        .and_then(|writer| writer.into_inner().map_err(|e| e.into_error()))
+        .and_then(|file| file.sync_all());
+    if let Err(e) = written {
+        fs::remove_file(&staged).ok();
+        return Err(fail(e));
+    }
+    fs::rename(&staged, path).map_err(fail)?;
+    Ok(fs::metadata(path).map_err(fail)?.len())
+}
+
+/// Builds the bundle at `path`: logs, Docker and compose state, the last lines of
+/// every container and a doctor report, with secrets masked throughout.
+pub async fn export(
+    app: &AppHandle,
+    project: &Project,
+    path: &Path,
+    min_free_bytes: u64,
+) -> Result<DiagnosticsBundle, LauncherError> {
+    let parts = collect(app, project, min_free_bytes).await;
+    let target = path.to_path_buf();
+    let size = tauri::async_runtime::spawn_blocking(move || write(&target, &parts))
+        .await
+        .map_err(|e| LauncherError::TaskFailed { message: e.to_string() })??;
+    Ok(DiagnosticsBundle { path: path.to_string_lossy().to_string(), size })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn project_dir(name: &str, env: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("abel-diagnostics-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(env::ENV_FILE), env).unwrap();
+        dir.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn sensitive_keys() {
+        let secrets = ["SECRET_KEY", "api_token", "DB_PASSWORD", "MYSQL_PASSWD", "AWS_CREDENTIALS", "PRIVATE_PEM"];
+        for key in secrets {
+            assert!(sensitive_key(key), "{}", key);
+        }
+        for key in ["LOG_LEVEL", "OLLAMA_HOST", "PORT", "DEBUG"] {
+            assert!(!sensitive_key(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn env_secrets_are_masked_wherever_they_appear() {
+        let dir = project_dir(
+            "env",
+            "SECRET_KEY=s3cr3t-value-0123456789\nAPI_TOKEN=\"tok-abcdef\"\nDB_PASSWORD=hunter22\n\
+             LOG_LEVEL=debug\nOLLAMA_HOST=http://ollama:11434\n",
+        );
+        let redactor = Redactor::for_project(&dir);
+        let log = "booting with s3cr3t-value-0123456789, token tok-abcdef\nconnecting as hunter22 to ollama";
+        let text = redactor.text(log);
+        assert!(!text.contains("s3cr3t-value-0123456789"));
+        assert!(!text.contains("tok-abcdef"));
+        assert!(!text.contains("hunter22"));
+        assert!(text.contains("to ollama"));
+
+        let env = redactor.text(&fs::read_to_string(Path::new(&dir).join(env::ENV_FILE)).unwrap());
+        assert!(env.contains("SECRET_KEY=[REDACTED]"));
+        assert!(env.contains("LOG_LEVEL=debug"));
+        assert!(env.contains("OLLAMA_HOST=http://ollama:11434"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn assignments_in_log_lines_are_masked_by_key() {
+        let redactor = Redactor::new(Vec::new());
+        let line = "backend | SECRET_KEY=abc GITHUB_TOKEN: ghp_x PASSWORD='p w' API_KEY=\"k\" WORKERS=4";
+        assert_eq!(
+            redactor.text(line),
+            "backend | SECRET_KEY=[REDACTED] GITHUB_TOKEN: [REDACTED] PASSWORD=[REDACTED] API_KEY=[REDACTED] WORKERS=4"
+        );
+    }
+
+    #[test]
+    fn url_credentials_are_masked() {
+        let redactor = Redactor::new(Vec::new());
+        assert_eq!(
+            redactor.text("connecting to postgres://abel:pa55@db:5432/abel"),
+            "connecting to postgres://abel:[REDACTED]@db:5432/abel"
+        );
+    }
+
+    #[test]
+    fn short_values_are_only_masked_by_key() {
+        let redactor = Redactor::new(vec!["ab".to_string(), "longer-secret".to_string()]);
+        assert_eq!(redactor.text("ab cd longer-secret"), "ab cd [REDACTED]");
+    }
+
+    #[test]
+    fn non_secrets_pass_through() {
+        let redactor = Redactor::new(vec!["not-in-here".to_string()]);
+        let text = "INITIATING BOOT SEQUENCE... PORT=8000 http://localhost:8000/health";
+        assert_eq!(redactor.text(text), text);
+    }
+
+    #[test]
+    fn json_masks_sensitive_keys_at_any_depth() {
+        let redactor = Redactor::new(vec!["hunter22".to_string()]);
+        let mut config = json!({
+            "services": { "backend": { "environment": {
+                "SECRET_KEY": "abc",
+                "POSTGRES_PASSWORD": 1234,
+                "LOG_LEVEL": "info",
+                "DATABASE_URL": "postgres://abel:hunter22@db/abel",
+            } } },
+            "notes": ["uses hunter22"],
+        });
+        redactor.json(&mut config);
+        let environment = &config["services"]["backend"]["environment"];
+        assert_eq!(environment["SECRET_KEY"], "[REDACTED]");
+        assert_eq!(environment["POSTGRES_PASSWORD"], "[REDACTED]");
+        assert_eq!(environment["LOG_LEVEL"], "info");
+        assert_eq!(environment["DATABASE_URL"], "postgres://abel:[REDACTED]@db/abel");
+        assert_eq!(config["notes"][0], "uses [REDACTED]");
+    }
+}
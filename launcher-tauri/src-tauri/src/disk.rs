@@ -0,0 +1,208 @@
+use crate::docker;
+use serde::Serialize;
+use std::path::Path;
+use tokio::process::Command;
+
+/// Free and total space of the volume holding `path`.
+#[derive(Clone, Serialize)]
+pub struct DiskSpace {
+    pub path: String,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Space on the volumes a build writes to, as returned by `check_disk_space`.
+#[derive(Clone, Serialize)]
+pub struct DiskReport {
+    /// Docker's data root. None when it isn't a host path, as with Docker Desktop,
+    /// whose data lives inside its VM.
+    pub docker_root: Option<DiskSpace>,
+    pub project: Option<DiskSpace>,
+    pub min_free_bytes: u64,
+}
+
+impl DiskReport {
+    /// Volumes below the minimum. Both paths may sit on the same one.
+    pub fn low(&self) -> Vec<&DiskSpace> {
+        let mut low: Vec<&DiskSpace> = [&self.docker_root, &self.project]
+            .into_iter()
+            .flatten()
+            .filter(|space| space.free_bytes < self.min_free_bytes)
+            .collect();
+        low.dedup_by(|a, b| a.free_bytes == b.free_bytes && a.total_bytes == b.total_bytes);
+        low
+    }
+}
+
+/// `df -Pk` prints a header, then `filesystem 1024-blocks used available capacity mount`.
+/// POSIX mode keeps each filesystem on one line even when its name is long. Names and
+/// mount points may hold spaces, so columns are counted from the capacity percentage.
+#[cfg(unix)]
+fn parse_df(stdout: &str) -> Option<(u64, u64)> {
+    let columns: Vec<&str> = stdout.lines().nth(1)?.split_whitespace().collect();
+    let capacity = columns.iter().position(|column| column.ends_with('%'))?;
+    let total = columns.get(capacity.checked_sub(3)?)?.parse::<u64>().ok()?;
+    let free = columns.get(capacity - 1)?.parse::<u64>().ok()?;
+    Some((free * 1024, total * 1024))
+}
+
+/// Linux and macOS both answer `df -P`; their native calls differ but the output doesn't.
+#[cfg(unix)]
+async fn free_space(path: &Path) -> Option<(u64, u64)> {
+    let output = Command::new("df").arg("-Pk").arg(path).output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Two numbers, `free total`, on one line.
+#[cfg(windows)]
+fn parse_drive_info(stdout: &str) -> Option<(u64, u64)> {
+    let mut numbers = stdout.split_whitespace().map(|n| n.parse::<u64>().ok());
+    Some((numbers.next()??, numbers.next()??))
+}
+
+/// DriveInfo takes any path and resolves its volume, mapped network drives included.
+#[cfg(windows)]
+async fn free_space(path: &Path) -> Option<(u64, u64)> {
+    let path = path.to_string_lossy().replace('\'', "''");
+    let script = format!(
+        "$d = New-Object System.IO.DriveInfo('{}'); \"$($d.AvailableFreeSpace) $($d.TotalSize)\"",
+        path
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_drive_info(&String::from_utf8_lossy(&output.stdout))
+}
+
+pub async fn space(path: &Path) -> Option<DiskSpace> {
+    if !path.exists() {
+        return None;
+    }
+    let (free_bytes, total_bytes) = free_space(path).await?;
+    Some(DiskSpace { path: path.to_string_lossy().to_string(), free_bytes, total_bytes })
+}
+
+/// Docker's data root as the daemon reports it.
+async fn docker_root() -> Option<String> {
+    let output = docker::command()
+        .args(["info", "--format", "{{.DockerRootDir}}"])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !root.is_empty()).then_some(root)
+}
+
+pub async fn check(project_dir: &Path, min_free_bytes: u64) -> DiskReport {
+    let docker_root = match docker_root().await {
+        Some(root) => space(Path::new(&root)).await,
+        None => None,
+    };
+    DiskReport {
+        docker_root,
+        project: space(project_dir).await,
+        min_free_bytes,
+    }
+}
+
+/// `12.3 GB`, for log lines.
+pub fn format_gb(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1e9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1_000_000_000;
+
+    fn volume(path: &str, free_bytes: u64, total_bytes: u64) -> Option<DiskSpace> {
+        Some(DiskSpace { path: path.to_string(), free_bytes, total_bytes })
+    }
+
+    fn low_paths(report: &DiskReport) -> Vec<&str> {
+        report.low().iter().map(|space| space.path.as_str()).collect()
+    }
+
+    #[test]
+    fn volumes_below_the_minimum_are_low() {
+        let report = |docker_free, project_free| DiskReport {
+            docker_root: volume("/var/lib/docker", docker_free, 500 * GB),
+            project: volume("/home/abel/abel", project_free, 250 * GB),
+            min_free_bytes: 20 * GB,
+        };
+        assert!(low_paths(&report(100 * GB, 50 * GB)).is_empty());
+        assert_eq!(low_paths(&report(19 * GB, 50 * GB)), ["/var/lib/docker"]);
+        assert_eq!(low_paths(&report(5 * GB, 3 * GB)), ["/var/lib/docker", "/home/abel/abel"]);
+        // Exactly the minimum is enough
+        assert!(low_paths(&report(20 * GB, 20 * GB)).is_empty());
+    }
+
+    #[test]
+    fn a_shared_volume_is_reported_once() {
+        let report = DiskReport {
+            docker_root: volume("/var/lib/docker", 4 * GB, 500 * GB),
+            project: volume("/home/abel/abel", 4 * GB, 500 * GB),
+            min_free_bytes: 20 * GB,
+        };
+        assert_eq!(low_paths(&report), ["/var/lib/docker"]);
+
+        let desktop = DiskReport { docker_root: None, project: volume("/Users/abel/abel", GB, 500 * GB), ..report };
+        assert_eq!(low_paths(&desktop), ["/Users/abel/abel"]);
+    }
+
+    #[test]
+    fn sizes_are_formatted_in_gigabytes() {
+        assert_eq!(format_gb(12_345_678_901), "12.3 GB");
+        assert_eq!(format_gb(20 * GB), "20.0 GB");
+        assert_eq!(format_gb(49_999_999), "0.0 GB");
+        assert_eq!(format_gb(0), "0.0 GB");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn df_output_is_parsed_by_column() {
+        let linux = "Filesystem     1024-blocks     Used Available Capacity Mounted on\n\
+                     /dev/nvme0n1p2   490691512 98765432 366905680      22% /\n";
+        assert_eq!(parse_df(linux), Some((366_905_680 * 1024, 490_691_512 * 1024)));
+        let macos = "Filesystem     1024-blocks      Used Available Capacity  Mounted on\n\
+                     /dev/disk3s5     971350180 512345678 420000000    55%    /System/Volumes/Data\n";
+        assert_eq!(parse_df(macos), Some((420_000_000 * 1024, 971_350_180 * 1024)));
+        let spaces = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      //abel@nas/My Share 1000 400 600 40% /Volumes/My Share\n";
+        assert_eq!(parse_df(spaces), Some((600 * 1024, 1000 * 1024)));
+
+        assert_eq!(parse_df("Filesystem 1024-blocks Used Available Capacity Mounted on\n"), None);
+        assert_eq!(parse_df("df: /missing: No such file or directory\n"), None);
+        assert_eq!(parse_df("header\n/dev/sda1 - - - 10% /\n"), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn drive_info_output_is_parsed() {
+        assert_eq!(parse_drive_info("366905680000 490691512000\r\n"), Some((366_905_680_000, 490_691_512_000)));
+        assert_eq!(parse_drive_info("366905680000\r\n"), None);
+        assert_eq!(parse_drive_info("Exception calling \"DriveInfo\""), None);
+    }
+
+    #[test]
+    fn the_current_platform_reports_its_volume() {
+        let dir = std::env::temp_dir();
+        let found = tauri::async_runtime::block_on(space(&dir)).expect("the temp dir's volume");
+        assert_eq!(found.path, dir.to_string_lossy());
+        assert!(found.total_bytes > 0);
+        assert!(found.free_bytes <= found.total_bytes);
+
+        let missing = dir.join(format!("abel-disk-missing-{}", std::process::id()));
+        assert!(tauri::async_runtime::block_on(space(&missing)).is_none());
+    }
+}
@@ -0,0 +1,528 @@
+use crate::compose::{self, ComposeInfo};
+use crate::context;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::ErrorKind;
+use crate::emit_output;
+use crate::error::LauncherError;
+use crate::logs::LogSource;
+use crate::proxy;
+use crate::remote;
+use crate::runtime::{self, RuntimeKind};
+use std::process::Output;
+use crate::AppHandle;
+use tokio::process::Command;
+
+/// Why the daemon can or cannot be used.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DaemonState {
+    Reachable,
+    NotInstalled,
+    DaemonDown,
+    PermissionDenied,
+}
+
+/// Whether a boot can be attempted, and if not, what the user has to fix first.
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum DockerStatus {
+    NotInstalled,
+    DaemonStopped,
+    /// The daemon runs but the socket isn't ours, e.g. not in the `docker` group on Linux.
+    PermissionDenied,
+    Ready { version: String },
+}
+
+impl DockerStatus {
+    /// Reads the result of `docker info --format {{.ServerVersion}}`. Docker exits
+    /// non-zero without a server version whenever the daemon half fails, and says
+    /// why on stderr.
+    pub fn from_info_output(success: bool, stdout: &str, stderr: &str) -> Self {
+        let version = stdout.trim();
+        if success && !version.is_empty() {
+            return DockerStatus::Ready { version: version.to_string() };
+        }
+        match classify_daemon_error(stderr) {
+            DaemonState::PermissionDenied => DockerStatus::PermissionDenied,
+            _ => DockerStatus::DaemonStopped,
+        }
+    }
+
+    /// The error a boot on `runtime` is refused with, or nothing when it is ready.
+    pub fn error(&self, runtime: RuntimeKind) -> Option<LauncherError> {
+        match self {
+            DockerStatus::NotInstalled => Some(LauncherError::runtime_missing(runtime)),
+            DockerStatus::DaemonStopped => Some(LauncherError::DaemonUnreachable {
+                message: "the daemon is not running".to_string(),
+            }),
+            DockerStatus::PermissionDenied => Some(LauncherError::PermissionDenied),
+            DockerStatus::Ready { .. } => None,
+        }
+    }
+
+    /// The status when the CLI could not be run at all.
+    fn unspawned(state: DaemonState) -> Self {
+        match state {
+            DaemonState::NotInstalled => DockerStatus::NotInstalled,
+            DaemonState::PermissionDenied => DockerStatus::PermissionDenied,
+            _ => DockerStatus::DaemonStopped,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DockerInfo {
+    pub daemon: DaemonState,
+    pub client_version: Option<String>,
+    pub server_version: Option<String>,
+    pub compose: Option<ComposeInfo>,
+    /// The docker context commands go to; `daemon` says whether it is reachable.
+    pub context: Option<String>,
+    pub os: Option<String>,
+    pub arch: Option<String>,
+    /// Memory available to the daemon, in bytes.
+    pub total_memory: Option<u64>,
+    /// The daemon's own complaint when it is not reachable.
+    pub error: Option<String>,
+}
+
+impl DockerInfo {
+    /// Reads `docker version --format json`. Returns whether the daemon answered;
+    /// when it did not, `daemon` and `error` say why.
+    fn read_version(&mut self, success: bool, stdout: &[u8], stderr: &[u8]) -> bool {
+        let version_json = parse_json(stdout);
+        self.client_version = string_at(&version_json, "/Client/Version");
+        self.server_version = string_at(&version_json, "/Server/Version");
+        self.os = string_at(&version_json, "/Server/Os");
+        self.arch = string_at(&version_json, "/Server/Arch");
+        if success && self.server_version.is_some() {
+            return true;
+        }
+        let stderr = String::from_utf8_lossy(stderr).trim().to_string();
+        self.daemon = classify_daemon_error(&stderr);
+        self.error = Some(stderr).filter(|s| !s.is_empty());
+        false
+    }
+
+    /// Reads `docker info --format json` for what `version` does not tell.
+    fn read_info(&mut self, stdout: &[u8]) {
+        let info_json = parse_json(stdout);
+        self.total_memory = info_json.get("MemTotal").and_then(Value::as_u64);
+        self.os = self.os.take().or_else(|| string_at(&info_json, "/OSType"));
+        self.arch = self.arch.take().or_else(|| string_at(&info_json, "/Architecture"));
+    }
+}
+
+/// Maps docker CLI error output onto the daemon state it indicates.
+pub fn classify_daemon_error(stderr: &str) -> DaemonState {
+    let lower = stderr.to_lowercase();
+    if lower.contains("permission denied") {
+        DaemonState::PermissionDenied
+    } else {
+        DaemonState::DaemonDown
+    }
+}
+
+/// The proxy, context and remote host every docker and compose process is started with.
+pub fn apply_environment(command: &mut Command) -> &mut Command {
+    proxy::apply(command);
+    context::apply(command);
+    remote::apply(command)
+}
+
+/// A CLI command for the container runtime in use, with [`apply_environment`].
+pub fn command() -> Command {
+    let mut command = Command::new(runtime::current().program());
+    apply_environment(&mut command);
+    command
+}
+
+async fn docker(args: &[&str]) -> Result<Output, DaemonState> {
+    command()
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(spawn_state)
+}
+
+/// What a failure to run the CLI says about the installation.
+fn spawn_state(error: std::io::Error) -> DaemonState {
+    match error.kind() {
+        ErrorKind::NotFound => DaemonState::NotInstalled,
+        ErrorKind::PermissionDenied => DaemonState::PermissionDenied,
+        _ => DaemonState::DaemonDown,
+    }
+}
+
+/// A status together with the stderr it was read from.
+pub struct DaemonProbe {
+    pub status: DockerStatus,
+    pub stderr: String,
+}
+
+impl DaemonProbe {
+    /// Reads what `info --format <version template>` answered, or why it didn't run.
+    pub fn read(result: std::io::Result<Output>) -> Self {
+        match result {
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let status = DockerStatus::from_info_output(output.status.success(), &stdout, &stderr);
+                DaemonProbe { status, stderr }
+            }
+            Err(error) => DaemonProbe { status: DockerStatus::unspawned(spawn_state(error)), stderr: String::new() },
+        }
+    }
+}
+
+/// Value of `name` in `reg query` output, whose lines read `    Name    REG_SZ    data`.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn reg_value(output: &str, name: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, rest) = line.trim().split_once("REG_")?;
+        if !key.trim().eq_ignore_ascii_case(name) {
+            return None;
+        }
+        // Skip the type (`SZ`, `EXPAND_SZ`, ...) to get at the data
+        let data = rest.split_once(char::is_whitespace)?.1.trim();
+        (!data.is_empty()).then(|| data.to_string())
+    })
+}
+
+/// Where Docker Desktop may be installed: the installer's uninstall entry first,
+/// then the default location under Program Files.
+#[cfg(windows)]
+async fn desktop_executables() -> Vec<std::path::PathBuf> {
+    use std::path::PathBuf;
+
+    const EXE: &str = "Docker Desktop.exe";
+    let mut paths = Vec::new();
+    let uninstall = Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall\Docker Desktop",
+            "/v",
+            "InstallLocation",
+        ])
+        .output()
+        .await;
+    if let Some(dir) = uninstall
+        .ok()
+        .and_then(|output| reg_value(&String::from_utf8_lossy(&output.stdout), "InstallLocation"))
+    {
+        paths.push(PathBuf::from(dir).join(EXE));
+    }
+    for root in ["ProgramFiles", "ProgramW6432"] {
+        if let Ok(dir) = std::env::var(root) {
+            paths.push(PathBuf::from(dir).join("Docker").join("Docker").join(EXE));
+        }
+    }
+    paths
+}
+
+/// Starts Docker Desktop without waiting for its daemon.
+#[cfg(windows)]
+pub async fn launch_desktop() -> Result<(), LauncherError> {
+    let exe = desktop_executables()
+        .await
+        .into_iter()
+        .find(|path| path.is_file())
+        .ok_or(LauncherError::DockerNotInstalled)?;
+    Command::new(&exe).spawn().map_err(|e| LauncherError::spawn(&exe, e))?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+pub async fn launch_desktop() -> Result<(), LauncherError> {
+    let output = Command::new("open").args(["-a", "Docker"]).output().await?;
+    if !output.status.success() {
+        // `open` fails only when no application by that name is installed
+        return Err(LauncherError::DockerNotInstalled);
+    }
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub async fn launch_desktop() -> Result<(), LauncherError> {
+    Err(LauncherError::NotSupported {
+        message: "Docker Desktop is not launched on Linux; start the docker service instead \
+                  (e.g. `sudo systemctl start docker`)"
+            .to_string(),
+    })
+}
+
+/// Podman runs containers in a VM everywhere but Linux; `machine start` boots it and
+/// can take a minute, so it isn't waited for.
+#[cfg(any(windows, target_os = "macos"))]
+async fn launch_podman_machine() -> Result<(), LauncherError> {
+    Command::new("podman")
+        .args(["machine", "start"])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| LauncherError::spawn("podman", e))?;
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+async fn launch_podman_machine() -> Result<(), LauncherError> {
+    Err(LauncherError::NotSupported {
+        message: "Podman needs no daemon on Linux; `podman info` tells what is wrong".to_string(),
+    })
+}
+
+/// Starts the engine of `runtime` without waiting for it.
+pub async fn launch_engine(runtime: RuntimeKind) -> Result<(), LauncherError> {
+    match runtime {
+        RuntimeKind::Docker => launch_desktop().await,
+        RuntimeKind::Podman => launch_podman_machine().await,
+    }
+}
+
+/// Parses docker's human-readable sizes (`20.5MiB`, `1.2kB`, `0B`) into bytes.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier = match unit.trim().to_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "kb" => 1e3,
+        "mb" => 1e6,
+        "gb" => 1e9,
+        "tb" => 1e12,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+/// Docker prints the JSON it could gather even when the daemon half of the query fails.
+fn parse_json(stdout: &[u8]) -> Value {
+    serde_json::from_slice(stdout).unwrap_or(Value::Null)
+}
+
+fn string_at(value: &Value, pointer: &str) -> Option<String> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+pub async fn get_info() -> DockerInfo {
+    let mut info = DockerInfo {
+        daemon: DaemonState::Reachable,
+        client_version: None,
+        server_version: None,
+        compose: compose::detect().await.ok(),
+        context: context::active().await,
+        os: None,
+        arch: None,
+        total_memory: None,
+        error: None,
+    };
+
+    let version = match docker(&["version", "--format", "json"]).await {
+        Ok(output) => output,
+        Err(state) => {
+            info.daemon = state;
+            return info;
+        }
+    };
+    if !info.read_version(version.status.success(), &version.stdout, &version.stderr) {
+        return info;
+    }
+    if let Ok(output) = docker(&["info", "--format", "json"]).await {
+        info.read_info(&output.stdout);
+    }
+
+    info
+}
+
+/// Local image id for `reference`, if the image is present.
+pub async fn image_id(reference: &str) -> Option<String> {
+    let output = docker(&["image", "inspect", "--format", "{{.Id}}", reference]).await.ok()?;
+    let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !id.is_empty()).then_some(id)
+}
+
+/// `State.StartedAt` of each container that still exists, as RFC 3339 strings.
+pub async fn started_at(container_ids: &[String]) -> Vec<String> {
+    if container_ids.is_empty() {
+        return Vec::new();
+    }
+    let mut args = vec!["container", "inspect", "--format", "{{.State.StartedAt}}"];
+    args.extend(container_ids.iter().map(String::as_str));
+    let Ok(output) = docker(&args).await else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Exit code a container last stopped with, if it still exists.
+pub async fn exit_code(container_id: &str) -> Option<i64> {
+    let output = docker(&["container", "inspect", "--format", "{{.State.ExitCode}}", container_id]).await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Runs a `docker ... prune -f` command, mirroring its output into the console, and
+/// returns the reclaimed bytes it reports.
+pub async fn prune(app: &AppHandle, args: &[&str]) -> Result<u64, LauncherError> {
+    let output = command()
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| LauncherError::spawn("docker", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in stdout.lines().filter(|line| !line.trim().is_empty()) {
+        emit_output(app, LogSource::Docker, None, "info", line.trim());
+    }
+    if !output.status.success() {
+        return Err(LauncherError::from_failure(output.status.code(), &String::from_utf8_lossy(&output.stderr)));
+    }
+
+    Ok(stdout.lines().find_map(parse_reclaimed).unwrap_or(0))
+}
+
+/// `Total reclaimed space: 1.2GB` (images, volumes) or `Total:  1.2GB` (builder).
+fn parse_reclaimed(line: &str) -> Option<u64> {
+    let value = line
+        .trim()
+        .strip_prefix("Total reclaimed space:")
+        .or_else(|| line.trim().strip_prefix("Total:"))?;
+    parse_size(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANNOT_CONNECT: &str =
+        "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?\n";
+    const WINDOWS_STOPPED: &str = "error during connect: \
+        this error may indicate that the docker daemon is not running: \
+        Get \"http://%2F%2F.%2Fpipe%2Fdocker_engine/v1.47/info\": \
+        open //./pipe/docker_engine: The system cannot find the file specified.\n";
+    const SOCKET_DENIED: &str = "permission denied while trying to connect to the Docker daemon socket at \
+        unix:///var/run/docker.sock: Get \"http://%2Fvar%2Frun%2Fdocker.sock/v1.47/info\": \
+        dial unix /var/run/docker.sock: connect: permission denied\n";
+
+    /// `docker version --format json` with the daemon up, then with it unreachable.
+    const VERSION_UP: &str = r#"{"Client":{"Version":"27.3.1","ApiVersion":"1.47","Os":"linux","Arch":"amd64"},
+        "Server":{"Platform":{"Name":"Docker Engine - Community"},
+        "Version":"27.3.1","ApiVersion":"1.47","Os":"linux","Arch":"amd64"}}"#;
+    const VERSION_DOWN: &str = r#"{"Client":{"Version":"27.3.1","ApiVersion":"1.47","Os":"linux","Arch":"amd64"},
+        "Server":null}"#;
+
+    fn empty_info() -> DockerInfo {
+        DockerInfo {
+            daemon: DaemonState::Reachable,
+            client_version: None,
+            server_version: None,
+            compose: None,
+            context: None,
+            os: None,
+            arch: None,
+            total_memory: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn info_output_for_each_status() {
+        let ready = |version: &str| DockerStatus::Ready { version: version.to_string() };
+        assert!(DockerStatus::from_info_output(true, "27.3.1\n", "") == ready("27.3.1"));
+        // Podman's template prints its own version
+        assert!(DockerStatus::from_info_output(true, "5.2.3\n", "") == ready("5.2.3"));
+        let warned = DockerStatus::from_info_output(true, "24.0.7\n", "WARNING: No swap limit support\n");
+        assert!(warned == ready("24.0.7"));
+
+        assert!(DockerStatus::from_info_output(false, "\n", CANNOT_CONNECT) == DockerStatus::DaemonStopped);
+        assert!(DockerStatus::from_info_output(false, "", WINDOWS_STOPPED) == DockerStatus::DaemonStopped);
+        assert!(DockerStatus::from_info_output(false, "\n", SOCKET_DENIED) == DockerStatus::PermissionDenied);
+        // A version without success, or success without one, is not a reachable daemon
+        assert!(DockerStatus::from_info_output(true, "  \n", "") == DockerStatus::DaemonStopped);
+        assert!(DockerStatus::from_info_output(false, "27.3.1\n", CANNOT_CONNECT) == DockerStatus::DaemonStopped);
+    }
+
+    #[test]
+    fn a_cli_that_cannot_run_is_not_installed() {
+        let missing = std::env::temp_dir().join(format!("abel-docker-missing-{}", std::process::id()));
+        let missing = missing.join("docker");
+        let state = spawn_state(std::process::Command::new(missing).output().unwrap_err());
+        assert!(state == DaemonState::NotInstalled);
+        assert!(DockerStatus::unspawned(state) == DockerStatus::NotInstalled);
+        assert!(DockerStatus::unspawned(DaemonState::PermissionDenied) == DockerStatus::PermissionDenied);
+        assert!(DockerStatus::unspawned(spawn_state(ErrorKind::TimedOut.into())) == DockerStatus::DaemonStopped);
+    }
+
+    #[test]
+    fn statuses_refuse_a_boot_unless_ready() {
+        let docker = RuntimeKind::Docker;
+        assert!(DockerStatus::Ready { version: "27.3.1".to_string() }.error(docker).is_none());
+        assert!(matches!(DockerStatus::NotInstalled.error(docker), Some(LauncherError::DockerNotInstalled)));
+        assert!(matches!(
+            DockerStatus::NotInstalled.error(RuntimeKind::Podman),
+            Some(LauncherError::PodmanNotInstalled)
+        ));
+        assert!(matches!(DockerStatus::DaemonStopped.error(docker), Some(LauncherError::DaemonUnreachable { .. })));
+        assert!(matches!(DockerStatus::PermissionDenied.error(docker), Some(LauncherError::PermissionDenied)));
+    }
+
+    #[test]
+    fn version_output_with_the_daemon_up() {
+        let mut info = empty_info();
+        assert!(info.read_version(true, VERSION_UP.as_bytes(), b""));
+        assert!(info.daemon == DaemonState::Reachable);
+        assert_eq!(info.client_version.as_deref(), Some("27.3.1"));
+        assert_eq!(info.server_version.as_deref(), Some("27.3.1"));
+        assert_eq!(info.os.as_deref(), Some("linux"));
+
+        info.read_info(br#"{"MemTotal":16624541696,"OSType":"windows","Architecture":"x86_64"}"#);
+        assert_eq!(info.total_memory, Some(16_624_541_696));
+        // What the server reported wins over `info`
+        assert_eq!(info.os.as_deref(), Some("linux"));
+        assert_eq!(info.arch.as_deref(), Some("amd64"));
+    }
+
+    #[test]
+    fn version_output_with_the_daemon_down() {
+        for (stderr, daemon) in [
+            (CANNOT_CONNECT, DaemonState::DaemonDown),
+            (WINDOWS_STOPPED, DaemonState::DaemonDown),
+            (SOCKET_DENIED, DaemonState::PermissionDenied),
+        ] {
+            let mut info = empty_info();
+            assert!(!info.read_version(false, VERSION_DOWN.as_bytes(), stderr.as_bytes()));
+            assert!(info.daemon == daemon, "{stderr}");
+            assert_eq!(info.client_version.as_deref(), Some("27.3.1"));
+            assert_eq!(info.server_version, None);
+            assert_eq!(info.error.as_deref(), Some(stderr.trim()));
+        }
+
+        // Older clients print nothing parseable without a daemon
+        let mut info = empty_info();
+        assert!(!info.read_version(false, b"", b""));
+        assert!(info.daemon == DaemonState::DaemonDown);
+        assert_eq!(info.client_version, None);
+        assert_eq!(info.error, None);
+    }
+}
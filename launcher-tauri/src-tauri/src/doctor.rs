@@ -0,0 +1,340 @@
+use crate::compose::{self, ComposeFlavor, Project};
+use crate::error::LauncherError;
+use crate::network;
+use crate::runtime::{self, RuntimeKind};
+use crate::{connectivity, disk, docker, gpu, ports, wsl};
+use serde::Serialize;
+use std::future::Future;
+use std::path::Path;
+
+/// Docker releases before this lack features the compose file relies on, like
+/// `deploy` device reservations.
+const MIN_DOCKER_MAJOR: u32 = 20;
+/// Memory the daemon should have for the full stack, models included.
+const RECOMMENDED_MEMORY_BYTES: u64 = 8_000_000_000;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckState {
+    Pass,
+    /// Worth knowing, but a boot can still work. Checks that could not run report this.
+    Warn,
+    Fail,
+}
+
+/// One line of the doctor's report.
+#[derive(Clone, Serialize)]
+pub struct DoctorCheck {
+    pub id: &'static str,
+    pub state: CheckState,
+    pub message: String,
+    /// What to do about a warning or failure.
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn pass(id: &'static str, message: impl Into<String>) -> Self {
+        DoctorCheck { id, state: CheckState::Pass, message: message.into(), hint: None }
+    }
+
+    fn warn(id: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        DoctorCheck { id, state: CheckState::Warn, message: message.into(), hint: Some(hint.into()) }
+    }
+
+    fn fail(id: &'static str, message: impl Into<String>, hint: impl Into<String>) -> Self {
+        DoctorCheck { id, state: CheckState::Fail, message: message.into(), hint: Some(hint.into()) }
+    }
+
+    /// A check that could not run because something it needs failed.
+    fn not_checked(id: &'static str, error: &LauncherError) -> Self {
+        DoctorCheck::warn(id, format!("not checked: {}", error), "fix the failures above and run the doctor again")
+    }
+}
+
+/// Result of `run_doctor`.
+#[derive(Clone, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+    /// No check failed. Warnings don't count against it.
+    pub passed: bool,
+}
+
+/// Which checks to run.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Scope {
+    /// Everything, for `run_doctor`.
+    Full,
+    /// The quick advisory checks a boot logs before it starts. The ones that refuse
+    /// a boot (Docker, the compose file, ports, disk, registries) run in the boot
+    /// itself, against the same functions.
+    Preflight,
+}
+
+fn docker_checks(info: &docker::DockerInfo) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+    if info.daemon == docker::DaemonState::NotInstalled {
+        checks.push(DoctorCheck::fail(
+            "docker_installed",
+            "Docker is not installed or not on PATH",
+            "install Docker Desktop (Windows, macOS) or Docker Engine (Linux)",
+        ));
+        return checks;
+    }
+    checks.push(DoctorCheck::pass(
+        "docker_installed",
+        format!("Docker client {}", info.client_version.as_deref().unwrap_or("found")),
+    ));
+
+    let reason = || info.error.clone().unwrap_or_else(|| "no answer".to_string());
+    match info.daemon {
+        docker::DaemonState::Reachable => checks.push(DoctorCheck::pass("docker_daemon", "the Docker daemon is running")),
+        docker::DaemonState::PermissionDenied => checks.push(DoctorCheck::fail(
+            "docker_daemon",
+            "permission denied talking to the Docker daemon",
+            "add your user to the docker group (`sudo usermod -aG docker $USER`) and log in again",
+        )),
+        _ => checks.push(DoctorCheck::fail(
+            "docker_daemon",
+            format!("the Docker daemon is not reachable: {}", reason()),
+            "start Docker Desktop, or the docker service on Linux (`sudo systemctl start docker`)",
+        )),
+    }
+
+    if let Some(version) = &info.server_version {
+        let major = version.split('.').next().and_then(|major| major.parse::<u32>().ok());
+        checks.push(match major {
+            Some(major) if major < MIN_DOCKER_MAJOR => DoctorCheck::warn(
+                "docker_version",
+                format!("Docker {} is older than {}.x", version, MIN_DOCKER_MAJOR),
+                "update Docker; older releases ignore parts of the compose file",
+            ),
+            _ => DoctorCheck::pass("docker_version", format!("Docker server {}", version)),
+        });
+    }
+    checks
+}
+
+fn memory_check(info: &docker::DockerInfo) -> DoctorCheck {
+    match info.total_memory {
+        Some(total) if total < RECOMMENDED_MEMORY_BYTES => DoctorCheck::warn(
+            "memory",
+            format!(
+                "Docker has {} of memory, {} recommended",
+                disk::format_gb(total),
+                disk::format_gb(RECOMMENDED_MEMORY_BYTES)
+            ),
+            "raise the memory limit in Docker Desktop's resource settings, or close other applications",
+        ),
+        Some(total) => DoctorCheck::pass("memory", format!("Docker has {} of memory", disk::format_gb(total))),
+        None => DoctorCheck::warn(
+            "memory",
+            "Docker did not report its memory",
+            "check that the Docker daemon is running",
+        ),
+    }
+}
+
+async fn compose_check() -> DoctorCheck {
+    match compose::detect().await {
+        Ok(info) if info.flavor == ComposeFlavor::Standalone && info.version.starts_with('1') => DoctorCheck::warn(
+            "compose",
+            format!("docker-compose {} (v1, no longer maintained)", info.version),
+            "install the Docker Compose v2 plugin",
+        ),
+        Ok(info) => {
+            let flavor = match info.flavor {
+                ComposeFlavor::Plugin => "docker compose",
+                ComposeFlavor::Standalone => "docker-compose",
+                ComposeFlavor::PodmanPlugin => "podman compose",
+                ComposeFlavor::PodmanCompose => "podman-compose",
+            };
+            DoctorCheck::pass("compose", format!("{} {}", flavor, info.version))
+        }
+        Err(error) => {
+            let hint = match runtime::current() {
+                RuntimeKind::Docker => "install Docker Compose, or Docker Desktop which includes it",
+                RuntimeKind::Podman => "install podman-compose, or docker-compose for `podman compose` to use",
+            };
+            DoctorCheck::fail("compose", error.to_string(), hint)
+        }
+    }
+}
+
+async fn compose_file_check(project: Project) -> DoctorCheck {
+    match compose::validate(&project).await {
+        Ok(validation) if validation.valid => DoctorCheck::pass("compose_file", "the compose file is valid"),
+        Ok(validation) => {
+            let error = LauncherError::ComposeInvalid { issues: validation.issues };
+            DoctorCheck::fail("compose_file", error.to_string(), "fix the compose file at the location given")
+        }
+        Err(error @ LauncherError::ComposeFileNotFound { .. }) => {
+            DoctorCheck::fail("compose_file", error.to_string(), "choose the A.B.E.L. project directory in the settings")
+        }
+        Err(error) => DoctorCheck::not_checked("compose_file", &error),
+    }
+}
+
+async fn ports_check(project: Project) -> DoctorCheck {
+    match ports::find_conflicts(&project).await {
+        Ok(conflicts) if conflicts.is_empty() => DoctorCheck::pass("ports", "every published port is free"),
+        Ok(conflicts) => DoctorCheck::fail(
+            "ports",
+            LauncherError::PortConflict { conflicts }.to_string(),
+            "stop whatever holds the ports, or change the published ports in the compose file",
+        ),
+        Err(error) => DoctorCheck::not_checked("ports", &error),
+    }
+}
+
+async fn disk_check(project: Project, min_free_bytes: u64) -> DoctorCheck {
+    let report = disk::check(Path::new(&project.dir), min_free_bytes).await;
+    match report.low().first() {
+        Some(low) => DoctorCheck::warn(
+            "disk",
+            LauncherError::LowDiskSpace {
+                path: low.path.clone(),
+                free_bytes: low.free_bytes,
+                required_bytes: min_free_bytes,
+            }
+            .to_string(),
+            "free some space, e.g. by pruning unused images from the launcher",
+        ),
+        None if report.project.is_none() && report.docker_root.is_none() => {
+            DoctorCheck::warn("disk", "free space could not be measured", "check the project directory exists")
+        }
+        None => DoctorCheck::pass("disk", format!("at least {} free", disk::format_gb(min_free_bytes))),
+    }
+}
+
+async fn gpu_check(project: Project) -> DoctorCheck {
+    match gpu::check(&project).await {
+        Ok(check) if check.gpu_services.is_empty() => DoctorCheck::pass("gpu", "no service requests a GPU"),
+        Ok(check) => {
+            let services = check.gpu_services.join(", ");
+            let hint = match check.recommendation {
+                gpu::GpuRecommendation::Ok => return DoctorCheck::pass("gpu", format!("GPU ready for {}", services)),
+                gpu::GpuRecommendation::RunCpuProfile => "enable a CPU-only profile",
+                gpu::GpuRecommendation::InstallToolkit => "install the NVIDIA Container Toolkit and restart Docker",
+            };
+            DoctorCheck::fail("gpu", format!("{} ({})", check.recommendation.describe(), services), hint)
+        }
+        Err(error) => DoctorCheck::not_checked("gpu", &error),
+    }
+}
+
+async fn connectivity_check(project: Project) -> DoctorCheck {
+    match connectivity::unreachable_for_pull(&project).await {
+        Ok(unreachable) if unreachable.is_empty() => {
+            DoctorCheck::pass("connectivity", "every image to pull is present or its registry is reachable")
+        }
+        Ok(unreachable) => {
+            let registries = unreachable.into_iter().map(|check| check.registry).collect();
+            DoctorCheck::fail(
+                "connectivity",
+                LauncherError::RegistryUnreachable { registries }.to_string(),
+                "check the network connection and the proxy settings",
+            )
+        }
+        Err(error) => DoctorCheck::not_checked("connectivity", &error),
+    }
+}
+
+/// Networks only exist while the stack does, so a stopped stack passes unchecked.
+async fn network_check(project: Project) -> DoctorCheck {
+    let running = match compose::service_status(&project).await {
+        Ok(statuses) => statuses.iter().any(|status| status.is_running()),
+        Err(error) => return DoctorCheck::not_checked("network", &error),
+    };
+    if !running {
+        return DoctorCheck::pass("network", "the stack is not running; networks are checked once it is");
+    }
+    match network::diagnose(&project).await {
+        Ok(report) if report.passed => DoctorCheck::pass("network", "networks and published ports answer"),
+        Ok(report) => {
+            let failed: Vec<String> =
+                report.checks.into_iter().filter(|check| !check.passed).map(|check| check.message).collect();
+            DoctorCheck::warn("network", failed.join("; "), "restart the stack to recreate its networks")
+        }
+        Err(error) => DoctorCheck::not_checked("network", &error),
+    }
+}
+
+/// Docker Desktop on Windows runs its engine in WSL 2, which fails in ways `docker
+/// info` only reports as an unreachable daemon.
+async fn wsl_check() -> DoctorCheck {
+    let wsl::WslReport::Checked(check) = wsl::check().await else {
+        return DoctorCheck::pass("wsl", "not Windows; no WSL backend to check");
+    };
+    let hint = match check.remediation.first() {
+        Some(wsl::WslRemediation::EnableVirtualization) => {
+            "enable virtualization (Intel VT-x or AMD-V) in the BIOS/UEFI settings and restart"
+        }
+        Some(wsl::WslRemediation::InstallWsl) => "run `wsl --install` in an administrator terminal and restart",
+        Some(wsl::WslRemediation::UpdateWslKernel) => "run `wsl --update` and restart Docker Desktop",
+        None => {
+            return match &check.docker_distro {
+                Some(distro) => DoctorCheck::pass("wsl", format!("WSL 2 ready, {} is {}", distro.name, distro.state)),
+                None => DoctorCheck::warn(
+                    "wsl",
+                    "WSL is installed but Docker Desktop's distro is missing",
+                    "start Docker Desktop, or enable the WSL 2 based engine in its settings",
+                ),
+            };
+        }
+    };
+    let problem = match check.remediation[0] {
+        wsl::WslRemediation::EnableVirtualization => "virtualization is disabled in the firmware",
+        wsl::WslRemediation::InstallWsl => "WSL is not installed",
+        wsl::WslRemediation::UpdateWslKernel => "the WSL 2 kernel needs an update",
+    };
+    DoctorCheck::fail("wsl", problem, hint)
+}
+
+type Pending = Option<tauri::async_runtime::JoinHandle<DoctorCheck>>;
+
+fn spawn<F>(wanted: bool, check: F) -> Pending
+where
+    F: Future<Output = DoctorCheck> + Send + 'static,
+{
+    wanted.then(|| tauri::async_runtime::spawn(check))
+}
+
+async fn gather(checks: &mut Vec<DoctorCheck>, pending: Pending) {
+    let Some(handle) = pending else {
+        return;
+    };
+    checks.push(match handle.await {
+        Ok(check) => check,
+        Err(e) => DoctorCheck::warn("internal", format!("a check did not finish: {}", e), "report this as a bug"),
+    });
+}
+
+/// Runs the checks of `scope` concurrently and reports them in a fixed order. A
+/// check that errors becomes a result of its own; nothing stops the rest.
+pub async fn run(project: &Project, scope: Scope, min_free_bytes: u64) -> DoctorReport {
+    let full = scope == Scope::Full;
+    let compose = spawn(full, compose_check());
+    let compose_file = spawn(full, compose_file_check(project.clone()));
+    let ports = spawn(full, ports_check(project.clone()));
+    let disk = spawn(full, disk_check(project.clone(), min_free_bytes));
+    let gpu = spawn(true, gpu_check(project.clone()));
+    let connectivity = spawn(full, connectivity_check(project.clone()));
+    let network = spawn(full, network_check(project.clone()));
+    let wsl = spawn(full && cfg!(windows), wsl_check());
+    // Docker's own answer is awaited here, alongside the rest already running
+    let info = docker::get_info().await;
+
+    let mut checks = if full { docker_checks(&info) } else { Vec::new() };
+    gather(&mut checks, compose).await;
+    gather(&mut checks, compose_file).await;
+    gather(&mut checks, ports).await;
+    gather(&mut checks, disk).await;
+    checks.push(memory_check(&info));
+    gather(&mut checks, gpu).await;
+    gather(&mut checks, connectivity).await;
+    gather(&mut checks, network).await;
+    gather(&mut checks, wsl).await;
+
+    let passed = checks.iter().all(|check| check.state != CheckState::Fail);
+    DoctorReport { checks, passed }
+}
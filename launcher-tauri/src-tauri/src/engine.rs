@@ -0,0 +1,188 @@
+use crate::compose::{self, Health, Project, ServiceStatus};
+use crate::containers::{self, ContainerInfo};
+use crate::stats::{self, StatsEvent};
+use crate::{context, docker, remote};
+use bollard::container::{ListContainersOptions, StatsOptions};
+use bollard::models::ContainerSummary;
+use bollard::system::EventsOptions;
+use bollard::Docker;
+use futures_util::future::{join_all, ready};
+use futures_util::{Stream, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// bollard's own default. It bounds a request up to its response, so a stream of
+/// events stays open past it.
+const TIMEOUT_SECS: u64 = 120;
+/// How long a daemon that couldn't be reached is left to the CLI before trying again.
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// `(docker_host, docker_context)` a connection was made for.
+type Target = (Option<String>, Option<String>);
+
+struct Connection {
+    target: Target,
+    /// None when the socket couldn't be reached, so the CLI is used meanwhile.
+    client: Option<Docker>,
+    checked: Instant,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static CONNECTION: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// `engine_api` in the settings. Off sends every read through the CLI, for setups
+/// whose socket the launcher can't open, e.g. behind a wrapper script.
+pub fn configure(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+    reset();
+}
+
+/// Drops the connection after a failed call, so the next one looks up the endpoint
+/// again, e.g. after `docker context use`.
+fn reset() {
+    *CONNECTION.lock().unwrap() = None;
+}
+
+/// The daemon the CLI would talk to, as it resolves `DOCKER_HOST` and contexts.
+async fn endpoint() -> Option<String> {
+    let output = docker::command()
+        .args(["context", "inspect", "--format", "{{.Endpoints.docker.Host}}"])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+    let endpoint = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !endpoint.is_empty()).then_some(endpoint)
+}
+
+fn connect(endpoint: &str) -> Option<Docker> {
+    let version = bollard::API_DEFAULT_VERSION;
+    let connected = match endpoint.split_once("://")?.0 {
+        #[cfg(unix)]
+        "unix" => Docker::connect_with_unix(endpoint, TIMEOUT_SECS, version),
+        #[cfg(windows)]
+        "npipe" => Docker::connect_with_named_pipe(endpoint, TIMEOUT_SECS, version),
+        // TLS needs the certificates the CLI finds on its own, so those stay with it
+        "tcp" | "http" if std::env::var_os("DOCKER_TLS_VERIFY").is_none() => {
+            Docker::connect_with_http(endpoint, TIMEOUT_SECS, version)
+        }
+        // ssh goes through the CLI's connection helper
+        _ => return None,
+    };
+    connected.ok()
+}
+
+/// A client for the Engine API, or None where reads go through the CLI: the setting
+/// is off, or the endpoint is one only the CLI can reach. Only the Docker runtime
+/// asks, so Podman never gets here.
+async fn client() -> Option<Docker> {
+    if !ENABLED.load(Ordering::SeqCst) {
+        return None;
+    }
+    let target = (remote::docker_host(), context::selected());
+    if let Some(connection) = CONNECTION.lock().unwrap().as_ref() {
+        if connection.target == target && (connection.client.is_some() || connection.checked.elapsed() < RETRY_AFTER) {
+            return connection.client.clone();
+        }
+    }
+    let client = match endpoint().await.as_deref().and_then(connect) {
+        Some(client) if client.ping().await.is_ok() => Some(client),
+        _ => None,
+    };
+    *CONNECTION.lock().unwrap() = Some(Connection { target, client: client.clone(), checked: Instant::now() });
+    client
+}
+
+fn project_filter(project_name: &str) -> HashMap<String, Vec<String>> {
+    HashMap::from([("label".to_string(), vec![format!("com.docker.compose.project={}", project_name)])])
+}
+
+async fn project_containers(client: &Docker, project: &Project) -> Option<Vec<ContainerSummary>> {
+    let filters = project_filter(&compose::cached_project_name(project).await);
+    let options = ListContainersOptions { all: true, filters, ..Default::default() };
+    client.list_containers(Some(options)).await.map_err(|_| reset()).ok()
+}
+
+/// The summary has health only in its status text, e.g. `Up 2 minutes (healthy)`
+/// or `Up 5 seconds (health: starting)`.
+fn summary_health(status: &str) -> Option<Health> {
+    let (_, health) = status.rsplit_once('(')?;
+    Health::parse(health.trim_end_matches(')').trim_start_matches("health:").trim())
+}
+
+fn summary_status(container: &ContainerSummary) -> Option<ServiceStatus> {
+    let label = |key: &str| container.labels.as_ref()?.get(key).map(String::as_str);
+    // Left by `compose run`, which `compose ps` doesn't list either
+    if label("com.docker.compose.oneoff").is_some_and(|oneoff| oneoff.eq_ignore_ascii_case("true")) {
+        return None;
+    }
+    Some(ServiceStatus {
+        service: label("com.docker.compose.service")?.to_string(),
+        container_id: container.id.clone().unwrap_or_default(),
+        state: container.state.as_deref().unwrap_or_default().to_lowercase(),
+        health: container.status.as_deref().and_then(summary_health),
+    })
+}
+
+/// What `compose ps --all` lists, or None to ask compose.
+pub async fn service_status(project: &Project) -> Option<Vec<ServiceStatus>> {
+    let client = client().await?;
+    let mut statuses: Vec<ServiceStatus> =
+        project_containers(&client, project).await?.iter().filter_map(summary_status).collect();
+    statuses.sort_by(|a, b| a.service.cmp(&b.service));
+    Some(statuses)
+}
+
+/// `container inspect` of every container of the project, read like the CLI's, or
+/// None to ask the CLI. A container removed in between is left out.
+pub async fn inspect_project(project: &Project) -> Option<Vec<ContainerInfo>> {
+    let client = client().await?;
+    let listed = project_containers(&client, project).await?;
+    let ids = listed.iter().filter_map(|container| container.id.as_deref());
+    let inspections = ids.map(|id| client.inspect_container(id, None));
+    let inspected: Vec<Value> = join_all(inspections)
+        .await
+        .into_iter()
+        .filter_map(|inspected| serde_json::to_value(inspected.ok()?).ok())
+        .collect();
+    Some(containers::from_inspected(&inspected))
+}
+
+/// One stats sample per container, or None to ask the CLI. Each takes the daemon
+/// about a second, as it measures CPU over two readings.
+pub async fn stats(container_ids: &[String]) -> Option<Vec<StatsEvent>> {
+    let client = client().await?;
+    let options = StatsOptions { stream: false, one_shot: false };
+    let samples = container_ids.iter().map(|id| {
+        let mut sample = Box::pin(client.stats(id, Some(options)));
+        async move { sample.next().await }
+    });
+    Some(
+        join_all(samples)
+            .await
+            .into_iter()
+            .filter_map(|sample| stats::from_engine(&serde_json::to_value(sample?.ok()?).ok()?))
+            .collect(),
+    )
+}
+
+/// The project's container events in the shape of `docker events --format '{{json .}}'`,
+/// or None to subscribe through the CLI. The stream ends when the daemon goes away.
+pub async fn events(project: &Project) -> Option<impl Stream<Item = Value> + Send + 'static> {
+    let client = client().await?;
+    let mut filters = project_filter(&compose::cached_project_name(project).await);
+    filters.insert("type".to_string(), vec!["container".to_string()]);
+    let messages = client
+        .events(Some(EventsOptions { filters, ..Default::default() }))
+        .take_while(|message| {
+            if message.is_err() {
+                reset();
+            }
+            ready(message.is_ok())
+        })
+        .filter_map(|message| ready(message.ok().and_then(|message| serde_json::to_value(message).ok())));
+    Some(messages)
+}
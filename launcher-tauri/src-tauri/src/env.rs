@@ -0,0 +1,160 @@
+use crate::error::LauncherError;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+
+pub const ENV_FILE: &str = ".env";
+/// The template a new `.env` is made from.
+pub const EXAMPLE_FILE: &str = ".env.example";
+const BACKUP_FILE: &str = ".env.bak";
+const TEMP_FILE: &str = ".env.tmp";
+
+/// One line of a `.env` file. Lines keep their order so a rewrite only changes
+/// what the user edited.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EnvLine {
+    Entry { key: String, value: String },
+    /// Comments, and anything that isn't an assignment, are kept verbatim.
+    Comment { text: String },
+    Blank,
+}
+
+pub fn valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Reads a double-quoted value, resolving the escapes `quote` writes.
+fn unquote_double(raw: &str) -> String {
+    let mut value = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(other) => value.push(other),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    value
+}
+
+fn parse_value(raw: &str) -> String {
+    let raw = raw.trim_start();
+    if let Some(rest) = raw.strip_prefix('"') {
+        unquote_double(rest)
+    } else if let Some(rest) = raw.strip_prefix('\'') {
+        rest.split('\'').next().unwrap_or_default().to_string()
+    } else {
+        // Unquoted values end at an inline ` #` comment
+        let end = raw.find(" #").unwrap_or(raw.len());
+        raw[..end].trim().to_string()
+    }
+}
+
+pub fn parse(contents: &str) -> Vec<EnvLine> {
+    contents
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                return EnvLine::Blank;
+            }
+            let assignment = trimmed.strip_prefix("export ").unwrap_or(trimmed);
+            match assignment.split_once('=') {
+                Some((key, value)) if !trimmed.starts_with('#') && valid_key(key.trim()) => EnvLine::Entry {
+                    key: key.trim().to_string(),
+                    value: parse_value(value),
+                },
+                _ => EnvLine::Comment { text: line.to_string() },
+            }
+        })
+        .collect()
+}
+
+/// Quotes values that would otherwise be split, truncated or misread. Single quotes
+/// are preferred since compose leaves them uninterpolated, like the value was read.
+fn quote(value: &str) -> String {
+    let needs_quotes = value
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '=' | '"' | '\'' | '#' | '\\'));
+    if !needs_quotes {
+        return value.to_string();
+    }
+    if !value.contains(['\'', '\n']) {
+        return format!("'{}'", value);
+    }
+
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn render(lines: &[EnvLine]) -> Result<String, LauncherError> {
+    let mut contents = String::new();
+    for line in lines {
+        match line {
+            EnvLine::Entry { key, value } => {
+                if !valid_key(key) {
+                    return Err(LauncherError::invalid(format!("invalid variable name: {:?}", key)));
+                }
+                contents.push_str(&format!("{}={}\n", key, quote(value)));
+            }
+            EnvLine::Comment { text } => {
+                if text.contains('\n') {
+                    return Err(LauncherError::invalid("comments must be a single line"));
+                }
+                contents.push_str(text);
+                contents.push('\n');
+            }
+            EnvLine::Blank => contents.push('\n'),
+        }
+    }
+    Ok(contents)
+}
+
+/// The project's `.env`, or nothing when it doesn't exist yet.
+pub fn read(project_dir: &str) -> Result<Vec<EnvLine>, LauncherError> {
+    match fs::read_to_string(Path::new(project_dir).join(ENV_FILE)) {
+        Ok(contents) => Ok(parse(&contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replaces the project's `.env` atomically, keeping the previous version as `.env.bak`.
+pub fn write(project_dir: &str, lines: &[EnvLine]) -> Result<(), LauncherError> {
+    let contents = render(lines)?;
+    let dir = Path::new(project_dir);
+    let path = dir.join(ENV_FILE);
+    let temp = dir.join(TEMP_FILE);
+
+    let mut file = File::create(&temp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+
+    if path.is_file() {
+        fs::copy(&path, dir.join(BACKUP_FILE))?;
+    }
+    fs::rename(&temp, &path).inspect_err(|_| {
+        fs::remove_file(&temp).ok();
+    })?;
+    Ok(())
+}
@@ -0,0 +1,411 @@
+use crate::compose::ComposeIssue;
+use crate::ports::PortConflict;
+use crate::runtime::RuntimeKind;
+use crate::state::Operation;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use std::ffi::OsStr;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+/// Every failure a command can report. Serialized as `{ code, message, ...details }`
+/// so the frontend can branch on `code` and show `message` as-is.
+#[derive(Debug, thiserror::Error)]
+pub enum LauncherError {
+    #[error("Docker is not installed or not on PATH")]
+    DockerNotInstalled,
+    #[error("Docker daemon is not reachable: {message}")]
+    DaemonUnreachable { message: String },
+    #[error("permission denied talking to the Docker daemon")]
+    PermissionDenied,
+    #[error("Docker Compose not found: neither `docker compose` nor `docker-compose` is available")]
+    ComposeMissing,
+    #[error("Podman is not installed or not on PATH")]
+    PodmanNotInstalled,
+    #[error("Podman Compose not found: neither `podman compose` nor `podman-compose` is available")]
+    PodmanComposeMissing,
+    #[error("no compose file (docker-compose.yml, compose.yaml, ...) found in {searched_dir}")]
+    ComposeFileNotFound { searched_dir: String },
+    #[error("compose file is invalid: {}", describe_issues(issues))]
+    ComposeInvalid { issues: Vec<ComposeIssue> },
+    #[error("{}", describe_failure(*exit_code, stderr))]
+    CommandFailed { exit_code: Option<i32>, stderr: String },
+    #[error("no output for {secs} seconds, the command was stopped")]
+    TimedOut { secs: u64 },
+    #[error("ports already in use: {}", describe_conflicts(conflicts))]
+    PortConflict { conflicts: Vec<PortConflict> },
+    #[error("busy: {}", describe_busy(*current_operation))]
+    Busy { current_operation: Operation },
+    #[error("unknown service: {service}")]
+    UnknownService { service: String, known: Vec<String> },
+    #[error("service {service} does not publish a port")]
+    NoPublishedPort { service: String },
+    #[error("service {service} uses a prebuilt image, there is nothing to build")]
+    NothingToBuild { service: String },
+    #[error("service {service} is not running")]
+    ServiceNotRunning { service: String },
+    #[error("{message}")]
+    NotSupported { message: String },
+    #[error("only {} free on {path}, {} required", crate::disk::format_gb(*free_bytes), crate::disk::format_gb(*required_bytes))]
+    LowDiskSpace { path: String, free_bytes: u64, required_bytes: u64 },
+    #[error("cannot reach {} to pull missing images", registries.join(", "))]
+    RegistryUnreachable { registries: Vec<String> },
+    #[error("shortcut {accelerator} could not be registered: {message}")]
+    HotkeyUnavailable { accelerator: String, message: String },
+    #[error("status API could not listen on port {port}: {message}")]
+    StatusApiUnavailable { port: u16, message: String },
+    #[error("the model server answered with an error: {message}")]
+    ModelServer { status: Option<u16>, message: String },
+    #[error("{model} is the active model (OLLAMA_MODEL); switch to another before deleting it")]
+    ActiveModel { model: String },
+    #[error("no boot sequence is in progress")]
+    NotStarting,
+    #[error("could not open {target}: {message}")]
+    OpenFailed { target: String, message: String },
+    #[error("the clipboard could not be written: {message}")]
+    ClipboardUnavailable { message: String },
+    #[error("the file dialog returned no usable path: {message}")]
+    DialogFailed { message: String },
+    #[error("a background task of the command failed: {message}")]
+    TaskFailed { message: String },
+    #[error("{message}")]
+    InvalidInput { message: String },
+    #[error("{message}")]
+    Io { message: String },
+    #[error("{message}")]
+    Other { message: String },
+}
+
+fn describe_failure(exit_code: Option<i32>, stderr: &str) -> String {
+    let code = exit_code
+        .map(|c| format!("exit code {}", c))
+        .unwrap_or_else(|| "terminated by signal".to_string());
+    match stderr.trim() {
+        "" => code,
+        stderr => format!("{} ({})", stderr, code),
+    }
+}
+
+fn describe_issues(issues: &[ComposeIssue]) -> String {
+    match (issues.first(), issues.len()) {
+        (None, _) => "no details".to_string(),
+        (Some(issue), 1) => issue.message.clone(),
+        (Some(issue), count) => format!("{} (and {} more)", issue.message, count - 1),
+    }
+}
+
+fn describe_busy(operation: Operation) -> String {
+    match operation {
+        Operation::Running => "the active stack is running, stop it first".to_string(),
+        operation => format!("{} is already in progress", busy_label(operation)),
+    }
+}
+
+fn busy_label(operation: Operation) -> &'static str {
+    match operation {
+        Operation::Starting => "a boot",
+        Operation::Stopping => "a shutdown",
+        Operation::Updating => "an update",
+        Operation::Idle | Operation::Running => "another operation",
+    }
+}
+
+fn describe_conflicts(conflicts: &[PortConflict]) -> String {
+    conflicts
+        .iter()
+        .map(|conflict| match &conflict.process {
+            Some(process) => format!("{} ({}, held by {})", conflict.port, conflict.service, process),
+            None => format!("{} ({})", conflict.port, conflict.service),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl LauncherError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LauncherError::DockerNotInstalled => "docker_not_installed",
+            LauncherError::DaemonUnreachable { .. } => "daemon_unreachable",
+            LauncherError::PermissionDenied => "permission_denied",
+            LauncherError::ComposeMissing => "compose_missing",
+            LauncherError::PodmanNotInstalled => "podman_not_installed",
+            LauncherError::PodmanComposeMissing => "podman_compose_missing",
+            LauncherError::ComposeFileNotFound { .. } => "compose_file_not_found",
+            LauncherError::ComposeInvalid { .. } => "compose_invalid",
+            LauncherError::CommandFailed { .. } => "command_failed",
+            LauncherError::TimedOut { .. } => "timed_out",
+            LauncherError::PortConflict { .. } => "port_conflict",
+            LauncherError::Busy { .. } => "busy",
+            LauncherError::UnknownService { .. } => "unknown_service",
+            LauncherError::NoPublishedPort { .. } => "no_published_port",
+            LauncherError::ServiceNotRunning { .. } => "service_not_running",
+            LauncherError::NothingToBuild { .. } => "nothing_to_build",
+            LauncherError::NotSupported { .. } => "not_supported",
+            LauncherError::LowDiskSpace { .. } => "low_disk_space",
+            LauncherError::RegistryUnreachable { .. } => "registry_unreachable",
+            LauncherError::HotkeyUnavailable { .. } => "hotkey_unavailable",
+            LauncherError::StatusApiUnavailable { .. } => "status_api_unavailable",
+            LauncherError::ModelServer { .. } => "model_server",
+            LauncherError::ActiveModel { .. } => "active_model",
+            LauncherError::NotStarting => "not_starting",
+            LauncherError::OpenFailed { .. } => "open_failed",
+            LauncherError::ClipboardUnavailable { .. } => "clipboard_unavailable",
+            LauncherError::DialogFailed { .. } => "dialog_failed",
+            LauncherError::TaskFailed { .. } => "task_failed",
+            LauncherError::InvalidInput { .. } => "invalid_input",
+            LauncherError::Io { .. } => "io",
+            LauncherError::Other { .. } => "other",
+        }
+    }
+
+    /// Variant-specific fields, flattened next to `code` and `message`.
+    fn details(&self) -> Value {
+        match self {
+            LauncherError::DockerNotInstalled | LauncherError::ComposeMissing => json!({ "runtime": "docker" }),
+            LauncherError::PodmanNotInstalled | LauncherError::PodmanComposeMissing => json!({ "runtime": "podman" }),
+            LauncherError::DaemonUnreachable { message } => json!({ "stderr": message }),
+            LauncherError::ComposeFileNotFound { searched_dir } => json!({ "searched_dir": searched_dir }),
+            LauncherError::ComposeInvalid { issues } => json!({ "issues": issues }),
+            LauncherError::CommandFailed { exit_code, stderr } => json!({ "exit_code": exit_code, "stderr": stderr }),
+            LauncherError::TimedOut { secs } => json!({ "secs": secs }),
+            LauncherError::PortConflict { conflicts } => json!({ "conflicts": conflicts }),
+            LauncherError::Busy { current_operation } => json!({ "current_operation": current_operation }),
+            LauncherError::UnknownService { service, known } => json!({ "service": service, "known": known }),
+            LauncherError::NoPublishedPort { service }
+            | LauncherError::ServiceNotRunning { service }
+            | LauncherError::NothingToBuild { service } => {
+                json!({ "service": service })
+            }
+            LauncherError::LowDiskSpace { path, free_bytes, required_bytes } => {
+                json!({ "path": path, "free_bytes": free_bytes, "required_bytes": required_bytes })
+            }
+            LauncherError::RegistryUnreachable { registries } => json!({ "registries": registries }),
+            LauncherError::HotkeyUnavailable { accelerator, message } => {
+                json!({ "accelerator": accelerator, "reason": message })
+            }
+            LauncherError::StatusApiUnavailable { port, message } => json!({ "port": port, "reason": message }),
+            LauncherError::ModelServer { status, .. } => json!({ "status": status }),
+            LauncherError::ActiveModel { model } => json!({ "model": model }),
+            LauncherError::OpenFailed { target, message } => json!({ "target": target, "reason": message }),
+            _ => json!({}),
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        LauncherError::InvalidInput { message: message.into() }
+    }
+
+    /// The opener could not hand `target`, a URL or path, to the system.
+    pub fn open_failed(target: &str, error: impl std::fmt::Display) -> Self {
+        LauncherError::OpenFailed { target: target.to_string(), message: error.to_string() }
+    }
+
+    /// The CLI of `runtime` is not installed.
+    pub fn runtime_missing(runtime: RuntimeKind) -> Self {
+        match runtime {
+            RuntimeKind::Docker => LauncherError::DockerNotInstalled,
+            RuntimeKind::Podman => LauncherError::PodmanNotInstalled,
+        }
+    }
+
+    /// No compose implementation for `runtime` is installed.
+    pub fn compose_missing(runtime: RuntimeKind) -> Self {
+        match runtime {
+            RuntimeKind::Docker => LauncherError::ComposeMissing,
+            RuntimeKind::Podman => LauncherError::PodmanComposeMissing,
+        }
+    }
+
+    /// Maps a failed spawn onto what it says about the machine: a missing `docker` or
+    /// `podman` binary means that runtime is absent, a missing `docker-compose` or
+    /// `podman-compose` means its Compose is.
+    pub fn spawn(program: impl AsRef<OsStr>, error: io::Error) -> Self {
+        let program = Path::new(program.as_ref())
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match error.kind() {
+            ErrorKind::NotFound if program == "docker-compose" => LauncherError::ComposeMissing,
+            ErrorKind::NotFound if program == "docker" => LauncherError::DockerNotInstalled,
+            ErrorKind::NotFound if program == "podman-compose" => LauncherError::PodmanComposeMissing,
+            ErrorKind::NotFound if program == "podman" => LauncherError::PodmanNotInstalled,
+            ErrorKind::PermissionDenied => LauncherError::PermissionDenied,
+            _ => error.into(),
+        }
+    }
+
+    /// Classifies a non-zero exit of a docker or compose command by its stderr.
+    pub fn from_failure(exit_code: Option<i32>, stderr: &str) -> Self {
+        let lower = stderr.to_lowercase();
+        if lower.contains("permission denied") && lower.contains("docker") {
+            LauncherError::PermissionDenied
+        } else if lower.contains("cannot connect to the docker daemon")
+            || lower.contains("is the docker daemon running")
+            || lower.contains("error during connect")
+        {
+            LauncherError::DaemonUnreachable { message: stderr.trim().to_string() }
+        } else if lower.contains("is not a docker command") && lower.contains("compose") {
+            LauncherError::ComposeMissing
+        } else {
+            LauncherError::CommandFailed { exit_code, stderr: stderr.trim().to_string() }
+        }
+    }
+}
+
+impl From<io::Error> for LauncherError {
+    fn from(error: io::Error) -> Self {
+        LauncherError::Io { message: error.to_string() }
+    }
+}
+
+impl From<String> for LauncherError {
+    fn from(message: String) -> Self {
+        LauncherError::Other { message }
+    }
+}
+
+impl From<&str> for LauncherError {
+    fn from(message: &str) -> Self {
+        LauncherError::Other { message: message.to_string() }
+    }
+}
+
+impl Serialize for LauncherError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Payload {
+            code: &'static str,
+            message: String,
+            #[serde(flatten)]
+            details: Value,
+        }
+
+        Payload {
+            code: self.code(),
+            message: self.to_string(),
+            details: self.details(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The error spawning `program` actually fails with.
+    fn spawn_error(program: &Path) -> io::Error {
+        std::process::Command::new(program).output().unwrap_err()
+    }
+
+    #[test]
+    fn missing_binaries_map_to_what_is_not_installed() {
+        let missing = std::env::temp_dir().join(format!("abel-error-missing-{}", std::process::id()));
+        let docker = missing.join("docker");
+        assert!(matches!(
+            LauncherError::spawn(&docker, spawn_error(&docker)),
+            LauncherError::DockerNotInstalled
+        ));
+        let compose = missing.join("docker-compose");
+        assert!(matches!(LauncherError::spawn(&compose, spawn_error(&compose)), LauncherError::ComposeMissing));
+        // Windows names carry an extension and any case
+        let not_found = || io::Error::from(ErrorKind::NotFound);
+        assert!(matches!(
+            LauncherError::spawn("Docker.EXE", not_found()),
+            LauncherError::DockerNotInstalled
+        ));
+        assert!(matches!(
+            LauncherError::spawn("docker-compose.exe", not_found()),
+            LauncherError::ComposeMissing
+        ));
+        let podman = missing.join("podman");
+        assert!(matches!(
+            LauncherError::spawn(&podman, spawn_error(&podman)),
+            LauncherError::PodmanNotInstalled
+        ));
+        let compose = missing.join("podman-compose");
+        let error = LauncherError::spawn(&compose, spawn_error(&compose));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "podman_compose_missing",
+                "message": "Podman Compose not found: neither `podman compose` nor `podman-compose` is available",
+                "runtime": "podman",
+            })
+        );
+        let other = missing.join("nerdctl");
+        assert!(matches!(LauncherError::spawn(&other, spawn_error(&other)), LauncherError::Io { .. }));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unexecutable_binaries_are_permission_denied() {
+        let dir = std::env::temp_dir().join(format!("abel-error-denied-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let docker = dir.join("docker");
+        std::fs::write(&docker, "#!/bin/sh\n").unwrap();
+        assert!(matches!(LauncherError::spawn(&docker, spawn_error(&docker)), LauncherError::PermissionDenied));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn failures_are_classified_by_stderr() {
+        let denied = "permission denied while trying to connect to the Docker daemon socket at unix:///run/docker.sock";
+        assert!(matches!(LauncherError::from_failure(Some(1), denied), LauncherError::PermissionDenied));
+        for stderr in [
+            "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?",
+            "error during connect: Get \"http://%2F%2F.%2Fpipe%2Fdocker_engine/v1.24/version\": EOF",
+        ] {
+            match LauncherError::from_failure(Some(1), &format!("{}\n", stderr)) {
+                LauncherError::DaemonUnreachable { message } => assert_eq!(message, stderr),
+                other => panic!("{stderr}: {other:?}"),
+            }
+        }
+        let no_plugin = "docker: 'compose' is not a docker command.\nSee 'docker --help'";
+        assert!(matches!(LauncherError::from_failure(Some(1), no_plugin), LauncherError::ComposeMissing));
+        // Permission problems outside docker are ordinary failures
+        assert!(matches!(
+            LauncherError::from_failure(Some(1), "open ./data: permission denied"),
+            LauncherError::CommandFailed { .. }
+        ));
+    }
+
+    #[test]
+    fn other_exits_keep_their_code_and_stderr() {
+        let error = LauncherError::from_failure(Some(17), "  no such service: web\n");
+        assert!(matches!(
+            &error,
+            LauncherError::CommandFailed { exit_code: Some(17), stderr } if stderr == "no such service: web"
+        ));
+        assert_eq!(error.to_string(), "no such service: web (exit code 17)");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "command_failed",
+                "message": "no such service: web (exit code 17)",
+                "exit_code": 17,
+                "stderr": "no such service: web",
+            })
+        );
+
+        assert_eq!(LauncherError::from_failure(Some(2), "").to_string(), "exit code 2");
+        assert_eq!(LauncherError::from_failure(None, "killed").to_string(), "killed (terminated by signal)");
+    }
+
+    #[test]
+    fn plugin_failures_carry_their_own_code() {
+        let error = LauncherError::open_failed("http://localhost:3000", "no browser installed");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "open_failed",
+                "message": "could not open http://localhost:3000: no browser installed",
+                "target": "http://localhost:3000",
+                "reason": "no browser installed",
+            })
+        );
+        let error = LauncherError::ClipboardUnavailable { message: "no display".to_string() };
+        assert_eq!(error.code(), "clipboard_unavailable");
+        assert_eq!(LauncherError::TaskFailed { message: "panicked".to_string() }.code(), "task_failed");
+    }
+}
@@ -0,0 +1,192 @@
+use crate::compose::{self, Health, Project};
+use crate::logs::LogSource;
+use crate::messages::MessageKey;
+use crate::runtime::ContainerRuntime;
+use crate::state::LauncherState;
+use crate::{emit_log_from, monitor, resolve_project};
+use futures_util::future::ready;
+use futures_util::stream::{BoxStream, StreamExt};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::AppHandle;
+use tauri::Manager;
+use tokio::task::AbortHandle;
+
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A stream that lasted this long was healthy, so the next failure backs off from
+/// the start again.
+const HEALTHY_STREAM: Duration = Duration::from_secs(60);
+
+/// The events subscription. Managed so exit and environment switches can abort its
+/// reader, which ends the stream and the `docker events` child behind it, if any.
+#[derive(Default)]
+pub struct Events {
+    reader: Mutex<Option<AbortHandle>>,
+    stopping: AtomicBool,
+    /// The stream is open, so the status poll can slow down to a reconciliation.
+    live: AtomicBool,
+}
+
+/// What happened to a container, as far as the launcher cares.
+pub enum Lifecycle {
+    Start,
+    Stop,
+    Die { exit_code: Option<i64> },
+    Health(Health),
+}
+
+pub struct ContainerEvent {
+    pub service: String,
+    pub container: String,
+    pub lifecycle: Lifecycle,
+}
+
+/// Docker 25 dropped the old top-level `status` and `id`, so `Action` and `Actor`
+/// are read first.
+fn event_from(event: &Value) -> Option<ContainerEvent> {
+    if event.get("Type").and_then(Value::as_str).is_some_and(|kind| kind != "container") {
+        return None;
+    }
+    let action = event.get("Action").or_else(|| event.get("status")).and_then(Value::as_str)?;
+    let attribute = |key: &str| event.pointer("/Actor/Attributes").and_then(|attributes| attributes.get(key));
+    let text = |key: &str| attribute(key).and_then(Value::as_str).map(str::to_string);
+
+    let lifecycle = match action.split_once(':') {
+        Some(("health_status", health)) => Lifecycle::Health(Health::parse(health.trim())?),
+        _ => match action {
+            "start" => Lifecycle::Start,
+            "stop" => Lifecycle::Stop,
+            "die" => Lifecycle::Die {
+                exit_code: attribute("exitCode").and_then(|code| match code {
+                    Value::String(code) => code.parse().ok(),
+                    code => code.as_i64(),
+                }),
+            },
+            _ => return None,
+        },
+    };
+    Some(ContainerEvent {
+        service: text("com.docker.compose.service")?,
+        container: text("name").unwrap_or_default(),
+        lifecycle,
+    })
+}
+
+pub fn start(app: &AppHandle) {
+    app.manage(Events::default());
+    tauri::async_runtime::spawn(subscribe_loop(app.clone()));
+}
+
+pub fn shutdown(app: &AppHandle) {
+    if let Some(events) = app.try_state::<Events>() {
+        events.stopping.store(true, Ordering::SeqCst);
+        kill(&events);
+    }
+}
+
+/// Starts a new subscription for the project the launcher now points at.
+pub fn resubscribe(app: &AppHandle) {
+    if let Some(events) = app.try_state::<Events>() {
+        kill(&events);
+    }
+}
+
+pub fn is_live(app: &AppHandle) -> bool {
+    app.try_state::<Events>().is_some_and(|events| events.live.load(Ordering::SeqCst))
+}
+
+fn kill(events: &Events) {
+    if let Some(reader) = events.reader.lock().unwrap().as_ref() {
+        reader.abort();
+    }
+}
+
+fn report(app: &AppHandle, event: &ContainerEvent) {
+    // A boot or stop in flight prints the same through compose already
+    if app.state::<Mutex<LauncherState>>().lock().unwrap().is_busy() {
+        return;
+    }
+    let (level, key, exit_code) = match &event.lifecycle {
+        Lifecycle::Start => ("info", MessageKey::ContainerStarted, None),
+        Lifecycle::Stop => ("info", MessageKey::ContainerStopped, None),
+        Lifecycle::Die { exit_code: Some(0) } => ("info", MessageKey::ContainerExited, None),
+        Lifecycle::Die { exit_code: Some(code) } => ("warning", MessageKey::ContainerDiedWithCode, Some(*code)),
+        Lifecycle::Die { exit_code: None } => ("warning", MessageKey::ContainerDied, None),
+        Lifecycle::Health(Health::Healthy) => ("info", MessageKey::ContainerHealthy, None),
+        Lifecycle::Health(Health::Unhealthy) => ("warning", MessageKey::ContainerUnhealthy, None),
+        Lifecycle::Health(Health::Starting) => return,
+    };
+    let message = match exit_code {
+        Some(code) => key.with(json!({ "container": event.container, "code": code })),
+        None => key.with(json!({ "container": event.container })),
+    };
+    emit_log_from(app, LogSource::Docker, Some(&event.service), level, message);
+}
+
+fn handle(app: &AppHandle, event: &ContainerEvent) {
+    report(app, event);
+    // The poll compares containers and reports crashes; now rather than in a few seconds
+    monitor::nudge(app);
+}
+
+/// The project's container events through `docker events --format '{{json .}}'`.
+/// Lines that aren't JSON are skipped.
+pub async fn subscribe_cli<R>(runtime: &R, project: &Project) -> Option<BoxStream<'static, Value>>
+where
+    R: ContainerRuntime + ?Sized,
+{
+    let label = format!("label=com.docker.compose.project={}", compose::cached_project_name(project).await);
+    let args = ["events", "--format", "{{json .}}", "--filter", "type=container", "--filter", &label];
+    let lines = runtime.cli_lines(&args).await.ok()?;
+    Some(lines.filter_map(|line| ready(serde_json::from_str(line.trim()).ok())).boxed())
+}
+
+/// Runs one subscription until its stream ends.
+async fn stream(app: &AppHandle) {
+    let project = resolve_project(app);
+    if let Some(messages) = project.runtime.events(&project).await {
+        read(app, messages).await;
+    }
+}
+
+/// Reads in a task of its own, so `kill` can abort it wherever the stream is waiting.
+async fn read(app: &AppHandle, mut messages: BoxStream<'static, Value>) {
+    let reader_app = app.clone();
+    let reader = tauri::async_runtime::spawn(async move {
+        while let Some(message) = messages.next().await {
+            if let Some(event) = event_from(&message) {
+                handle(&reader_app, &event);
+            }
+        }
+    });
+    let events = app.state::<Events>();
+    *events.reader.lock().unwrap() = Some(reader.inner().abort_handle());
+    // Checked after the reader is stored, so a shutdown in between still reaches it
+    if events.stopping.load(Ordering::SeqCst) {
+        kill(&events);
+    }
+    events.live.store(true, Ordering::SeqCst);
+    reader.await.ok();
+    events.live.store(false, Ordering::SeqCst);
+    events.reader.lock().unwrap().take();
+}
+
+/// Keeps a subscription open, backing off while the daemon is down or restarting.
+async fn subscribe_loop(app: AppHandle) {
+    let mut backoff = FIRST_BACKOFF;
+    loop {
+        let started = Instant::now();
+        stream(&app).await;
+        if app.state::<Events>().stopping.load(Ordering::SeqCst) {
+            return;
+        }
+        if started.elapsed() >= HEALTHY_STREAM {
+            backoff = FIRST_BACKOFF;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
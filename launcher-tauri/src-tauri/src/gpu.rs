@@ -0,0 +1,131 @@
+use crate::compose::{self, Project};
+use crate::docker;
+use crate::error::LauncherError;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::process::Command;
+
+/// What to do before booting a stack that asks for GPUs.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuRecommendation {
+    /// Nothing requests a GPU, or everything a GPU boot needs is in place.
+    Ok,
+    /// No NVIDIA GPU on this machine: boot without the GPU services instead.
+    RunCpuProfile,
+    /// The GPU is there but Docker can't hand it to containers.
+    InstallToolkit,
+}
+
+impl GpuRecommendation {
+    pub fn describe(self) -> &'static str {
+        match self {
+            GpuRecommendation::Ok => "GPU requirements are met",
+            GpuRecommendation::RunCpuProfile => {
+                "the compose file reserves an NVIDIA GPU but none was found - enable a CPU-only profile"
+            }
+            GpuRecommendation::InstallToolkit => {
+                "an NVIDIA GPU is present but Docker has no nvidia runtime - install the NVIDIA Container Toolkit"
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct GpuCheck {
+    /// Services whose definition reserves GPU devices.
+    pub gpu_services: Vec<String>,
+    /// `nvidia-smi` ran successfully on the host.
+    pub nvidia_smi: bool,
+    /// `docker info` lists an nvidia runtime.
+    pub nvidia_runtime: bool,
+    pub recommendation: GpuRecommendation,
+}
+
+/// A device request asks for a GPU when it names the gpu capability or the nvidia driver.
+fn is_gpu_device(device: &Value) -> bool {
+    // The spec makes `capabilities` a flat list, but nested lists are seen in the wild
+    let capability = device.get("capabilities").and_then(Value::as_array).is_some_and(|caps| {
+        caps.iter().any(|cap| match cap {
+            Value::Array(inner) => inner.iter().any(|cap| *cap == "gpu"),
+            cap => *cap == "gpu",
+        })
+    });
+    let driver = device.get("driver").and_then(Value::as_str) == Some("nvidia");
+    capability || driver
+}
+
+/// Services in `config --format json` output that reserve GPUs, through
+/// `deploy.resources.reservations.devices`, `gpus` or `runtime: nvidia`.
+fn gpu_services(config: &Value) -> Vec<String> {
+    let Some(services) = config.get("services").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    services
+        .iter()
+        .filter(|(_, definition)| {
+            let reserved = definition
+                .pointer("/deploy/resources/reservations/devices")
+                .and_then(Value::as_array)
+                .is_some_and(|devices| devices.iter().any(is_gpu_device));
+            let gpus = definition.get("gpus").is_some_and(|gpus| !gpus.is_null());
+            let runtime = definition.get("runtime").and_then(Value::as_str) == Some("nvidia");
+            reserved || gpus || runtime
+        })
+        .map(|(service, _)| service.clone())
+        .collect()
+}
+
+async fn has_nvidia_smi() -> bool {
+    Command::new("nvidia-smi")
+        .arg("-L")
+        .kill_on_drop(true)
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+async fn has_nvidia_runtime() -> bool {
+    let Ok(output) = docker::command()
+        .args(["info", "--format", "{{json .Runtimes}}"])
+        .kill_on_drop(true)
+        .output()
+        .await
+    else {
+        return false;
+    };
+    serde_json::from_slice::<Value>(&output.stdout)
+        .ok()
+        .and_then(|runtimes| runtimes.as_object().map(|runtimes| runtimes.contains_key("nvidia")))
+        .unwrap_or(false)
+}
+
+fn recommend(gpu_services: &[String], nvidia_smi: bool, nvidia_runtime: bool) -> GpuRecommendation {
+    // Docker Desktop's WSL 2 backend passes GPUs through without registering a runtime
+    let runtime_ok = nvidia_runtime || cfg!(windows);
+    if gpu_services.is_empty() || (nvidia_smi && runtime_ok) {
+        GpuRecommendation::Ok
+    } else if !nvidia_smi {
+        GpuRecommendation::RunCpuProfile
+    } else {
+        GpuRecommendation::InstallToolkit
+    }
+}
+
+/// Whether the stack asks for GPUs and whether this machine can provide them.
+/// The host probes are skipped when no service asks.
+pub async fn check(project: &Project) -> Result<GpuCheck, LauncherError> {
+    let config = compose::config_json(project).await?;
+    let gpu_services = gpu_services(&config);
+    let (nvidia_smi, nvidia_runtime) = if gpu_services.is_empty() {
+        (false, false)
+    } else {
+        (has_nvidia_smi().await, has_nvidia_runtime().await)
+    };
+    Ok(GpuCheck {
+        recommendation: recommend(&gpu_services, nvidia_smi, nvidia_runtime),
+        gpu_services,
+        nvidia_smi,
+        nvidia_runtime,
+    })
+}
@@ -0,0 +1,96 @@
+use crate::error::LauncherError;
+use crate::messages::MessageKey;
+use crate::notify::{self, Notice};
+use crate::state::LauncherState;
+use crate::{emit_log, is_running, start_services, stop_services, SettingsStore};
+use serde_json::json;
+use std::sync::Mutex;
+use crate::AppHandle;
+use tauri::Manager;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+/// The toggle shortcut currently registered with the OS, managed as state.
+#[derive(Default)]
+pub struct Hotkey(Mutex<Option<Shortcut>>);
+
+fn parse(accelerator: &str) -> Result<Shortcut, LauncherError> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| LauncherError::invalid(format!("not a valid shortcut: {} ({})", accelerator, e)))
+}
+
+/// Swaps the registered shortcut for `accelerator`, or drops it with None. The old one
+/// stays registered when the new one can't be, e.g. because another app holds it.
+pub fn register(app: &AppHandle, accelerator: Option<&str>) -> Result<(), LauncherError> {
+    let next = accelerator.map(parse).transpose()?;
+    let state = app.state::<Hotkey>();
+    let mut current = state.0.lock().unwrap();
+    if *current == next {
+        return Ok(());
+    }
+
+    let shortcuts = app.global_shortcut();
+    if let Some(previous) = *current {
+        shortcuts.unregister(previous).ok();
+    }
+    if let (Some(shortcut), Some(accelerator)) = (next, accelerator) {
+        if let Err(e) = shortcuts.register(shortcut) {
+            if let Some(previous) = *current {
+                shortcuts.register(previous).ok();
+            }
+            return Err(LauncherError::HotkeyUnavailable {
+                accelerator: accelerator.to_string(),
+                message: e.to_string(),
+            });
+        }
+    }
+    *current = next;
+    Ok(())
+}
+
+/// Registers the saved shortcut at startup. A taken combo is logged, not fatal.
+pub fn init(app: &AppHandle) {
+    let Some(accelerator) = app.state::<SettingsStore>().get().toggle_hotkey else {
+        return;
+    };
+    if let Err(e) = register(app, Some(&accelerator)) {
+        let params = json!({ "error": e.to_string() });
+        emit_log(app, "warning", MessageKey::HotkeyNotRegistered.with(params));
+    }
+}
+
+pub fn shutdown(app: &AppHandle) {
+    if let Some(state) = app.try_state::<Hotkey>() {
+        if state.0.lock().unwrap().take().is_some() {
+            app.global_shortcut().unregister_all().ok();
+        }
+    }
+}
+
+/// Plugin handler: boots a stopped stack and stops a running one.
+pub fn handle(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    if app.state::<Mutex<LauncherState>>().lock().unwrap().is_busy() {
+        emit_log(app, "info", MessageKey::HotkeyIgnored);
+        return;
+    }
+
+    let app = app.clone();
+    let running = is_running(&app);
+    let (title, body) = if running {
+        ("A.B.E.L. stopping", "Hotkey pressed, shutting the stack down.")
+    } else {
+        ("A.B.E.L. starting", "Hotkey pressed, booting the stack.")
+    };
+    emit_log(&app, "info", if running { MessageKey::HotkeyStopping } else { MessageKey::HotkeyStarting });
+    notify::send(&app, Notice::Requested, title, body);
+    tauri::async_runtime::spawn(async move {
+        if running {
+            stop_services(app).await.ok();
+        } else {
+            start_services(app, None).await.ok();
+        }
+    });
+}
@@ -0,0 +1,241 @@
+use crate::emit_log;
+use crate::messages::MessageKey;
+use crate::env::{ENV_FILE, EXAMPLE_FILE};
+use crate::error::LauncherError;
+use crate::zip::crc32;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use crate::AppHandle;
+use tauri::Manager;
+
+/// Where `bundle.resources` puts the project files, under the resource dir.
+const RESOURCE_DIR: &str = "project";
+/// The installed copy, under the app data dir.
+const INSTALL_DIR: &str = "project";
+const MANIFEST_FILE: &str = ".abel-install.json";
+
+/// `(installed as, bundled as)`. `.env` starts out as a copy of the example.
+const PROJECT_FILES: [(&str, &str); 4] = [
+    ("docker-compose.yml", "docker-compose.yml"),
+    (EXAMPLE_FILE, EXAMPLE_FILE),
+    ("docker/init-db.sql", "docker/init-db.sql"),
+    (ENV_FILE, EXAMPLE_FILE),
+];
+
+/// What the last install wrote, so an upgrade can tell the user's edits from a
+/// copy that is merely out of date.
+#[derive(Default, Serialize, Deserialize)]
+struct Manifest {
+    launcher_version: String,
+    /// CRC-32 of each file as it was written.
+    files: BTreeMap<String, u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAction {
+    Installed,
+    Upgraded,
+    Unchanged,
+    /// Edited since it was installed, so left alone; `force` replaces it.
+    KeptModified,
+}
+
+#[derive(Clone, Serialize)]
+pub struct InstalledFile {
+    pub path: String,
+    pub action: FileAction,
+    /// `+ line` and `- line` for an upgrade. Never filled for `.env`, whose values
+    /// are secrets.
+    pub changes: Vec<String>,
+    /// Where a modified file went before `force` replaced it.
+    pub backup: Option<String>,
+}
+
+/// Result of `install_project_files`.
+#[derive(Clone, Serialize)]
+pub struct InstallReport {
+    pub dir: String,
+    pub version: String,
+    pub previous_version: Option<String>,
+    pub files: Vec<InstalledFile>,
+}
+
+/// The default project directory of a standalone install.
+pub fn install_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(INSTALL_DIR))
+}
+
+fn read_manifest(dir: &Path) -> Option<Manifest> {
+    let contents = fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn read_optional(path: &Path) -> Result<Option<Vec<u8>>, LauncherError> {
+    match fs::read(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(LauncherError::Io { message: format!("could not read {}: {}", path.display(), e) }),
+    }
+}
+
+/// Written beside the target and renamed over it, so a crash never leaves half a file.
+fn write_file(path: &Path, contents: &[u8]) -> Result<(), LauncherError> {
+    let fail = |e: std::io::Error| LauncherError::Io { message: format!("could not write {}: {}", path.display(), e) };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(fail)?;
+    }
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!("{}.install.tmp", name));
+    fs::write(&temp, contents).map_err(fail)?;
+    fs::rename(&temp, path).map_err(|e| {
+        fs::remove_file(&temp).ok();
+        fail(e)
+    })
+}
+
+/// A line diff through the longest common subsequence; compose files are short
+/// enough for the quadratic table.
+fn diff(old: &str, new: &str) -> Vec<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || common[i][j + 1] >= common[i + 1][j]) {
+            changes.push(format!("+ {}", new[j]));
+            j += 1;
+        } else {
+            changes.push(format!("- {}", old[i]));
+            i += 1;
+        }
+    }
+    changes
+}
+
+/// Copies the bundled project files into [`install_dir`]. A file is only
+/// replaced when it is missing, or unchanged since the launcher last wrote it,
+/// unless `force` is set; a modified file is then kept as `<name>.bak`.
+pub fn install(app: &AppHandle, force: bool) -> Result<InstallReport, LauncherError> {
+    let bundled = app
+        .path()
+        .resource_dir()
+        .map_err(|e| LauncherError::Io { message: format!("no resource directory: {}", e) })?
+        .join(RESOURCE_DIR);
+    let dir = install_dir(app).ok_or_else(|| LauncherError::Io { message: "no app data directory".to_string() })?;
+    let manifest = read_manifest(&dir);
+    let version = app.package_info().version.to_string();
+    let mut next = Manifest { launcher_version: version.clone(), files: BTreeMap::new() };
+    let mut files = Vec::new();
+
+    for (target, source) in PROJECT_FILES {
+        let source_path = bundled.join(source);
+        let contents = read_optional(&source_path)?.ok_or_else(|| LauncherError::Io {
+            message: format!("bundled project file missing: {}", source_path.display()),
+        })?;
+        let path = dir.join(target);
+        let current = read_optional(&path)?;
+        let recorded = manifest.as_ref().and_then(|manifest| manifest.files.get(target)).copied();
+
+        let (action, backup) = match &current {
+            None => (FileAction::Installed, None),
+            Some(current) if *current == contents => (FileAction::Unchanged, None),
+            Some(current) if recorded == Some(crc32(current)) => (FileAction::Upgraded, None),
+            Some(_) if force => {
+                let backup = path.with_file_name(format!("{}.bak", target.rsplit('/').next().unwrap_or(target)));
+                fs::copy(&path, &backup).map_err(|e| LauncherError::Io {
+                    message: format!("could not back up {}: {}", path.display(), e),
+                })?;
+                (FileAction::Upgraded, Some(backup.to_string_lossy().to_string()))
+            }
+            Some(_) => (FileAction::KeptModified, None),
+        };
+
+        let changes = match (&current, action) {
+            (Some(current), FileAction::Upgraded) if target != ENV_FILE => {
+                diff(&String::from_utf8_lossy(current), &String::from_utf8_lossy(&contents))
+            }
+            _ => Vec::new(),
+        };
+        match action {
+            FileAction::Installed | FileAction::Upgraded => {
+                write_file(&path, &contents)?;
+                next.files.insert(target.to_string(), crc32(&contents));
+            }
+            FileAction::Unchanged => {
+                next.files.insert(target.to_string(), crc32(&contents));
+            }
+            // The old checksum stays, so the file still reads as edited next time
+            FileAction::KeptModified => {
+                if let Some(recorded) = recorded {
+                    next.files.insert(target.to_string(), recorded);
+                }
+            }
+        }
+        files.push(InstalledFile { path: target.to_string(), action, changes, backup });
+    }
+
+    let manifest_json = serde_json::to_string_pretty(&next).map_err(|e| e.to_string())?;
+    write_file(&dir.join(MANIFEST_FILE), manifest_json.as_bytes())?;
+    Ok(InstallReport {
+        dir: dir.to_string_lossy().to_string(),
+        version,
+        previous_version: manifest.map(|manifest| manifest.launcher_version),
+        files,
+    })
+}
+
+/// Logs what an install did, with the diff of every upgraded file.
+pub fn log_report(app: &AppHandle, report: &InstallReport) {
+    for file in &report.files {
+        match file.action {
+            FileAction::Installed => {
+                emit_log(app, "info", MessageKey::ProjectFileInstalled.with(json!({ "path": file.path })));
+            }
+            FileAction::Upgraded => {
+                let params = json!({ "path": file.path, "from": report.previous_version, "to": report.version });
+                emit_log(app, "info", MessageKey::ProjectFileUpgraded.with(params));
+                for change in &file.changes {
+                    emit_log(app, "info", MessageKey::ProjectFileChange.with(json!({ "change": change })));
+                }
+                if let Some(backup) = &file.backup {
+                    let params = json!({ "path": file.path, "backup": backup });
+                    emit_log(app, "warning", MessageKey::ProjectFileBackedUp.with(params));
+                }
+            }
+            FileAction::KeptModified if report.previous_version.as_deref() != Some(report.version.as_str()) => {
+                let params = json!({ "path": file.path, "version": report.version });
+                emit_log(app, "warning", MessageKey::ProjectFileKept.with(params));
+            }
+            FileAction::KeptModified | FileAction::Unchanged => {}
+        }
+    }
+}
+
+/// First run installs the files; later runs upgrade them after a launcher update.
+pub fn on_startup(app: &AppHandle) {
+    match install(app, false) {
+        Ok(report) => log_report(app, &report),
+        Err(error) => {
+            emit_log(app, "warning", MessageKey::ProjectFilesNotInstalled.with(json!({ "error": error.to_string() })));
+        }
+    }
+}
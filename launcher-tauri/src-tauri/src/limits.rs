@@ -0,0 +1,188 @@
+use crate::compose::{self, ComposeFlavor};
+use crate::error::LauncherError;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Owned by the launcher and passed as the last `-f`, after the user's own files.
+/// Written as JSON, which compose reads as YAML, so no YAML writer is needed.
+pub const OVERRIDE_FILE: &str = "docker-compose.launcher-override.yml";
+
+/// Caps for one service. None leaves a resource unlimited.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// Fractional cores, e.g. 1.5.
+    pub cpus: Option<f64>,
+    /// A compose byte value, e.g. `512m` or `2g`.
+    pub memory: Option<String>,
+}
+
+impl ResourceLimits {
+    pub fn validate(&self) -> Result<(), LauncherError> {
+        static MEMORY: OnceLock<Regex> = OnceLock::new();
+        let memory = MEMORY.get_or_init(|| Regex::new(r"(?i)^\d+(\.\d+)?\s*[bkmg]?$").unwrap());
+        if let Some(cpus) = self.cpus.filter(|cpus| !cpus.is_finite() || *cpus <= 0.0) {
+            return Err(LauncherError::invalid(format!("cpus must be above 0, got {}", cpus)));
+        }
+        if let Some(value) = self.memory.as_deref().filter(|value| !memory.is_match(value.trim())) {
+            return Err(LauncherError::invalid(format!("not a memory size like 512m or 2g: {}", value)));
+        }
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cpus.is_none() && self.memory.is_none()
+    }
+}
+
+pub fn override_path(project_dir: &str) -> PathBuf {
+    Path::new(project_dir).join(OVERRIDE_FILE)
+}
+
+fn read(project_dir: &str) -> Result<Map<String, Value>, LauncherError> {
+    let contents = match fs::read_to_string(override_path(project_dir)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(e.into()),
+    };
+    match serde_json::from_str(&contents) {
+        Ok(Value::Object(document)) => Ok(document),
+        _ => Err(LauncherError::invalid(format!(
+            "{} was edited into something other than JSON; clear the limits to start over",
+            OVERRIDE_FILE
+        ))),
+    }
+}
+
+fn write(project_dir: &str, document: &Map<String, Value>) -> Result<(), LauncherError> {
+    let path = override_path(project_dir);
+    let services = document.get("services").and_then(Value::as_object);
+    if services.is_none_or(|services| services.is_empty()) {
+        return clear(project_dir);
+    }
+    let contents = serde_json::to_string_pretty(document).map_err(|e| e.to_string())?;
+    let staged = path.with_extension("yml.tmp");
+    fs::write(&staged, contents)?;
+    Ok(fs::rename(&staged, &path)?)
+}
+
+/// Limits as either layout states them: `deploy.resources.limits`, or the
+/// `cpus` and `mem_limit` keys compose v1 reads.
+fn limits_of(service: &Value) -> ResourceLimits {
+    let deploy = service.pointer("/deploy/resources/limits");
+    let field = |deploy_key: &str, legacy_key: &str| {
+        deploy
+            .and_then(|limits| limits.get(deploy_key))
+            .or_else(|| service.get(legacy_key))
+            .cloned()
+    };
+    ResourceLimits {
+        cpus: field("cpus", "cpus").and_then(|cpus| match cpus {
+            Value::String(cpus) => cpus.parse().ok(),
+            cpus => cpus.as_f64(),
+        }),
+        memory: field("memory", "mem_limit").and_then(|memory| match memory {
+            Value::String(memory) => Some(memory),
+            memory => memory.as_u64().map(|bytes| bytes.to_string()),
+        }),
+    }
+}
+
+/// Drops both layouts from a service's entry, and `deploy` keys left empty by it.
+fn remove_limits(service: &mut Map<String, Value>) {
+    service.remove("cpus");
+    service.remove("mem_limit");
+    if let Some(deploy) = service.get_mut("deploy").and_then(Value::as_object_mut) {
+        if let Some(resources) = deploy.get_mut("resources").and_then(Value::as_object_mut) {
+            resources.remove("limits");
+            if resources.is_empty() {
+                deploy.remove("resources");
+            }
+        }
+        if deploy.is_empty() {
+            service.remove("deploy");
+        }
+    }
+}
+
+/// Compose v1 only honors `deploy` limits under `--compatibility`, so it gets the
+/// keys it reads on its own.
+async fn legacy_layout() -> bool {
+    compose::detect()
+        .await
+        .is_ok_and(|info| info.flavor == ComposeFlavor::Standalone && info.version.starts_with('1'))
+}
+
+pub fn get(project_dir: &str) -> Result<BTreeMap<String, ResourceLimits>, LauncherError> {
+    let document = read(project_dir)?;
+    let Some(services) = document.get("services").and_then(Value::as_object) else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(services
+        .iter()
+        .map(|(name, service)| (name.clone(), limits_of(service)))
+        .filter(|(_, limits)| !limits.is_empty())
+        .collect())
+}
+
+/// Sets `service`'s limits in the override, or removes them when `limits` is empty.
+/// Other services, and other keys of this one, are kept as they are.
+pub async fn set(project_dir: &str, service: &str, limits: &ResourceLimits) -> Result<(), LauncherError> {
+    limits.validate()?;
+    let mut document = read(project_dir)?;
+    let services = document
+        .entry("services")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or_else(|| LauncherError::invalid(format!("services in {} is not a map", OVERRIDE_FILE)))?;
+    let entry = services
+        .entry(service)
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or_else(|| LauncherError::invalid(format!("{} in {} is not a map", service, OVERRIDE_FILE)))?;
+
+    remove_limits(entry);
+    let cpus = limits.cpus.map(|cpus| cpus.to_string());
+    let memory = limits.memory.as_ref().map(|memory| memory.trim().to_lowercase());
+    if legacy_layout().await {
+        if let Some(cpus) = cpus {
+            entry.insert("cpus".to_string(), cpus.into());
+        }
+        if let Some(memory) = memory {
+            entry.insert("mem_limit".to_string(), memory.into());
+        }
+    } else if !limits.is_empty() {
+        let mut caps = Map::new();
+        if let Some(cpus) = cpus {
+            caps.insert("cpus".to_string(), cpus.into());
+        }
+        if let Some(memory) = memory {
+            caps.insert("memory".to_string(), memory.into());
+        }
+        let deploy = entry.entry("deploy").or_insert_with(|| json!({}));
+        if let Some(deploy) = deploy.as_object_mut() {
+            let resources = deploy.entry("resources").or_insert_with(|| json!({}));
+            if let Some(resources) = resources.as_object_mut() {
+                resources.insert("limits".to_string(), Value::Object(caps));
+            }
+        }
+    }
+    if entry.is_empty() {
+        services.remove(service);
+    }
+    write(project_dir, &document)
+}
+
+/// Removes the override. Nothing to remove is not an error.
+pub fn clear(project_dir: &str) -> Result<(), LauncherError> {
+    match fs::remove_file(override_path(project_dir)) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,255 @@
+use crate::error::LauncherError;
+use crate::logs::{LogLevel, LogSearch, LogSource};
+use crate::{format_timestamp, LogEvent, SettingsStore};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::AppHandle;
+use tauri::Manager;
+
+const LOG_FILE: &str = "launcher.log";
+/// Rotated files kept next to the live one, as `launcher.1.log` (newest) and up.
+const KEPT_ARCHIVES: usize = 3;
+/// Lines waiting for the writer. When it falls this far behind, new lines are
+/// counted and dropped rather than stalling whoever logged them.
+const QUEUE_CAPACITY: usize = 4096;
+/// How long quitting waits for the writer to drain and flush.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+enum Message {
+    Line(String),
+    Flush(mpsc::Sender<()>),
+}
+
+/// Handle to the writer thread mirroring the console into the app log directory.
+pub struct LogFile {
+    path: PathBuf,
+    tx: SyncSender<Message>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl LogFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn send(&self, line: String) {
+        match self.tx.try_send(Message::Line(line)) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            // The writer gave up on the file; the console still has everything
+            Err(TrySendError::Disconnected(_)) => {}
+        }
+    }
+}
+
+fn archive_path(path: &Path, index: usize) -> PathBuf {
+    path.with_file_name(format!("launcher.{}.log", index))
+}
+
+/// Shifts `launcher.log` to `launcher.1.log`, `.1` to `.2` and so on, dropping the oldest.
+fn rotate(path: &Path) {
+    fs::remove_file(archive_path(path, KEPT_ARCHIVES)).ok();
+    for index in (1..KEPT_ARCHIVES).rev() {
+        fs::rename(archive_path(path, index), archive_path(path, index + 1)).ok();
+    }
+    fs::rename(path, archive_path(path, 1)).ok();
+}
+
+fn open(path: &Path) -> std::io::Result<(BufWriter<File>, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok((BufWriter::new(file), size))
+}
+
+fn write_loop(path: PathBuf, max_bytes: u64, rx: Receiver<Message>, dropped: Arc<AtomicU64>) {
+    let Ok((mut writer, mut size)) = open(&path) else {
+        return;
+    };
+    // Blocks for the first message, then drains whatever queued up behind it before flushing
+    while let Ok(first) = rx.recv() {
+        for message in std::iter::once(first).chain(rx.try_iter()) {
+            let line = match message {
+                Message::Line(line) => line,
+                Message::Flush(done) => {
+                    writer.flush().ok();
+                    done.send(()).ok();
+                    continue;
+                }
+            };
+
+            let lost = dropped.swap(0, Ordering::Relaxed);
+            let line = if lost > 0 {
+                format!("{} lines dropped: the log file fell behind\n{}", lost, line)
+            } else {
+                line
+            };
+            if size + line.len() as u64 > max_bytes && size > 0 {
+                writer.flush().ok();
+                drop(writer);
+                rotate(&path);
+                let Ok(reopened) = open(&path) else {
+                    return;
+                };
+                (writer, size) = reopened;
+            }
+            if writer.write_all(line.as_bytes()).is_err() {
+                return;
+            }
+            size += line.len() as u64;
+        }
+        writer.flush().ok();
+    }
+}
+
+/// Starts the writer. Without an app log directory nothing is mirrored.
+pub fn start(app: &AppHandle) {
+    let Ok(dir) = app.path().app_log_dir() else {
+        return;
+    };
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+    let path = dir.join(LOG_FILE);
+    let max_bytes = app.state::<SettingsStore>().get().log_file_max_kb.max(64) * 1024;
+
+    let (tx, rx) = mpsc::sync_channel(QUEUE_CAPACITY);
+    let dropped = Arc::new(AtomicU64::new(0));
+    let writer_path = path.clone();
+    let writer_dropped = dropped.clone();
+    let spawned = std::thread::Builder::new()
+        .name("log-file".to_string())
+        .spawn(move || write_loop(writer_path, max_bytes, rx, writer_dropped));
+    if spawned.is_ok() {
+        app.manage(LogFile { path, tx, dropped });
+    }
+}
+
+fn source_name(source: LogSource) -> &'static str {
+    match source {
+        LogSource::Launcher => "launcher",
+        LogSource::Compose => "compose",
+        LogSource::Docker => "docker",
+        LogSource::Container => "container",
+    }
+}
+
+fn source_of(name: &str) -> Option<LogSource> {
+    match name {
+        "launcher" => Some(LogSource::Launcher),
+        "compose" => Some(LogSource::Compose),
+        "docker" => Some(LogSource::Docker),
+        "container" => Some(LogSource::Container),
+        _ => None,
+    }
+}
+
+/// Splits off the first space-separated token, skipping the padding before it.
+fn token(line: &str) -> Option<(&str, &str)> {
+    line.trim_start().split_once(' ')
+}
+
+/// Level, source and message of a line as `record` and `record_internal` write it.
+fn parse_line(line: &str) -> Option<(LogLevel, LogSource, &str)> {
+    let (_timestamp, mut rest) = token(line)?;
+    // Archives from before RFC3339 timestamps have the time of day as a field of its own
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        rest = token(rest)?.1;
+    }
+    let (level, rest) = token(rest)?;
+    let (origin, message) = token(rest)?;
+    let origin = origin.strip_prefix('[')?.strip_suffix(']')?;
+    let source = source_of(origin.split('/').next().unwrap_or(origin))?;
+    Some((LogLevel::of(&level.to_lowercase()), source, message))
+}
+
+/// A line of the log file, or one of its archives, that matched a search.
+#[derive(Clone, Serialize)]
+pub struct FileMatch {
+    pub file: String,
+    /// 1-based.
+    pub line: usize,
+    pub text: String,
+}
+
+/// The last `limit` matching lines across the archives and the live file, oldest
+/// first. Lines of another shape, like the dropped-lines notes, never match.
+pub fn search(app: &AppHandle, search: &LogSearch, limit: usize) -> Result<Vec<FileMatch>, LauncherError> {
+    let Some(file) = app.try_state::<LogFile>() else {
+        return Ok(Vec::new());
+    };
+    let live = file.path().to_path_buf();
+    let files = (1..=KEPT_ARCHIVES).rev().map(|index| archive_path(&live, index)).chain([live.clone()]);
+
+    let mut matches = VecDeque::new();
+    for path in files {
+        let reader = match File::open(&path) {
+            Ok(reader) => BufReader::new(reader),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        // Lossy, so one bad byte doesn't end the search of a file
+        for (number, line) in reader.split(b'\n').enumerate() {
+            let line = line?;
+            let line = String::from_utf8_lossy(&line);
+            let text = line.trim_end();
+            let Some((level, source, message)) = parse_line(text) else {
+                continue;
+            };
+            if search.matches(level, source, message) {
+                if matches.len() == limit {
+                    matches.pop_front();
+                }
+                matches.push_back(FileMatch { file: name.clone(), line: number + 1, text: text.to_string() });
+            }
+        }
+    }
+    Ok(matches.into())
+}
+
+/// Queues `event` for the file, whatever the console filter makes of it.
+pub fn record(app: &AppHandle, event: &LogEvent) {
+    let Some(file) = app.try_state::<LogFile>() else {
+        return;
+    };
+    let origin = match &event.service {
+        Some(service) => format!("{}/{}", source_name(event.source), service),
+        None => source_name(event.source).to_string(),
+    };
+    file.send(format!(
+        "{} {:<7} [{}] {}\n",
+        format_timestamp(&event.timestamp),
+        event.level.to_uppercase(),
+        origin,
+        event.message
+    ));
+}
+
+/// Records a failure inside the launcher that has no console event of its own,
+/// such as an emit that did not reach the webview.
+pub fn record_internal(app: &AppHandle, message: impl std::fmt::Display) {
+    if let Some(file) = app.try_state::<LogFile>() {
+        let now = format_timestamp(&chrono::Local::now());
+        file.send(format!("{} INTERNAL [launcher] {}\n", now, message));
+    }
+}
+
+/// Waits, briefly, for everything queued so far to reach the disk.
+pub fn shutdown(app: &AppHandle) {
+    let Some(file) = app.try_state::<LogFile>() else {
+        return;
+    };
+    let (done, flushed) = mpsc::channel();
+    if file.tx.send(Message::Flush(done)).is_ok() {
+        flushed.recv_timeout(FLUSH_TIMEOUT).ok();
+    }
+}
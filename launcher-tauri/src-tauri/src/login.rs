@@ -0,0 +1,250 @@
+use crate::error::LauncherError;
+use std::path::PathBuf;
+
+/// Passed by the login registration so the launcher starts in the tray.
+pub const MINIMIZED_FLAG: &str = "--minimized";
+
+const NAME: &str = "ABEL Launcher";
+
+pub fn started_minimized(args: &[String]) -> bool {
+    args.iter().skip(1).any(|arg| arg == MINIMIZED_FLAG)
+}
+
+/// What the registration points at. An AppImage runs from a fresh mount each
+/// time, so the image file itself is registered instead.
+fn executable() -> Result<PathBuf, LauncherError> {
+    if cfg!(target_os = "linux") {
+        if let Some(image) = std::env::var_os("APPIMAGE").filter(|image| !image.is_empty()) {
+            return Ok(PathBuf::from(image));
+        }
+    }
+    Ok(std::env::current_exe()?)
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+fn home() -> Result<PathBuf, LauncherError> {
+    std::env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .ok_or_else(|| LauncherError::Other { message: "HOME is not set".to_string() })
+}
+
+fn remove(path: &std::path::Path) -> Result<(), LauncherError> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether logging in starts this executable, read back from the registration
+/// itself. One left behind by another install, pointing elsewhere, counts as off.
+pub async fn is_enabled() -> Result<bool, LauncherError> {
+    platform::is_enabled(&executable()?).await
+}
+
+/// Registers or fully removes the login item, then reports what is in place.
+pub async fn set_enabled(enabled: bool) -> Result<bool, LauncherError> {
+    let exe = executable()?;
+    if enabled {
+        platform::register(&exe).await?;
+    } else {
+        platform::unregister().await?;
+    }
+    platform::is_enabled(&exe).await
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{remove, NAME};
+    use crate::docker::reg_value;
+    use crate::error::LauncherError;
+    use std::path::{Path, PathBuf};
+    use tokio::process::Command;
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+    /// A shortcut an installer or the user may have added instead of the Run value.
+    fn startup_shortcut() -> Option<PathBuf> {
+        let appdata = std::env::var_os("APPDATA")?;
+        Some(
+            PathBuf::from(appdata)
+                .join(r"Microsoft\Windows\Start Menu\Programs\Startup")
+                .join(format!("{}.lnk", NAME)),
+        )
+    }
+
+    async fn run_value() -> Result<Option<String>, LauncherError> {
+        let output = Command::new("reg").args(["query", RUN_KEY, "/v", NAME]).output().await?;
+        // Exits 1 when the value doesn't exist
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(reg_value(&String::from_utf8_lossy(&output.stdout), NAME))
+    }
+
+    async fn reg(args: &[&str]) -> Result<(), LauncherError> {
+        let output = Command::new("reg").args(args).output().await?;
+        if !output.status.success() {
+            return Err(LauncherError::CommandFailed {
+                exit_code: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    pub async fn is_enabled(exe: &Path) -> Result<bool, LauncherError> {
+        let exe = exe.to_string_lossy().to_lowercase();
+        if let Some(value) = run_value().await? {
+            return Ok(value.to_lowercase().contains(&exe));
+        }
+        // A shortcut's target takes COM to read, so its presence has to do
+        Ok(startup_shortcut().is_some_and(|path| path.is_file()))
+    }
+
+    pub async fn register(exe: &Path) -> Result<(), LauncherError> {
+        let command = format!("\"{}\" {}", exe.display(), super::MINIMIZED_FLAG);
+        reg(&["add", RUN_KEY, "/v", NAME, "/t", "REG_SZ", "/d", &command, "/f"]).await
+    }
+
+    pub async fn unregister() -> Result<(), LauncherError> {
+        if run_value().await?.is_some() {
+            reg(&["delete", RUN_KEY, "/v", NAME, "/f"]).await?;
+        }
+        match startup_shortcut() {
+            Some(path) => remove(&path),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{home, remove};
+    use crate::error::LauncherError;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    const LABEL: &str = "com.abel.launcher";
+
+    fn plist_path() -> Result<PathBuf, LauncherError> {
+        Ok(home()?.join("Library/LaunchAgents").join(format!("{}.plist", LABEL)))
+    }
+
+    fn escape(value: &str) -> String {
+        value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+    }
+
+    fn program_string(exe: &Path) -> String {
+        format!("<string>{}</string>", escape(&exe.to_string_lossy()))
+    }
+
+    pub async fn is_enabled(exe: &Path) -> Result<bool, LauncherError> {
+        match fs::read_to_string(plist_path()?) {
+            Ok(contents) => Ok(contents.contains(&program_string(exe))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Agents in `~/Library/LaunchAgents` are loaded by launchd at the next
+    /// login, so the file is all there is to it.
+    pub async fn register(exe: &Path) -> Result<(), LauncherError> {
+        let path = plist_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        {}
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+            LABEL,
+            program_string(exe),
+            super::MINIMIZED_FLAG
+        );
+        Ok(fs::write(path, plist)?)
+    }
+
+    pub async fn unregister() -> Result<(), LauncherError> {
+        remove(&plist_path()?)
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    use super::{home, remove, NAME};
+    use crate::error::LauncherError;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// Per the XDG autostart spec.
+    fn desktop_path() -> Result<PathBuf, LauncherError> {
+        let config = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => home()?.join(".config"),
+        };
+        Ok(config.join("autostart").join("abel-launcher.desktop"))
+    }
+
+    /// Quoting for an `Exec` argument, from the desktop entry spec.
+    fn quote(exe: &Path) -> String {
+        let mut quoted = String::from("\"");
+        for c in exe.to_string_lossy().chars() {
+            if matches!(c, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    /// An entry switched off by a desktop's settings stays on disk with one of these.
+    fn disabled(contents: &str) -> bool {
+        contents.lines().map(str::trim).any(|line| {
+            line.eq_ignore_ascii_case("Hidden=true") || line.eq_ignore_ascii_case("X-GNOME-Autostart-enabled=false")
+        })
+    }
+
+    pub async fn is_enabled(exe: &Path) -> Result<bool, LauncherError> {
+        let contents = match fs::read_to_string(desktop_path()?) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e.into()),
+        };
+        let exec = format!("Exec={} ", quote(exe));
+        Ok(!disabled(&contents) && contents.lines().any(|line| line.trim().starts_with(&exec)))
+    }
+
+    pub async fn register(exe: &Path) -> Result<(), LauncherError> {
+        let path = desktop_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={} {}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+            NAME,
+            quote(exe),
+            super::MINIMIZED_FLAG
+        );
+        Ok(fs::write(path, entry)?)
+    }
+
+    pub async fn unregister() -> Result<(), LauncherError> {
+        remove(&desktop_path()?)
+    }
+}
@@ -0,0 +1,562 @@
+use crate::error::LauncherError;
+use crate::{docker, format_timestamp, LogEvent};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use crate::AppHandle;
+
+/// Where a console line came from.
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSource {
+    /// The launcher's own status messages.
+    Launcher,
+    /// Output of a `docker-compose` invocation (build, up, down, pull).
+    Compose,
+    /// Output of the `docker` CLI itself, e.g. prune.
+    Docker,
+    /// Container output attached through `follow_logs`.
+    Container,
+}
+
+/// Severity of a console line, least to most severe.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    /// Level of an event's `level` string. `success` ranks as info; anything
+    /// unrecognised does too, so it is never hidden below the default.
+    pub fn of(level: &str) -> LogLevel {
+        match level {
+            "debug" => LogLevel::Debug,
+            "warning" => LogLevel::Warning,
+            "error" => LogLevel::Error,
+            _ => LogLevel::Info,
+        }
+    }
+}
+
+/// Which events reach the console. Empty lists allow everything; events without a
+/// service are only subject to the source list.
+#[derive(Clone, Default)]
+pub struct LogFilter {
+    pub sources: Vec<LogSource>,
+    pub services: Vec<String>,
+    /// Events below this level are held back. The launcher's own messages are
+    /// exempt, so a boot starting or failing always shows.
+    pub min_level: LogLevel,
+}
+
+impl LogFilter {
+    pub fn allows(&self, event: &LogEvent) -> bool {
+        let source_ok = self.sources.is_empty() || self.sources.contains(&event.source);
+        let service_ok = match &event.service {
+            Some(service) => self.services.is_empty() || self.services.contains(service),
+            None => true,
+        };
+        let level_ok = event.source == LogSource::Launcher || LogLevel::of(&event.level) >= self.min_level;
+        source_ok && service_ok && level_ok
+    }
+}
+
+/// The filter applied before `log` events are emitted.
+#[derive(Default)]
+pub struct LogFilterState(Mutex<LogFilter>);
+
+impl LogFilterState {
+    pub fn get(&self) -> LogFilter {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, filter: LogFilter) {
+        *self.0.lock().unwrap() = filter;
+    }
+
+    pub fn set_min_level(&self, level: LogLevel) {
+        self.0.lock().unwrap().min_level = level;
+    }
+}
+
+/// Maps a `docker-compose logs` line onto the service that printed it.
+///
+/// The prefix is `web-1`, `abel-web-1` or `abel_web_1` depending on the compose
+/// version, so it is matched against the known service names rather than parsed.
+pub fn container_service(line: &str, known: &[String]) -> Option<String> {
+    let (prefix, _) = line.split_once('|')?;
+    let prefix = prefix.trim();
+    let base = prefix
+        .rsplit_once(['-', '_'])
+        .filter(|(_, replica)| !replica.is_empty() && replica.chars().all(|c| c.is_ascii_digit()))
+        .map_or(prefix, |(base, _)| base);
+
+    known
+        .iter()
+        .filter(|service| {
+            base == service.as_str()
+                || base.strip_suffix(service.as_str()).is_some_and(|rest| rest.ends_with(['-', '_']))
+        })
+        .max_by_key(|service| service.len())
+        .cloned()
+}
+
+/// Markers that settle a line's level on their own, wherever they appear.
+const ERROR_MARKERS: [&str; 3] = ["Traceback (most recent call last)", "panicked at", "Exception in ASGI application"];
+
+/// Level words as Python logging, uvicorn and Rust tracing print them.
+const LEVEL_WORDS: [(&str, &str); 9] = [
+    ("CRITICAL", "error"),
+    ("FATAL", "error"),
+    ("ERROR", "error"),
+    ("WARNING", "warning"),
+    ("WARN", "warning"),
+    ("INFO", "info"),
+    ("DEBUG", "debug"),
+    ("TRACE", "debug"),
+    ("NOTSET", "debug"),
+];
+
+/// Level of a container log line, for colouring it like the launcher's own events.
+/// The first level word wins, since every format puts it before the message; lines
+/// without one are info.
+pub fn infer_level(line: &str) -> &'static str {
+    if ERROR_MARKERS.iter().any(|marker| line.contains(marker)) {
+        return "error";
+    }
+
+    let first_level = line
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find_map(|word| LEVEL_WORDS.iter().find(|(level, _)| *level == word).map(|(_, level)| *level));
+    if let Some(level) = first_level {
+        return level;
+    }
+    // The last line of a Python traceback, e.g. `ValueError: bad input`
+    let exception = line.split_whitespace().any(|word| {
+        word.starts_with(|c: char| c.is_ascii_uppercase())
+            && (word.ends_with("Error:") || word.ends_with("Exception:"))
+    });
+    if exception {
+        "error"
+    } else {
+        "info"
+    }
+}
+
+/// A `docker-compose logs --timestamps` line taken apart.
+pub struct ContainerLine {
+    pub service: Option<String>,
+    /// The time docker recorded the line at.
+    pub timestamp: Option<chrono::DateTime<chrono::Local>>,
+    /// The line with docker's timestamp removed, prefix kept.
+    pub message: String,
+}
+
+/// Splits `backend-1  | 2024-05-01T10:00:00.123456789Z message` into its parts.
+pub fn parse_container_line(line: &str, known: &[String]) -> ContainerLine {
+    let service = container_service(line, known);
+    let (prefix, rest) = match line.split_once('|') {
+        Some((prefix, rest)) => (Some(prefix), rest.trim_start()),
+        None => (None, line),
+    };
+    let (first, text) = rest.split_once(' ').unwrap_or((rest, ""));
+    let Ok(time) = chrono::DateTime::parse_from_rfc3339(first) else {
+        return ContainerLine { service, timestamp: None, message: line.to_string() };
+    };
+
+    ContainerLine {
+        service,
+        timestamp: Some(time.with_timezone(&chrono::Local)),
+        message: match prefix {
+            Some(prefix) => format!("{}| {}", prefix, text),
+            None => text.to_string(),
+        },
+    }
+}
+
+/// Events kept for backfill and export; the oldest are dropped first.
+pub const HISTORY_CAPACITY: usize = 2000;
+
+/// Ring buffer of the `LogEvent`s emitted this session, oldest first.
+#[derive(Default)]
+pub struct LogHistory(Mutex<VecDeque<LogEvent>>);
+
+impl LogHistory {
+    pub fn push(&self, event: LogEvent) {
+        let mut entries = self.0.lock().unwrap();
+        if entries.len() == HISTORY_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(event);
+    }
+
+    /// The last `limit` events (all of them when None), oldest first.
+    pub fn recent(&self, limit: Option<usize>) -> Vec<LogEvent> {
+        let entries = self.0.lock().unwrap();
+        let skip = limit.map_or(0, |limit| entries.len().saturating_sub(limit));
+        entries.iter().skip(skip).cloned().collect()
+    }
+
+    /// The last `limit` events logged up to `at`, oldest first.
+    pub fn until(&self, at: chrono::DateTime<chrono::Local>, limit: usize) -> Vec<LogEvent> {
+        let entries = self.0.lock().unwrap();
+        let mut events: Vec<LogEvent> =
+            entries.iter().rev().filter(|event| event.timestamp <= at).take(limit).cloned().collect();
+        events.reverse();
+        events
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    /// The last `limit` matching events, oldest first, with their position in the
+    /// buffer at the time of the search.
+    pub fn search(&self, search: &LogSearch, limit: usize) -> Vec<LogMatch> {
+        let entries = self.0.lock().unwrap();
+        let mut matches: Vec<LogMatch> = entries
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, event)| search.matches(LogLevel::of(&event.level), event.source, &event.message))
+            .take(limit)
+            .map(|(index, event)| LogMatch { index, event: event.clone() })
+            .collect();
+        matches.reverse();
+        matches
+    }
+}
+
+/// Longest pattern `search_logs` accepts.
+const MAX_PATTERN_LEN: usize = 256;
+/// Ceiling on a compiled pattern. The regex crate never backtracks, so size is
+/// what a pathological pattern costs; past this it is refused instead of run.
+const PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+/// A compiled `search_logs` query. Plain text is matched literally.
+pub struct LogSearch {
+    pattern: Regex,
+    /// At or above this level.
+    level: Option<LogLevel>,
+    source: Option<LogSource>,
+}
+
+impl LogSearch {
+    pub fn new(
+        query: &str,
+        regex: bool,
+        case_sensitive: bool,
+        level: Option<LogLevel>,
+        source: Option<LogSource>,
+    ) -> Result<LogSearch, LauncherError> {
+        if query.is_empty() {
+            return Err(LauncherError::invalid("the search query can't be empty"));
+        }
+        if query.len() > MAX_PATTERN_LEN {
+            return Err(LauncherError::invalid(format!(
+                "the search query is limited to {} characters",
+                MAX_PATTERN_LEN
+            )));
+        }
+        let pattern = if regex { query.to_string() } else { regex::escape(query) };
+        let pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(!case_sensitive)
+            .size_limit(PATTERN_SIZE_LIMIT)
+            .dfa_size_limit(PATTERN_SIZE_LIMIT)
+            .build()
+            .map_err(|e| LauncherError::invalid(format!("invalid pattern: {}", e)))?;
+        Ok(LogSearch { pattern, level, source })
+    }
+
+    pub fn matches(&self, level: LogLevel, source: LogSource, message: &str) -> bool {
+        self.level.is_none_or(|min| level >= min)
+            && self.source.is_none_or(|wanted| source == wanted)
+            && self.pattern.is_match(message)
+    }
+}
+
+/// An event from the history that matched a search.
+#[derive(Clone, Serialize)]
+pub struct LogMatch {
+    pub index: usize,
+    pub event: LogEvent,
+}
+
+/// Environment summary at the top of an export, so a bug report is self-describing.
+async fn header(app: &AppHandle) -> String {
+    let info = docker::get_info().await;
+    let unknown = || "unavailable".to_string();
+
+    let mut header = String::new();
+    writeln!(header, "A.B.E.L. launcher log export").ok();
+    writeln!(header, "Exported:       {}", chrono::Local::now().to_rfc3339()).ok();
+    writeln!(header, "App version:    {}", app.package_info().version).ok();
+    writeln!(header, "OS:             {} ({})", std::env::consts::OS, std::env::consts::ARCH).ok();
+    writeln!(header, "Docker client:  {}", info.client_version.unwrap_or_else(unknown)).ok();
+    writeln!(header, "Docker server:  {}", info.server_version.unwrap_or_else(unknown)).ok();
+    writeln!(header, "Compose:        {}", info.compose.map(|compose| compose.version).unwrap_or_else(unknown)).ok();
+    header.push('\n');
+    header
+}
+
+/// `event` as a line of the plain text export.
+pub fn text_line(event: &LogEvent) -> String {
+    format!("{} {:<7} {}", format_timestamp(&event.timestamp), event.level.to_uppercase(), event.message)
+}
+
+/// Writes the header and the buffered history to `path`, one event per line.
+pub async fn export(app: &AppHandle, history: &LogHistory, path: &Path) -> Result<(), LauncherError> {
+    let mut contents = header(app).await;
+    for event in history.recent(None) {
+        writeln!(contents, "{}", text_line(&event)).ok();
+    }
+
+    // sync_all surfaces a full disk that a plain buffered write would swallow
+    let fail = |e: std::io::Error| LauncherError::Io {
+        message: format!("could not write log export to {}: {}", path.display(), e),
+    };
+    let mut file = File::create(path).map_err(fail)?;
+    file.write_all(contents.as_bytes()).map_err(fail)?;
+    file.sync_all().map_err(fail)
+}
+
+/// Parses the `since` of a JSON export.
+pub fn parse_since(since: &str) -> Result<chrono::DateTime<chrono::FixedOffset>, LauncherError> {
+    chrono::DateTime::parse_from_rfc3339(since.trim()).map_err(|e| {
+        LauncherError::invalid(format!(
+            "since must be an RFC3339 timestamp like 2024-05-01T14:30:00+02:00, got {:?}: {}",
+            since, e
+        ))
+    })
+}
+
+/// Writes the history to `writer` as newline-delimited JSON, one event per line and
+/// only those after `since` when given. Returns how many events were written.
+pub fn write_json(
+    history: &LogHistory,
+    writer: &mut impl Write,
+    since: Option<chrono::DateTime<chrono::FixedOffset>>,
+) -> std::io::Result<usize> {
+    let mut written = 0;
+    for event in history.recent(None) {
+        if since.is_some_and(|since| event.timestamp <= since) {
+            continue;
+        }
+        // The event as the frontend gets it, so every field it carries is kept
+        serde_json::to_writer(&mut *writer, &event)?;
+        writer.write_all(b"\n")?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// [`write_json`] to the file at `path`.
+pub fn export_json(
+    history: &LogHistory,
+    path: &Path,
+    since: Option<chrono::DateTime<chrono::FixedOffset>>,
+) -> Result<usize, LauncherError> {
+    let fail = |e: std::io::Error| LauncherError::Io {
+        message: format!("could not write log export to {}: {}", path.display(), e),
+    };
+    let mut writer = BufWriter::new(File::create(path).map_err(fail)?);
+    let written = write_json(history, &mut writer, since).map_err(fail)?;
+    // As with the text export, sync_all surfaces a full disk
+    let file = writer.into_inner().map_err(|e| fail(e.into_error()))?;
+    file.sync_all().map_err(fail)?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Container output as each format prints it, and the level it should show at.
+    const LEVELS: [(&str, &str); 20] = [
+        // Python logging, default and custom formats
+        ("WARNING:root:model took 12s to answer", "warning"),
+        ("CRITICAL:abel.memory:out of memory", "error"),
+        ("2024-05-01 10:00:00,123 - abel.core - ERROR - could not reach the database", "error"),
+        ("2024-05-01 10:00:00,123 - abel.core - DEBUG - cache hit for user 42", "debug"),
+        ("[2024-05-01 10:00:00] INFO in app: scheduler started", "info"),
+        ("Traceback (most recent call last):", "error"),
+        ("ValueError: invalid literal for int() with base 10: 'abc'", "error"),
+        ("    raise ConnectionError(url)", "info"),
+        // uvicorn
+        ("INFO:     Uvicorn running on http://0.0.0.0:8000 (Press CTRL+C to quit)", "info"),
+        ("INFO:     172.18.0.1:52844 - \"GET /health HTTP/1.1\" 200 OK", "info"),
+        ("WARNING:  Invalid HTTP request received.", "warning"),
+        ("ERROR:    Exception in ASGI application", "error"),
+        // Rust tracing
+        ("2024-05-01T10:00:00.123456Z  INFO abel_worker: listening on 0.0.0.0:9000", "info"),
+        ("2024-05-01T10:00:00.123456Z  WARN abel_worker::queue: retrying job 7", "warning"),
+        ("2024-05-01T10:00:00.123456Z ERROR abel_worker::queue: job 7 failed", "error"),
+        ("2024-05-01T10:00:00.123456Z TRACE hyper::proto::h1: parsed 3 headers", "debug"),
+        ("thread 'tokio-runtime-worker' panicked at src/queue.rs:88:14:", "error"),
+        // The first level word wins; lowercase words and unlabelled lines are info
+        ("INFO: retried after ERROR 503", "info"),
+        ("no error found in 3 files", "info"),
+        ("PostgreSQL init process complete; ready for start up.", "info"),
+    ];
+
+    #[test]
+    fn levels_of_python_uvicorn_and_tracing_lines() {
+        for (line, level) in LEVELS {
+            assert_eq!(infer_level(line), level, "{line}");
+        }
+    }
+
+    #[test]
+    fn container_lines_keep_service_time_and_level() {
+        let known = vec!["backend".to_string(), "worker".to_string(), "db".to_string()];
+        let cases = [
+            (
+                "backend-1  | 2024-05-01T10:00:00.123456789Z INFO:     Application startup complete.",
+                Some("backend"),
+                "backend-1  | INFO:     Application startup complete.",
+                "info",
+            ),
+            (
+                "abel-worker-1  | 2024-05-01T10:00:01.5Z 2024-05-01T10:00:01.499Z  WARN abel_worker: slow job",
+                Some("worker"),
+                "abel-worker-1  | 2024-05-01T10:00:01.499Z  WARN abel_worker: slow job",
+                "warning",
+            ),
+            (
+                "abel_db_1 | 2024-05-01T10:00:02+02:00 2024-05-01 10:00:02,000 - init - ERROR - disk full",
+                Some("db"),
+                "abel_db_1 | 2024-05-01 10:00:02,000 - init - ERROR - disk full",
+                "error",
+            ),
+        ];
+        for (line, service, message, level) in cases {
+            let parsed = parse_container_line(line, &known);
+            assert_eq!(parsed.service.as_deref(), service, "{line}");
+            assert!(parsed.timestamp.is_some(), "{line}");
+            assert_eq!(parsed.message, message);
+            assert_eq!(infer_level(&parsed.message), level, "{line}");
+        }
+
+        let parsed = parse_container_line("backend-1  | 2024-05-01T10:00:00.000000001Z started", &known);
+        let recorded = chrono::DateTime::parse_from_rfc3339("2024-05-01T10:00:00.000000001Z").unwrap();
+        assert_eq!(parsed.timestamp.unwrap(), recorded);
+    }
+
+    /// A full history after `pushed` lines: every tenth an error from the launcher,
+    /// the rest compose output.
+    fn history(pushed: usize) -> LogHistory {
+        let history = LogHistory::default();
+        for i in 0..pushed {
+            let message = format!("job {} finished in {}ms", i, i % 997);
+            history.push(match i % 10 {
+                0 => crate::log_event(LogSource::Launcher, None, "error", message),
+                _ => crate::log_event(LogSource::Compose, None, "info", message),
+            });
+        }
+        history
+    }
+
+    fn search(query: &str, regex: bool, case_sensitive: bool) -> LogSearch {
+        LogSearch::new(query, regex, case_sensitive, None, None).unwrap()
+    }
+
+    fn messages(matches: &[LogMatch]) -> Vec<&str> {
+        matches.iter().map(|found| found.event.message.as_str()).collect()
+    }
+
+    #[test]
+    fn history_keeps_the_newest_entries() {
+        let history = history(5000);
+        let recent = history.recent(None);
+        assert_eq!(recent.len(), HISTORY_CAPACITY);
+        assert_eq!(recent[0].message, "job 3000 finished in 9ms");
+        assert_eq!(history.search(&search("job 2999 ", false, false), 10).len(), 0);
+    }
+
+    #[test]
+    fn search_returns_the_last_matches_oldest_first() {
+        let history = history(5000);
+        let found = history.search(&search(r"^job \d+7 finished", true, false), 3);
+        assert_eq!(
+            messages(&found),
+            ["job 4977 finished in 989ms", "job 4987 finished in 2ms", "job 4997 finished in 12ms"]
+        );
+        assert_eq!(found.iter().map(|found| found.index).collect::<Vec<_>>(), [1977, 1987, 1997]);
+        assert_eq!(history.search(&search(r"^job \d+7 finished", true, false), usize::MAX).len(), 200);
+    }
+
+    #[test]
+    fn plain_queries_are_literal() {
+        let history = history(3000);
+        assert!(history.search(&search("job.4999", false, false), 10).is_empty());
+        assert_eq!(history.search(&search("job 2999 finished", false, false), 10).len(), 1);
+        assert_eq!(history.search(&search("job.2999", true, false), 10).len(), 1);
+        assert!(history.search(&search("(job", false, false), 10).is_empty());
+    }
+
+    #[test]
+    fn case_sensitivity_is_opted_into() {
+        let history = history(2500);
+        assert_eq!(history.search(&search("JOB 2499", false, false), 10).len(), 1);
+        assert!(history.search(&search("JOB 2499", false, true), 10).is_empty());
+    }
+
+    #[test]
+    fn level_and_source_narrow_the_matches() {
+        let history = history(4000);
+        let errors = LogSearch::new("finished", false, false, Some(LogLevel::Warning), None).unwrap();
+        let found = history.search(&errors, usize::MAX);
+        assert_eq!(found.len(), HISTORY_CAPACITY / 10);
+        assert!(found.iter().all(|found| found.event.level == "error"));
+
+        let compose = LogSearch::new("job 3990", false, false, None, Some(LogSource::Compose)).unwrap();
+        assert!(history.search(&compose, 10).is_empty());
+        let launcher = LogSearch::new("job 3990", false, false, None, Some(LogSource::Launcher)).unwrap();
+        assert_eq!(history.search(&launcher, 10).len(), 1);
+    }
+
+    /// The message an invalid query is refused with.
+    fn refusal(query: &str, regex: bool) -> String {
+        match LogSearch::new(query, regex, false, None, None) {
+            Err(LauncherError::InvalidInput { message }) => message,
+            Err(other) => panic!("{query}: {other:?}"),
+            Ok(_) => panic!("{query} was accepted"),
+        }
+    }
+
+    #[test]
+    fn oversized_and_invalid_patterns_are_refused() {
+        assert!(LogSearch::new(&"a".repeat(MAX_PATTERN_LEN), false, false, None, None).is_ok());
+        let too_long = "a".repeat(MAX_PATTERN_LEN + 1);
+        assert_eq!(refusal(&too_long, false), "the search query is limited to 256 characters");
+        assert_eq!(refusal("", true), "the search query can't be empty");
+        assert!(refusal("(unclosed", true).starts_with("invalid pattern: "));
+        // Short, but compiles past the size limit
+        let huge = r"(?:\w{100}){100}";
+        assert!(huge.len() <= MAX_PATTERN_LEN);
+        assert!(refusal(huge, true).starts_with("invalid pattern: "));
+    }
+
+    #[test]
+    fn lines_without_a_timestamp_are_kept_whole() {
+        let known = vec!["backend".to_string()];
+        let parsed = parse_container_line("backend-1  | ERROR:    Exception in ASGI application", &known);
+        assert_eq!(parsed.service.as_deref(), Some("backend"));
+        assert!(parsed.timestamp.is_none());
+        assert_eq!(parsed.message, "backend-1  | ERROR:    Exception in ASGI application");
+
+        let parsed = parse_container_line("2024-05-01T10:00:00Z unprefixed", &known);
+        assert_eq!(parsed.service, None);
+        assert_eq!(parsed.message, "unprefixed");
+    }
+}
@@ -1,158 +1,3506 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use serde::Serialize;
-use std::process::Command;
+mod ansi;
+mod autorestart;
+mod backend;
+mod backup;
+mod compose;
+mod connectivity;
+mod containers;
+mod context;
+mod deeplink;
+mod diagnostics;
+mod disk;
+mod docker;
+mod doctor;
+mod engine;
+mod env;
+mod error;
+mod events;
+mod gpu;
+mod hotkey;
+mod install;
+mod limits;
+mod logfile;
+mod login;
+mod logs;
+mod messages;
+mod models;
+mod monitor;
+mod network;
+mod notify;
+mod ports;
+mod process;
+mod progress;
+mod proxy;
+mod remote;
+mod retry;
+mod runtime;
+mod schedule;
+mod secrets;
+mod settings;
+mod setup;
+mod shutdown;
+mod state;
+mod stats;
+mod statusapi;
+mod telemetry;
+mod terminal;
+mod tray;
+mod update;
+mod uptime;
+mod usage;
+mod versions;
+mod volumes;
+mod wsl;
+mod zip;
+
+use error::LauncherError;
+use autorestart::AutoRestart;
+use compose::{
+    all_services_running, compose_command, run_compose, run_compose_with, ComposeInfo, Health, Project, ServiceStatus,
+    StartOptions,
+};
+use logs::{LogFilter, LogFilterState, LogHistory, LogLevel, LogSource};
+use messages::{Message, MessageKey};
+use notify::Notice;
+use process::{spawn_piped, ChildSlot, RunOptions, LOG_BATCH_SIZE};
+use runtime::ContainerRuntime;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use settings::SettingsStore;
+use state::{LauncherState, Operation};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::Emitter;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_opener::OpenerExt;
+
+/// The Tauri runtime behind every command. Tests run them on the mock runtime,
+/// which needs no window system.
+#[cfg(not(test))]
+type AppRuntime = tauri::Wry;
+#[cfg(test)]
+type AppRuntime = tauri::test::MockRuntime;
+type AppHandle = tauri::AppHandle<AppRuntime>;
+
+static LAST_STATUS: Mutex<Option<StatusEvent>> = Mutex::new(None);
+static LAST_ERROR: Mutex<Option<LastError>> = Mutex::new(None);
+static STATUS_SENT: Mutex<StatusSent> = Mutex::new(StatusSent { status: None, at: None, pending: false });
+
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Extra time `down` gets past its own stop timeout before the watchdog kills it.
+const STOP_GRACE: Duration = Duration::from_secs(15);
+/// How long Docker Desktop gets to bring its daemon up after being launched.
+const DOCKER_LAUNCH_TIMEOUT: Duration = Duration::from_secs(120);
+/// History lines up to the failure that go into `copy_error_report`.
+const ERROR_REPORT_LOG_LINES: usize = 100;
+
+#[derive(Clone, Serialize)]
+struct LogEvent {
+    message: String,
+    level: String,
+    /// Sent as RFC3339, see `format_timestamp`.
+    #[serde(serialize_with = "serialize_timestamp")]
+    timestamp: chrono::DateTime<chrono::Local>,
+    /// Time of day only, the way the console shows it.
+    display_time: String,
+    source: LogSource,
+    /// Compose service the line belongs to, when known.
+    service: Option<String>,
+    /// Terminal colour the child printed the line in, when `ansi_colors` is on.
+    color: Option<String>,
+    /// Catalog key of a launcher message, e.g. `boot.failed`, for the frontend to
+    /// translate; `message` is then the English text. None for child process output.
+    key: Option<&'static str>,
+    /// Values the message is filled with, e.g. `{"error": ...}`.
+    params: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Phase {
+    Stopped,
+    Starting,
+    Running,
+    /// Only part of the stack is up, or a healthcheck is failing.
+    Degraded,
+    Stopping,
+    /// The last operation failed; `StatusEvent::error` says why.
+    Failed,
+    /// The daemon stopped answering while the stack was up. Its containers may
+    /// still exist and resume, so nothing is taken as stopped until it returns.
+    DaemonLost,
+}
+
+#[derive(Clone, PartialEq, Serialize)]
+struct StatusEvent {
+    phase: Phase,
+    error: Option<String>,
+    services_up: u32,
+    services_total: u32,
+    healthy: bool,
+    /// Multi-phase operation in flight, e.g. "restarting" or "updating".
+    operation: Option<&'static str>,
+    // Pre-phase fields, derived from `phase` for frontends that still read them
+    running: bool,
+    starting: bool,
+    /// Seconds since the stack last came online, filled in as the event is emitted.
+    uptime_secs: Option<u64>,
+    /// Name of the active environment, filled in as the event is emitted.
+    environment: Option<String>,
+    /// The most recent failure until the next successful start, filled in as the event is emitted.
+    last_error: Option<String>,
+    last_error_code: Option<String>,
+}
+
+impl StatusEvent {
+    /// A status in `phase`, carrying over the last known service counts.
+    fn new(phase: Phase) -> Self {
+        let (services_up, services_total) = LAST_STATUS
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or((0, 0), |last| (last.services_up, last.services_total));
+        Self {
+            phase,
+            error: None,
+            services_up,
+            services_total,
+            healthy: phase == Phase::Running,
+            operation: None,
+            running: matches!(phase, Phase::Running | Phase::Degraded | Phase::Stopping | Phase::DaemonLost),
+            starting: matches!(phase, Phase::Starting | Phase::Stopping),
+            uptime_secs: None,
+            environment: None,
+            last_error: None,
+            last_error_code: None,
+        }
+    }
+
+    fn with_operation(self, operation: &'static str) -> Self {
+        Self { operation: Some(operation), ..self }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ServiceStatusEvent {
+    service: String,
+    running: bool,
+    starting: bool,
+}
+
+/// Tracks the boot in flight so `cancel_start` can abort it.
+#[derive(Default)]
+struct BootControl {
+    active: AtomicBool,
+    cancelled: AtomicBool,
+    child: ChildSlot,
+    /// When the whole stack was last brought up, i.e. when compose last read its files.
+    last_up: Mutex<Option<SystemTime>>,
+}
+
+/// Marks a boot as active for its lifetime.
+struct ActiveBoot<'a>(&'a BootControl);
+
+impl BootControl {
+    fn begin(&self) -> ActiveBoot<'_> {
+        self.cancelled.store(false, Ordering::SeqCst);
+        self.active.store(true, Ordering::SeqCst);
+        ActiveBoot(self)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn mark_up(&self) {
+        *self.last_up.lock().unwrap() = Some(SystemTime::now());
+    }
+}
+
+impl Drop for ActiveBoot<'_> {
+    fn drop(&mut self) {
+        self.0.active.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Tracks the running `docker-compose pull` so `cancel_pull` can kill it.
+#[derive(Default)]
+struct PullControl {
+    cancelled: AtomicBool,
+    child: ChildSlot,
+}
+
+#[derive(Serialize)]
+struct PullResult {
+    pulled: Vec<String>,
+    up_to_date: Vec<String>,
+    failed: Vec<String>,
+    cancelled: bool,
+}
+
+/// Tracks the running `docker-compose down` for its watchdog.
+#[derive(Default)]
+struct StopControl {
+    escalated: AtomicBool,
+    child: ChildSlot,
+}
+
+/// Task streaming `docker-compose logs -f`, if one is attached.
+#[derive(Default)]
+struct LogFollower(Mutex<Option<tauri::async_runtime::JoinHandle<()>>>);
+
+/// Holds an operation of the [`LauncherState`] and settles it when dropped, however
+/// the command exits.
+struct OperationGuard {
+    app: AppHandle,
+}
+
+impl OperationGuard {
+    fn acquire(app: &AppHandle, operation: Operation) -> Result<Self, LauncherError> {
+        app.state::<Mutex<LauncherState>>().lock().unwrap().begin(operation)?;
+        Ok(Self { app: app.clone() })
+    }
+
+    fn advance(&self, operation: Operation) {
+        self.app.state::<Mutex<LauncherState>>().lock().unwrap().advance(operation);
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        self.app.state::<Mutex<LauncherState>>().lock().unwrap().finish();
+    }
+}
+
+/// Whether every service was up when last observed.
+fn is_running(app: &AppHandle) -> bool {
+    app.state::<Mutex<LauncherState>>().lock().unwrap().is_running()
+}
+
+/// The one format event timestamps are written in, to the webview, the log file and
+/// exports alike: RFC3339 to the millisecond with the local offset, e.g.
+/// `2024-05-01T14:30:05.123+02:00`.
+fn format_timestamp(at: &chrono::DateTime<chrono::Local>) -> String {
+    at.to_rfc3339_opts(chrono::SecondsFormat::Millis, false)
+}
+
+fn serialize_timestamp<S: serde::Serializer>(
+    at: &chrono::DateTime<chrono::Local>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_timestamp(at))
+}
+
+fn display_time(at: &chrono::DateTime<chrono::Local>) -> String {
+    at.format("%H:%M:%S%.3f").to_string()
+}
+
+/// `--project-dir` from the command line, or from a later launch forwarded to this one.
+#[derive(Default)]
+struct ProjectDirArg(Mutex<Option<String>>);
+
+fn project_dir_info(app: &AppHandle) -> settings::ProjectDirInfo {
+    let cli = app.state::<ProjectDirArg>().0.lock().unwrap().clone();
+    let setting = app.state::<SettingsStore>().get().current_project_dir();
+    let installed = install::install_dir(app);
+    settings::resolve_project_dir(cli.as_deref(), setting.as_deref(), installed.as_deref())
+}
+
+fn resolve_project_dir(app: &AppHandle) -> String {
+    project_dir_info(app).path
+}
+
+/// The project plus the settings that shape every compose invocation.
+fn resolve_project(app: &AppHandle) -> Project {
+    let settings = app.state::<SettingsStore>().get();
+    Project {
+        runtime: runtime::active(app),
+        dir: resolve_project_dir(app),
+        files: settings.current_compose_files(),
+        profiles: settings.current_profiles(),
+    }
+}
+
+/// Live status of the stack. `Running` needs every declared service up and healthy.
+async fn observe_status(project: &Project) -> Result<StatusEvent, LauncherError> {
+    observe_services(project).await.map(|(status, _)| status)
+}
+
+/// [`observe_status`] along with the containers it was derived from.
+async fn observe_services(project: &Project) -> Result<(StatusEvent, Vec<ServiceStatus>), LauncherError> {
+    let expected = compose::expected_services(project).await?;
+    let statuses = compose::service_status(project).await?;
+    let up = expected
+        .iter()
+        .filter(|name| statuses.iter().any(|s| &s.service == *name && s.is_running()))
+        .count();
+    let all_up = !expected.is_empty() && up == expected.len();
+    let healthy = all_up && compose::unhealthy_services(&statuses).is_empty();
+
+    let phase = match up {
+        0 => Phase::Stopped,
+        _ if healthy => Phase::Running,
+        _ => Phase::Degraded,
+    };
+    let status = StatusEvent {
+        services_up: up as u32,
+        services_total: expected.len() as u32,
+        healthy,
+        running: all_up,
+        starting: false,
+        ..StatusEvent::new(phase)
+    };
+    Ok((status, statuses))
+}
+
+/// Longest error text a status carries. Compose can dump pages of stderr; all of it
+/// is in the log history already.
+const STATUS_ERROR_MAX_CHARS: usize = 1000;
+
+fn truncate_error(message: &str) -> String {
+    let message = message.trim();
+    match message.char_indices().nth(STATUS_ERROR_MAX_CHARS) {
+        Some((end, _)) => format!("{}... (truncated, full text in the log)", &message[..end]),
+        None => message.to_string(),
+    }
+}
+
+/// The most recent failed operation, kept for a window opened after it happened.
+#[derive(Clone, Serialize)]
+struct LastError {
+    message: String,
+    code: String,
+    timestamp: String,
+    /// The whole text, stderr included, for `copy_error_report`.
+    #[serde(skip)]
+    detail: String,
+    #[serde(skip)]
+    at: chrono::DateTime<chrono::Local>,
+}
+
+fn record_failure(error: &LauncherError) {
+    let detail = error.to_string();
+    let at = chrono::Local::now();
+    *LAST_ERROR.lock().unwrap() = Some(LastError {
+        message: truncate_error(&detail),
+        code: error.code().to_string(),
+        timestamp: format_timestamp(&at),
+        detail,
+        at,
+    });
+}
+
+/// A `Failed` status that still reports whatever is actually up. Also records
+/// `error` as the last failure.
+async fn failed_status(project: &Project, error: &LauncherError) -> StatusEvent {
+    record_failure(error);
+    let observed = observe_status(project).await.unwrap_or_else(|_| StatusEvent::new(Phase::Failed));
+    StatusEvent {
+        phase: Phase::Failed,
+        error: Some(truncate_error(&error.to_string())),
+        healthy: false,
+        starting: false,
+        ..observed
+    }
+}
+
+/// Shortest gap between two `status` events. A change inside it goes out when the
+/// gap ends, as whatever the status is by then.
+const STATUS_MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The `status` event the frontend has, so the poll, the events stream and the
+/// commands repeating one another send nothing.
+struct StatusSent {
+    /// Without the uptime, which would make every poll look like a change.
+    status: Option<StatusEvent>,
+    at: Option<Instant>,
+    /// A send is scheduled for the end of the gap.
+    pending: bool,
+}
+
+/// What [`send_status`] does with a status.
+#[derive(Debug, PartialEq)]
+enum StatusSend {
+    /// The frontend has it already.
+    Unchanged,
+    Now,
+    /// Inside the gap: schedule a send for when it ends.
+    After(Duration),
+    /// Inside the gap with a send already scheduled, which picks this one up.
+    Scheduled,
+}
+
+impl StatusSent {
+    /// Whether `status`, compared without its uptime, goes out at `now`.
+    fn decide(&self, status: &StatusEvent, urgent: bool, now: Instant) -> StatusSend {
+        if self.status.as_ref() == Some(status) {
+            return StatusSend::Unchanged;
+        }
+        let wait = self.at.map_or(Duration::ZERO, |at| {
+            STATUS_MIN_INTERVAL.saturating_sub(now.saturating_duration_since(at))
+        });
+        if urgent || wait.is_zero() {
+            StatusSend::Now
+        } else if self.pending {
+            StatusSend::Scheduled
+        } else {
+            StatusSend::After(wait)
+        }
+    }
+}
+
+/// The one way a status is recorded and reaches the frontend. Failures go out at
+/// once; anything else at most every [`STATUS_MIN_INTERVAL`], and only if it changed.
+fn emit_status(app: &AppHandle, status: StatusEvent) {
+    let urgent = matches!(status.phase, Phase::Failed | Phase::DaemonLost);
+    {
+        // Both under the state lock, so the state machine and the last status never disagree
+        let launcher = app.state::<Mutex<LauncherState>>();
+        let mut launcher = launcher.lock().unwrap();
+        launcher.observe(status.running);
+        *LAST_STATUS.lock().unwrap() = Some(status.clone());
+    }
+    tray::update(app, status.running, status.starting);
+    if matches!(status.phase, Phase::Stopped | Phase::Stopping | Phase::Starting | Phase::Failed) {
+        uptime::clear(app);
+    }
+    send_status(app, urgent);
+}
+
+/// Sends the last status, now or at the end of the gap.
+fn send_status(app: &AppHandle, urgent: bool) {
+    let Some(status) = LAST_STATUS.lock().unwrap().clone() else {
+        return;
+    };
+    let status = with_context(app, status);
+    let compared = StatusEvent { uptime_secs: None, ..status.clone() };
+    let mut sent = STATUS_SENT.lock().unwrap();
+    match sent.decide(&compared, urgent, Instant::now()) {
+        StatusSend::Unchanged | StatusSend::Scheduled => {}
+        StatusSend::Now => {
+            // Emitted under the lock, so two sends can't overtake each other
+            app.emit("status", status).ok();
+            sent.status = Some(compared);
+            sent.at = Some(Instant::now());
+        }
+        StatusSend::After(wait) => {
+            sent.pending = true;
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(wait).await;
+                STATUS_SENT.lock().unwrap().pending = false;
+                send_status(&app, false);
+            });
+        }
+    }
+}
+
+/// Fills in the fields a status event carries beyond what was observed.
+fn with_context(app: &AppHandle, status: StatusEvent) -> StatusEvent {
+    let uptime_secs = uptime::secs(app);
+    let environment = app.state::<SettingsStore>().get().active_environment;
+    let last_error = LAST_ERROR.lock().unwrap().clone();
+    StatusEvent {
+        uptime_secs,
+        environment,
+        last_error_code: last_error.as_ref().map(|error| error.code.clone()),
+        last_error: last_error.map(|error| error.message),
+        ..status
+    }
+}
+
+/// The status as last emitted, or observed now when nothing has been emitted yet.
+async fn current_status(app: &AppHandle) -> Result<StatusEvent, LauncherError> {
+    let last = LAST_STATUS.lock().unwrap().clone();
+    let status = match last {
+        Some(status) => status,
+        None => observe_status(&resolve_project(app)).await?,
+    };
+    Ok(with_context(app, status))
+}
+
+fn emit_log(app: &AppHandle, level: &str, message: impl Into<Message>) {
+    emit_log_from(app, LogSource::Launcher, None, level, message);
+}
+
+/// History keeps every event; the live stream only carries what the filter allows.
+fn emit_log_from(
+    app: &AppHandle,
+    source: LogSource,
+    service: Option<&str>,
+    level: &str,
+    message: impl Into<Message>,
+) {
+    let message = message.into();
+    let event = log_event(source, service, level, message.english());
+    emit_event(app, LogEvent { key: Some(message.key.key()), params: message.params(), ..event });
+}
+
+/// Like [`emit_log_from`] for output of a docker command, which has no key.
+fn emit_output(app: &AppHandle, source: LogSource, service: Option<&str>, level: &str, text: impl Into<String>) {
+    emit_event(app, log_event(source, service, level, text.into()));
+}
+
+fn log_event(source: LogSource, service: Option<&str>, level: &str, message: String) -> LogEvent {
+    let now = chrono::Local::now();
+    LogEvent {
+        message,
+        level: level.to_string(),
+        timestamp: now,
+        display_time: display_time(&now),
+        source,
+        service: service.map(str::to_string),
+        color: None,
+        key: None,
+        params: None,
+    }
+}
+
+/// Records `event` and emits it if the console filter lets it through.
+fn emit_event(app: &AppHandle, event: LogEvent) {
+    if record_event(app, &event) {
+        if let Err(e) = app.emit("log", event) {
+            logfile::record_internal(app, format!("log event not delivered: {}", e));
+        }
+    }
+}
+
+/// Keeps `event` in the log file and history. True if the console filter lets it through.
+fn record_event(app: &AppHandle, event: &LogEvent) -> bool {
+    logfile::record(app, event);
+    if let Some(history) = app.try_state::<LogHistory>() {
+        history.push(event.clone());
+    }
+    app.try_state::<LogFilterState>().is_none_or(|filter| filter.get().allows(event))
+}
+
+/// Polls healthchecks until every service that defines one is healthy, or the
+/// configured timeout runs out. Returns the services still pending on timeout.
+async fn wait_for_healthy(app: &AppHandle, project: &Project) -> Result<(), Vec<String>> {
+    let timeout = Duration::from_secs(app.state::<SettingsStore>().get().health_timeout_secs);
+    let deadline = Instant::now() + timeout;
+    let mut announced = false;
+
+    loop {
+        let statuses = compose::service_status(project).await.unwrap_or_default();
+        let pending = compose::unhealthy_services(&statuses);
+        if pending.is_empty() {
+            return Ok(());
+        }
+        if Instant::now() >= deadline || app.state::<BootControl>().is_cancelled() {
+            return Err(pending);
+        }
+        if !announced {
+            emit_log(app, "info", MessageKey::HealthAwaiting.with(json!({ "services": pending.join(", ") })));
+            announced = true;
+        }
+        tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+    }
+}
+
+/// Polls the core's readiness endpoint until it answers 200. Healthy containers only
+/// mean compose is done; the API can take another minute loading models.
+async fn wait_for_ready(app: &AppHandle) -> bool {
+    let settings = app.state::<SettingsStore>().get();
+    if settings.ready_url.is_empty() {
+        return true;
+    }
+    let timeout = Duration::from_secs(settings.ready_timeout_secs);
+    let interval = Duration::from_secs(settings.ready_interval_secs.max(1));
+    let started = Instant::now();
+
+    loop {
+        if backend::is_ready(&remote::localize(&settings.ready_url)).await {
+            return true;
+        }
+        if started.elapsed() >= timeout || app.state::<BootControl>().is_cancelled() {
+            return false;
+        }
+        emit_log(app, "info", MessageKey::CoreWaiting.with(json!({ "secs": started.elapsed().as_secs() })));
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Final step of a successful boot: only claims online once healthchecks pass and
+/// the core answers. Returns false when the boot was cancelled while waiting.
+async fn report_health(app: &AppHandle, project: &Project, online_message: MessageKey) -> bool {
+    let result = match wait_for_healthy(app, project).await {
+        Ok(()) if !wait_for_ready(app).await => Err(None),
+        Ok(()) => Ok(()),
+        Err(pending) => Err(Some(pending)),
+    };
+    if app.state::<BootControl>().is_cancelled() {
+        return false;
+    }
+    let observed = observe_status(project).await;
+    match result {
+        Ok(()) => {
+            emit_log(app, "success", online_message);
+            *LAST_ERROR.lock().unwrap() = None;
+            uptime::mark_online(app);
+            notify::send(app, Notice::Requested, "A.B.E.L. online", "All services are up and healthy.");
+            emit_status(app, observed.unwrap_or_else(|_| StatusEvent::new(Phase::Running)));
+            if app.state::<SettingsStore>().get().auto_open {
+                if let Err(e) = open_web_ui(app.clone()).await {
+                    emit_log(app, "warning", MessageKey::WebUiOpenFailed.with(json!({ "error": e.to_string() })));
+                }
+            }
+        }
+        Err(pending) => {
+            let error = match pending {
+                Some(pending) => {
+                    let services = pending.join(", ");
+                    emit_log(app, "warning", MessageKey::HealthTimedOut.with(json!({ "services": services })));
+                    format!("health check timed out: {}", pending.join(", "))
+                }
+                None => {
+                    let secs = app.state::<SettingsStore>().get().ready_timeout_secs;
+                    emit_log(app, "warning", MessageKey::CoreNotReady.with(json!({ "secs": secs })));
+                    format!("core not ready after {} seconds", secs)
+                }
+            };
+            // Containers are up either way, so this is degraded rather than failed
+            let observed = observed.map_or_else(|_| StatusEvent::new(Phase::Degraded), |status| match status.phase {
+                Phase::Running => StatusEvent { phase: Phase::Degraded, healthy: false, ..status },
+                _ => status,
+            });
+            record_failure(&LauncherError::Other { message: error.clone() });
+            emit_status(app, StatusEvent { error: Some(error), ..observed });
+        }
+    }
+    true
+}
+
+fn min_free_disk_bytes(app: &AppHandle) -> u64 {
+    // Saturating, as a settings file edited by hand may hold any number
+    app.state::<SettingsStore>().get().min_free_disk_gb.saturating_mul(1_000_000_000)
+}
+
+#[tauri::command]
+async fn check_disk_space(app: AppHandle) -> disk::DiskReport {
+    let project = resolve_project(&app);
+    disk::check(Path::new(&project.dir), min_free_disk_bytes(&app)).await
+}
+
+/// Warns about, or with `refuse_low_disk` refuses, a boot short on disk space.
+async fn check_disk_before_build(app: &AppHandle, project: &Project) -> Result<(), LauncherError> {
+    let report = disk::check(Path::new(&project.dir), min_free_disk_bytes(app)).await;
+    let Some(low) = report.low().first().map(|space| (*space).clone()) else {
+        return Ok(());
+    };
+    let error = LauncherError::LowDiskSpace {
+        path: low.path,
+        free_bytes: low.free_bytes,
+        required_bytes: report.min_free_bytes,
+    };
+    if app.state::<SettingsStore>().get().refuse_low_disk {
+        return Err(error);
+    }
+    emit_log(app, "warning", MessageKey::LowDiskSpace.with(json!({ "error": error.to_string() })));
+    Ok(())
+}
+
+/// Warns about, or with `refuse_offline_pull` refuses, a boot that has images to pull
+/// from registries it can't reach. Docker would otherwise retry them for minutes.
+async fn check_connectivity_before_pull(app: &AppHandle, project: &Project) -> Result<(), LauncherError> {
+    let unreachable = connectivity::unreachable_for_pull(project).await.unwrap_or_default();
+    if unreachable.is_empty() {
+        return Ok(());
+    }
+    for check in &unreachable {
+        let reason = check.error.as_deref().unwrap_or("unreachable");
+        let params = json!({
+            "registry": check.registry,
+            "reason": reason,
+            "images": check.images.join(", "),
+        });
+        emit_log(app, "warning", MessageKey::RegistryUnreachable.with(params));
+    }
+    let registries = unreachable.into_iter().map(|check| check.registry).collect();
+    let error = LauncherError::RegistryUnreachable { registries };
+    if app.state::<SettingsStore>().get().refuse_offline_pull {
+        return Err(error);
+    }
+    emit_log(app, "warning", MessageKey::OfflinePull.with(json!({ "error": error.to_string() })));
+    Ok(())
+}
+
+#[tauri::command]
+async fn check_connectivity(app: AppHandle) -> Result<connectivity::ConnectivityReport, LauncherError> {
+    connectivity::check(&resolve_project(&app)).await
+}
+
+#[tauri::command]
+async fn check_gpu(app: AppHandle) -> Result<gpu::GpuCheck, LauncherError> {
+    gpu::check(&resolve_project(&app)).await
+}
+
+/// Runs every preflight check at once and reports each one, logging those that
+/// did not pass.
+#[tauri::command]
+async fn run_doctor(app: AppHandle) -> doctor::DoctorReport {
+    let report = doctor::run(&resolve_project(&app), doctor::Scope::Full, min_free_disk_bytes(&app)).await;
+    for check in report.checks.iter().filter(|check| check.state != doctor::CheckState::Pass) {
+        let level = if check.state == doctor::CheckState::Fail { "error" } else { "warning" };
+        let params = json!({ "check": check.id, "message": check.message });
+        emit_log(&app, level, MessageKey::DoctorCheck.with(params));
+    }
+    report
+}
+
+/// Inspects the project's networks and published ports, logging each failed check.
+#[tauri::command]
+async fn diagnose_network(app: AppHandle) -> Result<network::NetworkReport, LauncherError> {
+    let report = network::diagnose(&resolve_project(&app)).await?;
+    for check in report.checks.iter().filter(|check| !check.passed) {
+        let params = json!({ "message": check.message });
+        emit_log(&app, "warning", MessageKey::NetworkCheckFailed.with(params));
+    }
+    Ok(report)
+}
+
+/// The WSL 2 backend Docker Desktop runs on, with what to fix. NotApplicable off Windows.
+#[tauri::command]
+async fn check_wsl() -> wsl::WslReport {
+    wsl::check().await
+}
+
+/// Which container runtime commands go to, as configured or detected, and whether
+/// it can be used.
+#[tauri::command]
+async fn check_runtime(app: AppHandle) -> runtime::RuntimeReport {
+    let active = runtime::active(&app);
+    let status = retry::docker_status(&app, active.as_ref(), "RUNTIME CHECK").await;
+    let kind = active.kind().await;
+    runtime::RuntimeReport {
+        runtime: kind,
+        preference: app.state::<SettingsStore>().get().container_runtime,
+        status,
+        compose: compose::detect_for(kind).await.ok(),
+    }
+}
+
+/// Refuses to go on unless the daemon is usable, logging exactly what is wrong as
+/// `refusal`, a message taking the `error`.
+async fn ensure_docker_ready(
+    app: &AppHandle,
+    runtime: &dyn ContainerRuntime,
+    refusal: MessageKey,
+) -> Result<(), LauncherError> {
+    match retry::docker_status(app, runtime, "DOCKER CHECK").await.error(runtime.kind().await) {
+        Some(error) => {
+            emit_log(app, "error", refusal.with(json!({ "error": error.to_string() })));
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Refuses to boot a stack whose compose file doesn't parse, logging each issue as
+/// `refusal`. When compose can't run at all the boot goes ahead, so the docker
+/// checks report why.
+async fn ensure_compose_valid(app: &AppHandle, refusal: MessageKey) -> Result<(), LauncherError> {
+    let Ok(validation) = compose::validate(&resolve_project(app)).await else {
+        return Ok(());
+    };
+    if validation.valid {
+        return Ok(());
+    }
+    for issue in &validation.issues {
+        let location = match (&issue.file, issue.line, issue.column) {
+            (file, Some(line), Some(column)) => format!(" ({}LINE {}, COLUMN {})", file_prefix(file), line, column),
+            (file, Some(line), None) => format!(" ({}LINE {})", file_prefix(file), line),
+            (Some(file), None, _) => format!(" ({})", file),
+            (None, None, _) => String::new(),
+        };
+        let params = json!({ "issue": issue.message, "location": location });
+        emit_log(app, "error", refusal.with(params));
+    }
+    Err(LauncherError::ComposeInvalid { issues: validation.issues })
+}
+
+fn file_prefix(file: &Option<String>) -> String {
+    file.as_ref().map(|file| format!("{}, ", file)).unwrap_or_default()
+}
+
+#[tauri::command]
+async fn validate_compose(app: AppHandle) -> Result<compose::ComposeValidation, LauncherError> {
+    compose::validate(&resolve_project(&app)).await
+}
+
+#[tauri::command]
+async fn check_backend_health(app: AppHandle) -> Result<backend::BackendHealth, LauncherError> {
+    let url = app.state::<SettingsStore>().get().ready_url;
+    if url.is_empty() {
+        return Err(LauncherError::invalid("no health endpoint is configured"));
+    }
+    Ok(backend::ping(&remote::localize(&url)).await)
+}
+
+/// Launcher, Docker, Compose, project and image versions, for support requests.
+#[tauri::command]
+async fn get_versions(app: AppHandle) -> versions::Versions {
+    versions::collect(&resolve_project(&app)).await
+}
+
+/// Puts the versions on the clipboard as markdown and returns the same text.
+#[tauri::command]
+async fn copy_versions_to_clipboard(app: AppHandle) -> Result<String, LauncherError> {
+    let markdown = versions::collect(&resolve_project(&app)).await.to_markdown();
+    app.clipboard()
+        .write_text(markdown.as_str())
+        .map_err(|e| LauncherError::ClipboardUnavailable { message: e.to_string() })?;
+    emit_log(&app, "info", MessageKey::VersionsCopied);
+    Ok(markdown)
+}
+
+/// Puts the last failure, the log leading up to it and the versions on the clipboard
+/// as markdown, secrets masked as in the diagnostics bundle. Returns the characters
+/// copied.
+#[tauri::command]
+async fn copy_error_report(app: AppHandle) -> Result<usize, LauncherError> {
+    let last_error = LAST_ERROR.lock().unwrap().clone();
+    let Some(last_error) = last_error else {
+        return Err(LauncherError::invalid("no failure has been recorded since the last successful boot"));
+    };
+    let project = resolve_project(&app);
+    let log = app.state::<LogHistory>().until(last_error.at, ERROR_REPORT_LOG_LINES);
+    let mut report = format!(
+        "### A.B.E.L. error report\n\n```text\n{} at {}\n{}\n\n-- last {} log lines --\n",
+        last_error.code,
+        last_error.timestamp,
+        last_error.detail.trim_end(),
+        log.len()
+    );
+    for event in &log {
+        report.push_str(&logs::text_line(event));
+        report.push('\n');
+    }
+    report.push_str("```\n\n");
+    report.push_str(&versions::collect(&project).await.to_markdown());
+    let report = diagnostics::Redactor::for_project(&project.dir).text(&report);
+
+    app.clipboard()
+        .write_text(report.as_str())
+        .map_err(|e| LauncherError::ClipboardUnavailable { message: e.to_string() })?;
+    emit_log(&app, "info", MessageKey::ErrorReportCopied);
+    Ok(report.chars().count())
+}
+
+#[tauri::command]
+async fn get_docker_info() -> docker::DockerInfo {
+    docker::get_info().await
+}
+
+#[tauri::command]
+async fn list_contexts() -> Result<Vec<context::DockerContext>, LauncherError> {
+    context::list().await
+}
+
+#[derive(Serialize)]
+struct ContextChange {
+    context: Option<String>,
+    /// Services of the old context are still up there, out of the launcher's sight.
+    left_running: bool,
+}
+
+/// Persists the docker context to use, None for the CLI's own choice. Nothing is
+/// stopped on the old one; if it still runs services the switch says so.
+#[tauri::command]
+async fn set_docker_context(app: AppHandle, name: Option<String>) -> Result<ContextChange, LauncherError> {
+    if runtime::active(&app).kind().await != runtime::RuntimeKind::Docker {
+        let message = "contexts are a Docker feature; Podman has none".to_string();
+        return Err(LauncherError::NotSupported { message });
+    }
+    let name = name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+    if let Some(name) = &name {
+        if !context::list().await?.iter().any(|context| &context.name == name) {
+            return Err(LauncherError::invalid(format!("unknown docker context: {}", name)));
+        }
+    }
+    if app.state::<SettingsStore>().get().docker_context == name {
+        return Ok(ContextChange { context: name, left_running: false });
+    }
+
+    if app.state::<SettingsStore>().get().docker_host.is_some() {
+        return Err(LauncherError::invalid("a docker_host is set, which overrides any context; clear it first"));
+    }
+
+    let guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let left_running = services_left_running(&app).await;
+    app.state::<SettingsStore>()
+        .update(|settings| settings.docker_context = name.clone())?;
+    context::configure(name.as_deref());
+    emit_log(&app, "info", MessageKey::DockerContextSet.with(json!({ "context": name })));
+    if left_running {
+        emit_log(&app, "warning", MessageKey::ContextLeftRunning);
+    }
+    drop(guard);
+    environment_switched(&app).await;
+    Ok(ContextChange { context: name, left_running })
+}
+
+/// Whether the daemon about to be left behind still runs part of the stack.
+async fn services_left_running(app: &AppHandle) -> bool {
+    is_running(app)
+        || compose::service_status(&resolve_project(app))
+            .await
+            .is_ok_and(|statuses| statuses.iter().any(ServiceStatus::is_running))
+}
+
+#[derive(Serialize)]
+struct DockerHostChange {
+    docker_host: Option<String>,
+    /// Services on the previous daemon are still up there, out of the launcher's sight.
+    left_running: bool,
+}
+
+/// Persists a remote daemon for every docker and compose command, None for the local
+/// one. Compose keeps reading the local project dir; see [`remote::apply`].
+#[tauri::command]
+async fn set_docker_host(app: AppHandle, host: Option<String>) -> Result<DockerHostChange, LauncherError> {
+    if runtime::active(&app).kind().await != runtime::RuntimeKind::Docker {
+        let message = "a remote docker host needs the Docker runtime".to_string();
+        return Err(LauncherError::NotSupported { message });
+    }
+    let host = host.map(|host| host.trim().to_string()).filter(|host| !host.is_empty());
+    if let Some(host) = &host {
+        remote::validate(host)?;
+    }
+    let settings = app.state::<SettingsStore>().get();
+    if settings.docker_host == host {
+        return Ok(DockerHostChange { docker_host: host, left_running: false });
+    }
+    if host.is_some() && settings.docker_context.is_some() {
+        return Err(LauncherError::invalid("a docker_context is set; clear it before using a docker host"));
+    }
+
+    let guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let left_running = services_left_running(&app).await;
+    app.state::<SettingsStore>()
+        .update(|settings| settings.docker_host = host.clone())?;
+    remote::configure(host.as_deref());
+    emit_log(&app, "info", MessageKey::DockerHostSet.with(json!({ "host": host.as_deref().unwrap_or("LOCAL") })));
+    if left_running {
+        emit_log(&app, "warning", MessageKey::HostLeftRunning);
+    }
+    drop(guard);
+    environment_switched(&app).await;
+    Ok(DockerHostChange { docker_host: host, left_running })
+}
+
+/// Checks that `docker info` gets through `host`, or the saved docker host, and how fast.
+#[tauri::command]
+async fn test_remote_connection(
+    app: AppHandle,
+    host: Option<String>,
+) -> Result<remote::RemoteTest, LauncherError> {
+    let host = host
+        .or_else(|| app.state::<SettingsStore>().get().docker_host)
+        .ok_or_else(|| LauncherError::invalid("no docker host given or configured"))?;
+    remote::validate(&host)?;
+    let test = remote::test(&host).await;
+    match &test.server_version {
+        Some(version) => {
+            let params = json!({ "host": host, "ms": test.latency_ms, "version": version });
+            emit_log(&app, "info", MessageKey::DockerHostAnswered.with(params));
+        }
+        None => emit_log(&app, "warning", MessageKey::DockerHostUnreachable.with(json!({ "host": host }))),
+    }
+    Ok(test)
+}
+
+#[tauri::command]
+async fn get_compose_info() -> Result<ComposeInfo, LauncherError> {
+    compose::detect().await
+}
+
+#[tauri::command]
+async fn check_status(app: AppHandle) -> Result<bool, LauncherError> {
+    let project = resolve_project(&app);
+
+    let running = retry::retried(&app, "STATUS CHECK", || all_services_running(&project), retry::error_stderr).await?;
+    app.state::<Mutex<LauncherState>>().lock().unwrap().observe(running);
+
+    Ok(running)
+}
+
+#[tauri::command]
+async fn get_service_status(app: AppHandle) -> Result<Vec<ServiceStatus>, LauncherError> {
+    compose::service_status(&resolve_project(&app)).await
+}
+
+/// Every container of the project with its image, state, timings and ports, for
+/// the dashboard. Empty when none was ever created.
+#[tauri::command]
+async fn list_containers(app: AppHandle) -> Result<Vec<containers::ContainerInfo>, LauncherError> {
+    containers::list(&resolve_project(&app)).await
+}
+
+#[tauri::command]
+async fn check_ports(app: AppHandle) -> Result<Vec<ports::PortConflict>, LauncherError> {
+    ports::find_conflicts(&resolve_project(&app)).await
+}
+
+/// Launches Docker Desktop and waits for its daemon to answer.
+async fn launch_docker_desktop(app: &AppHandle, runtime: &dyn ContainerRuntime) -> Result<(), LauncherError> {
+    if matches!(runtime.probe().await.status, docker::DockerStatus::Ready { .. }) {
+        return Ok(());
+    }
+    let kind = runtime.kind().await;
+    let engine = kind.engine_name();
+    emit_log(app, "info", MessageKey::EngineLaunching.with(json!({ "engine": engine })));
+    docker::launch_engine(kind).await.inspect_err(|e| {
+        let params = json!({ "engine": engine, "error": e.to_string() });
+        emit_log(app, "error", MessageKey::EngineLaunchFailed.with(params));
+    })?;
+    retry::daemon_launched();
+
+    let started = Instant::now();
+    loop {
+        tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+        match runtime.probe().await.status {
+            docker::DockerStatus::Ready { version } => {
+                emit_log(app, "success", MessageKey::DaemonOnline.with(json!({ "version": version })));
+                return Ok(());
+            }
+            _ if started.elapsed() >= DOCKER_LAUNCH_TIMEOUT => {
+                let secs = DOCKER_LAUNCH_TIMEOUT.as_secs();
+                emit_log(app, "error", MessageKey::DaemonLaunchTimedOut.with(json!({ "secs": secs })));
+                return Err(LauncherError::DaemonUnreachable {
+                    message: format!("Docker Desktop did not start within {} seconds", secs),
+                });
+            }
+            _ => {
+                let params = json!({ "secs": started.elapsed().as_secs() });
+                emit_log(app, "info", MessageKey::DaemonWaiting.with(params));
+            }
+        }
+    }
+}
+
+#[tauri::command]
+async fn start_docker_desktop(app: AppHandle) -> Result<(), LauncherError> {
+    launch_docker_desktop(&app, runtime::active(&app).as_ref()).await
+}
+
+/// Gets the runtime ready for a boot: launches its engine first when the daemon is
+/// stopped and `auto_launch_docker` is on.
+async fn prepare_docker(app: &AppHandle, refusal: MessageKey) -> Result<(), LauncherError> {
+    let runtime = runtime::active(app);
+    let auto_launch = app.state::<SettingsStore>().get().auto_launch_docker;
+    if auto_launch && runtime.probe().await.status == docker::DockerStatus::DaemonStopped {
+        launch_docker_desktop(app, runtime.as_ref()).await.ok();
+    }
+    ensure_docker_ready(app, runtime.as_ref(), refusal).await
+}
+
+/// Boots with `options`, or with the saved `start_options` when there are none.
+#[tauri::command]
+async fn start_services(app: AppHandle, options: Option<StartOptions>) -> Result<(), LauncherError> {
+    let options = options.unwrap_or_else(|| app.state::<SettingsStore>().get().start_options);
+    options.validate()?;
+    let _guard = OperationGuard::acquire(&app, Operation::Starting)?;
+    let started = Instant::now();
+    let refused = |error: &LauncherError| telemetry::boot_failed(&app, error.code(), started);
+    ensure_compose_valid(&app, MessageKey::BootRefusedComposeInvalid).await.inspect_err(refused)?;
+    prepare_docker(&app, MessageKey::BootRefused).await.inspect_err(refused)?;
+    boot_sequence(&app, &options).await
+}
+
+/// Boots the stack on launch when `auto_start` is enabled.
+async fn auto_start(app: AppHandle) {
+    // Taken before the docker probe so a manual click in the meantime is rejected as busy
+    let Ok(_guard) = OperationGuard::acquire(&app, Operation::Starting) else {
+        return;
+    };
+
+    if ensure_compose_valid(&app, MessageKey::AutoStartSkippedComposeInvalid).await.is_err()
+        || prepare_docker(&app, MessageKey::AutoStartSkipped).await.is_err()
+    {
+        return;
+    }
+
+    emit_log(&app, "info", MessageKey::AutoStartBooting);
+    boot_sequence(&app, &app.state::<SettingsStore>().get().start_options).await.ok();
+}
+
+/// Brings back a stack that was running when the launcher last closed. Containers a
+/// restart policy already brought up are only reconciled, not booted again.
+async fn restore_running_state(app: AppHandle) {
+    let Ok(_guard) = OperationGuard::acquire(&app, Operation::Starting) else {
+        return;
+    };
+    if prepare_docker(&app, MessageKey::StateRestoreSkipped).await.is_err() {
+        return;
+    }
+
+    if let Ok(status) = observe_status(&resolve_project(&app)).await {
+        if status.running {
+            emit_log(&app, "info", MessageKey::StateRestoreRunning);
+            uptime::reconcile(app.clone()).await;
+            emit_status(&app, status);
+            return;
+        }
+    }
+    if ensure_compose_valid(&app, MessageKey::StateRestoreSkippedComposeInvalid).await.is_err() {
+        return;
+    }
+    emit_log(&app, "info", MessageKey::StateRestoreBooting);
+    boot_sequence(&app, &app.state::<SettingsStore>().get().start_options).await.ok();
+}
+
+async fn boot_sequence(app: &AppHandle, start: &StartOptions) -> Result<(), LauncherError> {
+    let project = resolve_project(app);
+    let control = app.state::<BootControl>();
+    let _active = control.begin();
+    app.state::<AutoRestart>().arm();
+    let started = Instant::now();
+
+    // Emit starting status
+    emit_status(app, StatusEvent::new(Phase::Starting));
+    emit_log(app, "info", MessageKey::BootStart);
+    emit_log(app, "info", MessageKey::BootOptions.with(json!({ "options": start.describe() })));
+
+    // A build that runs out of space fails only after minutes of work
+    if let Err(error) = check_disk_before_build(app, &project).await {
+        emit_log(app, "error", MessageKey::BootAborted.with(json!({ "error": error.to_string() })));
+        notify::boot_failed(app, &error.to_string());
+        telemetry::boot_failed(app, error.code(), started);
+        emit_status(app, failed_status(&project, &error).await);
+        return Err(error);
+    }
+
+    if let Err(error) = check_connectivity_before_pull(app, &project).await {
+        emit_log(app, "error", MessageKey::BootAborted.with(json!({ "error": error.to_string() })));
+        notify::boot_failed(app, &error.to_string());
+        telemetry::boot_failed(app, error.code(), started);
+        emit_status(app, failed_status(&project, &error).await);
+        return Err(error);
+    }
+
+    // An opaque compose error minutes in is all a missing GPU runtime would give otherwise
+    let preflight = doctor::run(&project, doctor::Scope::Preflight, min_free_disk_bytes(app)).await;
+    for check in preflight.checks.iter().filter(|check| check.state != doctor::CheckState::Pass) {
+        emit_log(app, "warning", MessageKey::PreflightWarning.with(json!({ "message": check.message })));
+    }
+
+    // Catch taken ports now rather than minutes into the build
+    let conflicts = ports::find_conflicts(&project).await.unwrap_or_default();
+    if !conflicts.is_empty() {
+        let error = LauncherError::PortConflict { conflicts };
+        emit_log(app, "error", MessageKey::BootAborted.with(json!({ "error": error.to_string() })));
+        notify::boot_failed(app, &error.to_string());
+        telemetry::boot_failed(app, error.code(), started);
+        emit_status(app, failed_status(&project, &error).await);
+        return Err(error);
+    }
+
+    let boot_timeout = app.state::<SettingsStore>().get().boot_timeout_secs;
+    let options = RunOptions {
+        slot: Some(&control.child),
+        idle_timeout: Some(Duration::from_secs(boot_timeout)),
+        track_progress: true,
+    };
+    control.mark_up();
+    let result = project.runtime.compose_up(app, &project, start, options).await;
+    if control.is_cancelled() {
+        abort_boot(app, &project).await;
+        return Ok(());
+    }
+
+    let exit = match result {
+        Ok(exit) => exit,
+        Err(e) => {
+            emit_log(app, "error", MessageKey::BootFailed.with(json!({ "error": e.to_string() })));
+            notify::boot_failed(app, &e.to_string());
+            telemetry::boot_failed(app, e.code(), started);
+            emit_status(app, failed_status(&project, &e).await);
+            return Err(e);
+        }
+    };
+
+    if exit.timed_out.is_some() {
+        let error = exit.error();
+        emit_log(app, "error", MessageKey::BootTimedOut.with(json!({ "secs": boot_timeout })));
+        notify::boot_failed(app, &error.to_string());
+        telemetry::boot_failed(app, "boot_timeout", started);
+        emit_status(app, failed_status(&project, &error).await);
+    } else if exit.success {
+        if report_health(app, &project, MessageKey::BootOnline).await {
+            remember_intent(app, true);
+            telemetry::boot_succeeded(app, started);
+        } else {
+            telemetry::boot_failed(app, "unhealthy", started);
+            abort_boot(app, &project).await;
+        }
+    } else {
+        emit_log(app, "error", MessageKey::BootFailed.with(json!({ "error": exit.describe() })));
+        notify::boot_failed(app, &exit.describe());
+        telemetry::boot_failed(app, "command_failed", started);
+        emit_status(app, failed_status(&project, &exit.error()).await);
+    }
+
+    Ok(())
+}
+
+/// `down -t <stop timeout>` under a watchdog. If compose itself hangs past the timeout
+/// plus a grace period it is killed, and whatever it left running is `docker kill`ed.
+async fn compose_down(app: &AppHandle, project: &Project) -> Result<(), LauncherError> {
+    let stop_timeout = app.state::<SettingsStore>().get().stop_timeout_secs;
+    let control = app.state::<StopControl>();
+    control.escalated.store(false, Ordering::SeqCst);
+
+    let watchdog_app = app.clone();
+    let watchdog = tauri::async_runtime::spawn(async move {
+        // Saturating, as a settings file edited by hand may hold any number
+        tokio::time::sleep(Duration::from_secs(stop_timeout).saturating_add(STOP_GRACE)).await;
+        let control = watchdog_app.state::<StopControl>();
+        if control.child.kill_tree() {
+            control.escalated.store(true, Ordering::SeqCst);
+            emit_log(&watchdog_app, "warning", MessageKey::ShutdownHung);
+        }
+    });
+
+    let options = RunOptions { slot: Some(&control.child), ..Default::default() };
+    let result = project.runtime.compose_down(app, project, stop_timeout, options).await;
+    watchdog.abort();
+
+    if !control.escalated.load(Ordering::SeqCst) {
+        return match result? {
+            exit if exit.success => Ok(()),
+            exit => Err(exit.error()),
+        };
+    }
+
+    let remaining: Vec<String> = compose::service_status(project)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(ServiceStatus::is_running)
+        .map(|status| status.container_id)
+        .collect();
+    if remaining.is_empty() {
+        return Ok(());
+    }
+    emit_log(app, "warning", MessageKey::ShutdownKilling.with(json!({ "count": remaining.len() })));
+    project.runtime.kill(&remaining).await
+}
+
+/// Cleans up whatever a cancelled boot left behind.
+async fn abort_boot(app: &AppHandle, project: &Project) {
+    emit_log(app, "warning", MessageKey::BootCancelled);
+    if let Err(e) = compose_down(app, project).await {
+        emit_log(app, "error", MessageKey::BootCleanupFailed.with(json!({ "error": e.to_string() })));
+    }
+
+    emit_log(app, "warning", MessageKey::BootCancelDone);
+    emit_status(app, observe_status(project).await.unwrap_or_else(|_| StatusEvent::new(Phase::Stopped)));
+}
+
+#[tauri::command]
+fn cancel_start(app: AppHandle) -> Result<(), LauncherError> {
+    let control = app.state::<BootControl>();
+    if !control.active.load(Ordering::SeqCst) {
+        return Err(LauncherError::NotStarting);
+    }
+
+    // The boot task notices the flag once compose dies and runs the cleanup itself
+    control.cancelled.store(true, Ordering::SeqCst);
+    control.child.kill_tree();
+    emit_log(&app, "warning", MessageKey::BootCancelling);
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_services(app: AppHandle) -> Result<(), LauncherError> {
+    if stop_sequence(&app).await? {
+        remember_intent(&app, false);
+    }
+    Ok(())
+}
+
+/// Takes the stack down, reporting whether it went. Quitting with `stop_on_exit`
+/// runs this from the exit hook, leaving the intent for the next launch to restore.
+async fn stop_sequence(app: &AppHandle) -> Result<bool, LauncherError> {
+    let _guard = OperationGuard::acquire(app, Operation::Stopping)?;
+    let project = resolve_project(app);
+    // A crash restart still waiting out its backoff would undo the stop
+    app.state::<AutoRestart>().disarm();
+
+    emit_status(app, StatusEvent::new(Phase::Stopping));
+    emit_log(app, "warning", MessageKey::ShutdownStart);
+
+    let result = compose_down(app, &project).await;
+    stats::stop(app);
+
+    // Whatever happened above, report the containers as they actually are
+    let stopped = result.is_ok();
+    let status = match result {
+        Ok(()) => {
+            emit_log(app, "info", MessageKey::ShutdownComplete);
+            notify::send(app, Notice::Requested, "A.B.E.L. stopped", "All services have been shut down.");
+            observe_status(&project).await.unwrap_or_else(|_| StatusEvent::new(Phase::Stopped))
+        }
+        Err(error) => {
+            emit_log(app, "error", MessageKey::ShutdownFailed.with(json!({ "error": error.to_string() })));
+            failed_status(&project, &error).await
+        }
+    };
+    emit_status(app, status);
+
+    Ok(stopped)
+}
+
+/// Records what the user last left the stack as, for `restore_state`.
+fn remember_intent(app: &AppHandle, running: bool) {
+    let store = app.state::<SettingsStore>();
+    if store.get().wanted_running != running {
+        store.update(|settings| settings.wanted_running = running).ok();
+    }
+}
+
+#[tauri::command]
+async fn restart_services(app: AppHandle) -> Result<(), LauncherError> {
+    let guard = OperationGuard::acquire(&app, Operation::Stopping)?;
+    let project = resolve_project(&app);
+
+    emit_status(&app, StatusEvent::new(Phase::Stopping).with_operation("restarting"));
+    emit_log(&app, "warning", MessageKey::RestartStart);
+
+    // Phase 1: tear down. Never attempt the boot phase on top of a failed shutdown.
+    if let Err(error) = compose_down(&app, &project).await {
+        let status = failed_status(&project, &error).await;
+        emit_log(&app, "error", MessageKey::RestartShutdownFailed.with(json!({ "error": error.to_string() })));
+        emit_status(&app, status);
+        return Ok(());
+    }
+
+    guard.advance(Operation::Starting);
+    emit_status(&app, StatusEvent::new(Phase::Starting).with_operation("restarting"));
+    emit_log(&app, "info", MessageKey::RestartBooting);
+
+    // Phase 2: boot
+    app.state::<BootControl>().mark_up();
+    let start = app.state::<SettingsStore>().get().start_options;
+    let up = project.runtime.compose_up(&app, &project, &start, RunOptions::default()).await;
+    match up {
+        Ok(exit) if exit.success => {
+            report_health(&app, &project, MessageKey::RestartOnline).await;
+        }
+        failed => {
+            let error = match failed {
+                Ok(exit) => exit.error(),
+                Err(e) => e,
+            };
+            // A failed `up` can leave part of the stack running
+            let status = failed_status(&project, &error).await;
+            emit_log(&app, "error", MessageKey::RestartBootFailed.with(json!({ "error": error.to_string() })));
+            emit_status(&app, status);
+        }
+    }
+
+    Ok(())
+}
+
+async fn validate_service(project: &Project, name: &str) -> Result<(), LauncherError> {
+    let known = compose::expected_services(project).await?;
+    if known.iter().any(|service| service == name) {
+        Ok(())
+    } else {
+        Err(LauncherError::UnknownService { service: name.to_string(), known })
+    }
+}
+
+/// Recomputes the running state from live container state and broadcasts it.
+async fn refresh_running(app: &AppHandle, project: &Project) -> bool {
+    let status = match observe_status(project).await {
+        Ok(status) => status,
+        Err(e) => failed_status(project, &e).await,
+    };
+    let running = status.running;
+    emit_status(app, status);
+    running
+}
+
+async fn service_running(project: &Project, name: &str) -> bool {
+    compose::service_status(project)
+        .await
+        .map(|statuses| statuses.iter().any(|s| s.service == name && s.is_running()))
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+async fn start_service(app: AppHandle, name: String) -> Result<(), LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let project = resolve_project(&app);
+    validate_service(&project, &name).await?;
+
+    app.emit("service-status", ServiceStatusEvent { service: name.clone(), running: false, starting: true }).ok();
+    emit_log(&app, "info", MessageKey::ServiceStarting.with(json!({ "service": name })));
+
+    let exit = run_compose(&app, &project, &["up", "-d", &name]).await;
+    let running = service_running(&project, &name).await;
+    match exit {
+        Ok(exit) if exit.success => {
+            emit_log(&app, "success", MessageKey::ServiceStarted.with(json!({ "service": name })))
+        }
+        Ok(exit) => {
+            let params = json!({ "service": name, "error": exit.describe() });
+            emit_log(&app, "error", MessageKey::ServiceStartFailed.with(params));
+        }
+        Err(e) => {
+            let params = json!({ "service": name, "error": e.to_string() });
+            emit_log(&app, "error", MessageKey::ServiceStartFailed.with(params));
+        }
+    }
+
+    app.emit("service-status", ServiceStatusEvent { service: name, running, starting: false }).ok();
+    refresh_running(&app, &project).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_service(app: AppHandle, name: String) -> Result<(), LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let project = resolve_project(&app);
+    validate_service(&project, &name).await?;
+
+    app.emit("service-status", ServiceStatusEvent { service: name.clone(), running: true, starting: true }).ok();
+    emit_log(&app, "warning", MessageKey::ServiceStopping.with(json!({ "service": name })));
+
+    let exit = run_compose(&app, &project, &["stop", &name]).await;
+    let running = service_running(&project, &name).await;
+    match exit {
+        Ok(exit) if exit.success => {
+            emit_log(&app, "info", MessageKey::ServiceStopped.with(json!({ "service": name })))
+        }
+        Ok(exit) => {
+            let params = json!({ "service": name, "error": exit.describe() });
+            emit_log(&app, "error", MessageKey::ServiceStopFailed.with(params));
+        }
+        Err(e) => {
+            let params = json!({ "service": name, "error": e.to_string() });
+            emit_log(&app, "error", MessageKey::ServiceStopFailed.with(params));
+        }
+    }
+
+    app.emit("service-status", ServiceStatusEvent { service: name, running, starting: false }).ok();
+    refresh_running(&app, &project).await;
+    Ok(())
+}
+
+/// Waits until `name` is running and past its healthcheck, within the health timeout.
+async fn wait_for_service(app: &AppHandle, project: &Project, name: &str) -> bool {
+    let timeout = Duration::from_secs(app.state::<SettingsStore>().get().health_timeout_secs);
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let status = compose::service_status(project)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|status| status.service == name);
+        match status {
+            Some(status) if status.is_running() && matches!(status.health, None | Some(Health::Healthy)) => return true,
+            Some(status) if status.health == Some(Health::Unhealthy) => return false,
+            _ if Instant::now() >= deadline => return false,
+            _ => tokio::time::sleep(HEALTH_POLL_INTERVAL).await,
+        }
+    }
+}
+
+#[tauri::command]
+async fn restart_service(app: AppHandle, name: String, recreate: Option<bool>) -> Result<(), LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let project = resolve_project(&app);
+    validate_service(&project, &name).await?;
+
+    app.emit("service-status", ServiceStatusEvent { service: name.clone(), running: true, starting: true }).ok();
+    emit_log(&app, "warning", MessageKey::ServiceRestarting.with(json!({ "service": name })));
+
+    let args: &[&str] = if recreate.unwrap_or(false) {
+        &["up", "-d", "--force-recreate", &name]
+    } else {
+        &["restart", &name]
+    };
+    let exit = run_compose(&app, &project, args).await;
+    let error = match exit {
+        Ok(exit) if exit.success => None,
+        Ok(exit) => Some(exit.error()),
+        Err(e) => Some(e),
+    };
+
+    let back = error.is_none() && wait_for_service(&app, &project, &name).await;
+    let running = service_running(&project, &name).await;
+    app.emit("service-status", ServiceStatusEvent { service: name.clone(), running, starting: false }).ok();
+
+    let status = observe_status(&project).await.unwrap_or_else(|_| StatusEvent::new(Phase::Degraded));
+    if back {
+        emit_log(&app, "success", MessageKey::ServiceRestarted.with(json!({ "service": name })));
+        emit_status(&app, status);
+        return Ok(());
+    }
+
+    let reason = match &error {
+        Some(error) => error.to_string(),
+        None => "not healthy after restart".to_string(),
+    };
+    emit_log(&app, "error", MessageKey::ServiceRestartFailed.with(json!({ "service": name, "error": reason })));
+    emit_status(&app, StatusEvent {
+        phase: if status.services_up > 0 { Phase::Degraded } else { status.phase },
+        error: Some(format!("{}: {}", name, reason)),
+        healthy: false,
+        ..status
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Rebuilds one service's image, without the layer cache when `no_cache` is set, and
+/// recreates its container from it.
+#[tauri::command]
+async fn rebuild_service(app: AppHandle, name: String, no_cache: bool) -> Result<(), LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let project = resolve_project(&app);
+    validate_service(&project, &name).await?;
+    let config = compose::config_json(&project).await?;
+    if config.pointer(&format!("/services/{}/build", name)).is_none() {
+        return Err(LauncherError::NothingToBuild { service: name });
+    }
+    check_disk_before_build(&app, &project).await?;
+
+    app.emit("service-status", ServiceStatusEvent { service: name.clone(), running: false, starting: true }).ok();
+    let rebuilding = if no_cache { MessageKey::ServiceRebuildingNoCache } else { MessageKey::ServiceRebuilding };
+    emit_log(&app, "info", rebuilding.with(json!({ "service": name })));
+
+    let mut build: Vec<&str> = vec!["build"];
+    if no_cache {
+        build.push("--no-cache");
+    }
+    build.push(&name);
+    let options = || RunOptions { track_progress: true, ..RunOptions::default() };
+    let error = match run_compose_with(&app, &project, &build, options()).await {
+        Ok(exit) if exit.success => match run_compose_with(&app, &project, &["up", "-d", &name], options()).await {
+            Ok(exit) if exit.success => None,
+            Ok(exit) => Some(exit.error()),
+            Err(e) => Some(e),
+        },
+        Ok(exit) => Some(exit.error()),
+        Err(e) => Some(e),
+    };
+
+    let back = error.is_none() && wait_for_service(&app, &project, &name).await;
+    let running = service_running(&project, &name).await;
+    app.emit("service-status", ServiceStatusEvent { service: name.clone(), running, starting: false }).ok();
+    refresh_running(&app, &project).await;
+
+    match error {
+        None if back => {
+            emit_log(&app, "success", MessageKey::ServiceRebuilt.with(json!({ "service": name })));
+            Ok(())
+        }
+        None => {
+            emit_log(&app, "warning", MessageKey::ServiceRebuiltUnhealthy.with(json!({ "service": name })));
+            Ok(())
+        }
+        Some(error) => {
+            let params = json!({ "service": name, "error": error.to_string() });
+            emit_log(&app, "error", MessageKey::ServiceRebuildFailed.with(params));
+            Err(error)
+        }
+    }
+}
+
+/// Opens a terminal window with a shell inside the service's container.
+#[tauri::command]
+async fn open_shell(app: AppHandle, service: String) -> Result<(), LauncherError> {
+    let project = resolve_project(&app);
+    validate_service(&project, &service).await?;
+
+    // A terminal pointed at a stopped container would just flash and close
+    let container = compose::service_status(&project).await?
+        .into_iter()
+        .find(|status| status.service == service && status.is_running())
+        .map(|status| status.container_id)
+        .ok_or_else(|| LauncherError::ServiceNotRunning { service: service.clone() })?;
+
+    let launch = terminal::open(&terminal::exec_command(&container))?;
+    let params = json!({ "service": service, "terminal": launch.program });
+    emit_log(&app, "info", MessageKey::ShellOpened.with(params));
+    Ok(())
+}
+
+async fn web_ui_url(project: &Project, service: &str) -> Result<String, LauncherError> {
+    let config = compose::config_json(project).await?;
+    let defined = config.pointer(&format!("/services/{}", service)).is_some();
+    if !defined {
+        let known = compose::expected_services(project).await.unwrap_or_default();
+        return Err(LauncherError::UnknownService { service: service.to_string(), known });
+    }
+    ports::service_url(&config, service).ok_or_else(|| LauncherError::NoPublishedPort { service: service.to_string() })
+}
+
+#[tauri::command]
+async fn list_models(app: AppHandle) -> Result<Vec<models::ModelInfo>, LauncherError> {
+    let service = app.state::<SettingsStore>().get().model_service;
+    models::list(&resolve_project(&app), &service).await
+}
+
+/// Downloads a model through the model service, reporting `model-pull-progress`.
+#[tauri::command]
+async fn pull_model(app: AppHandle, name: String) -> Result<(), LauncherError> {
+    let service = app.state::<SettingsStore>().get().model_service;
+    emit_log(&app, "info", MessageKey::ModelPulling.with(json!({ "model": name })));
+    match models::pull(&app, &resolve_project(&app), &service, &name).await {
+        Ok(()) => {
+            emit_log(&app, "success", MessageKey::ModelPulled.with(json!({ "model": name })));
+            Ok(())
+        }
+        Err(error) => {
+            emit_log(&app, "error", MessageKey::ModelPullFailed.with(json!({ "error": error.to_string() })));
+            Err(error)
+        }
+    }
+}
+
+/// Refused for the model the backend is configured with.
+#[tauri::command]
+async fn delete_model(app: AppHandle, name: String) -> Result<(), LauncherError> {
+    let service = app.state::<SettingsStore>().get().model_service;
+    models::delete(&resolve_project(&app), &service, &name).await?;
+    emit_log(&app, "info", MessageKey::ModelDeleted.with(json!({ "model": name })));
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_web_ui_url(app: AppHandle) -> Result<String, LauncherError> {
+    web_ui_url(&resolve_project(&app), &app.state::<SettingsStore>().get().web_ui_service).await
+}
+
+#[tauri::command]
+async fn open_web_ui(app: AppHandle) -> Result<String, LauncherError> {
+    let url = get_web_ui_url(app.clone()).await?;
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| LauncherError::open_failed(&url, e))?;
+    emit_log(&app, "info", MessageKey::WebUiOpened.with(json!({ "url": url })));
+    Ok(url)
+}
+
+#[tauri::command]
+async fn follow_logs(app: AppHandle, service: Option<String>) -> Result<(), LauncherError> {
+    let project = resolve_project(&app);
+
+    let mut args = vec!["logs", "-f", "--no-color", "--timestamps"];
+    if let Some(name) = service.as_deref() {
+        args.push(name);
+    }
+    let (mut child, mut rx) = spawn_piped(&mut compose_command(&project, &args).await?)
+        .inspect_err(|e| logfile::record_internal(&app, format!("could not spawn log follower: {}", e)))?;
+    let known = compose::expected_services(&project).await.unwrap_or_default();
+
+    emit_log(&app, "info", MessageKey::LogsAttached.with(json!({ "service": service })));
+
+    let task_app = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let mut batch = Vec::with_capacity(LOG_BATCH_SIZE);
+        while rx.recv_many(&mut batch, LOG_BATCH_SIZE).await > 0 {
+            let keep_colors = process::keep_colors(&task_app);
+            for line in batch.drain(..) {
+                // Containers log to stdout and stderr alike, so only the text says what a line is
+                let parsed = logs::parse_container_line(&line.text, &known);
+                let timestamp = parsed.timestamp.unwrap_or_else(chrono::Local::now);
+                process::emit_line(
+                    &task_app,
+                    LogEvent {
+                        level: logs::infer_level(&parsed.message).to_string(),
+                        timestamp,
+                        display_time: display_time(&timestamp),
+                        source: LogSource::Container,
+                        service: service.clone().or(parsed.service),
+                        message: parsed.message,
+                        color: line.color.filter(|_| keep_colors).map(str::to_string),
+                        key: None,
+                        params: None,
+                    },
+                );
+            }
+        }
+        child.wait().await.ok();
+        emit_log(&task_app, "warning", MessageKey::LogsEnded.with(json!({ "service": service })));
+    });
+
+    // Replace any previous follower rather than leaking its process
+    if let Some(previous) = app.state::<LogFollower>().0.lock().unwrap().replace(task) {
+        previous.abort();
+    }
+
+    Ok(())
+}
+
+/// Aborting the follower task drops its child, which `kill_on_drop` terminates.
+#[tauri::command]
+fn stop_follow_logs(app: AppHandle) -> bool {
+    let Some(task) = app.state::<LogFollower>().0.lock().unwrap().take() else {
+        return false;
+    };
+    task.abort();
+    emit_log(&app, "info", MessageKey::LogsDetached);
+    true
+}
+
+#[tauri::command]
+async fn pull_images(app: AppHandle) -> Result<PullResult, LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let project = resolve_project(&app);
+    let control = app.state::<PullControl>();
+    control.cancelled.store(false, Ordering::SeqCst);
+
+    let images = compose::pullable_images(&project).await?;
+    // Comparing image ids is the only signal that works across compose versions
+    let mut before = Vec::new();
+    for image in &images {
+        before.push(docker::image_id(image).await);
+    }
+
+    emit_log(&app, "info", MessageKey::PullStart.with(json!({ "count": images.len() })));
+    let options = RunOptions { slot: Some(&control.child), ..Default::default() };
+    let exit = run_compose_with(&app, &project, &["pull"], options).await?;
+    let cancelled = control.cancelled.load(Ordering::SeqCst);
+
+    let mut result = PullResult { pulled: Vec::new(), up_to_date: Vec::new(), failed: Vec::new(), cancelled };
+    for (image, before) in images.into_iter().zip(before) {
+        match docker::image_id(&image).await {
+            None => result.failed.push(image),
+            Some(after) if Some(&after) != before.as_ref() => result.pulled.push(image),
+            // A failed or interrupted run can't prove an unchanged image was actually checked
+            Some(_) if !exit.success => result.failed.push(image),
+            Some(_) => result.up_to_date.push(image),
+        }
+    }
+
+    let summary = json!({
+        "updated": result.pulled.join(", "),
+        "current": result.up_to_date.join(", "),
+        "failed": result.failed.join(", "),
+    });
+    if cancelled {
+        emit_log(&app, "warning", MessageKey::PullCancelled.with(summary));
+    } else if result.failed.is_empty() {
+        emit_log(&app, "success", MessageKey::PullComplete.with(summary));
+    } else {
+        emit_log(&app, "error", MessageKey::PullFailed.with(summary));
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+fn cancel_pull(app: AppHandle) -> bool {
+    let control = app.state::<PullControl>();
+    control.cancelled.store(true, Ordering::SeqCst);
+    control.child.kill_tree()
+}
+
+#[derive(Serialize)]
+struct UpdateResult {
+    recreated: Vec<String>,
+}
+
+#[tauri::command]
+async fn update_services(app: AppHandle) -> Result<UpdateResult, LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let project = resolve_project(&app);
+    let was_running = is_running(&app);
+
+    emit_status(&app, StatusEvent { running: was_running, ..StatusEvent::new(Phase::Starting).with_operation("updating") });
+    emit_log(&app, "info", MessageKey::UpdateStart);
+
+    // Phase 1: pull. On failure the running containers are left exactly as they were.
+    let pull_error = match run_compose(&app, &project, &["pull"]).await {
+        Ok(exit) if exit.success => None,
+        Ok(exit) => Some(exit.error()),
+        Err(e) => Some(e),
+    };
+    if let Some(error) = pull_error {
+        emit_log(&app, "error", MessageKey::UpdatePullFailed.with(json!({ "error": error.to_string() })));
+        emit_status(&app, failed_status(&project, &error).await);
+        return Err(error);
+    }
+
+    // Phase 2: recreate whatever changed
+    emit_log(&app, "info", MessageKey::UpdateRecreating);
+    async fn containers(project: &Project) -> Vec<(String, String)> {
+        compose::service_status(project)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|status| (status.service, status.container_id))
+            .collect()
+    }
+    let before = containers(&project).await;
+    app.state::<BootControl>().mark_up();
+    let up_error = match run_compose(&app, &project, &["up", "-d"]).await {
+        Ok(exit) if exit.success => None,
+        Ok(exit) => Some(exit.error()),
+        Err(e) => Some(e),
+    };
+    let after = containers(&project).await;
+
+    let mut recreated: Vec<String> = after
+        .into_iter()
+        .filter(|entry| !before.contains(entry))
+        .map(|(service, _)| service)
+        .collect();
+    recreated.sort();
+    recreated.dedup();
+
+    if let Some(error) = up_error {
+        let status = failed_status(&project, &error).await;
+        emit_log(&app, "error", MessageKey::UpdateRecreateFailed.with(json!({ "error": error.to_string() })));
+        emit_status(&app, status);
+        return Err(error);
+    }
+    refresh_running(&app, &project).await;
+    if recreated.is_empty() {
+        emit_log(&app, "success", MessageKey::UpdateUpToDate);
+    } else {
+        let params = json!({ "count": recreated.len(), "services": recreated.join(", ") });
+        emit_log(&app, "success", MessageKey::UpdateComplete.with(params));
+    }
+
+    Ok(UpdateResult { recreated })
+}
+
+#[derive(Serialize)]
+struct CleanupResult {
+    images_bytes: u64,
+    build_cache_bytes: u64,
+    /// Only set when volume pruning was requested.
+    volumes_bytes: Option<u64>,
+}
+
+/// Volume operations must not race the containers writing to them.
+async fn ensure_stack_stopped(app: &AppHandle, action: &str) -> Result<(), LauncherError> {
+    let any_running = compose::service_status(&resolve_project(app))
+        .await
+        .map(|statuses| statuses.iter().any(ServiceStatus::is_running))
+        .unwrap_or(false);
+    if any_running || is_running(app) {
+        return Err(LauncherError::invalid(format!(
+            "refusing to {} while A.B.E.L. services are running",
+            action
+        )));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn cleanup_docker(app: AppHandle, prune_volumes: Option<bool>) -> Result<CleanupResult, LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let prune_volumes = prune_volumes.unwrap_or(false);
+
+    if prune_volumes {
+        ensure_stack_stopped(&app, "prune volumes").await?;
+    }
+
+    let project = resolve_project(&app);
+    if let Ok(usage) = usage::disk_usage(&project).await {
+        emit_log(&app, "info", MessageKey::CleanupUsageBefore.with(json!({ "usage": usage.summary() })));
+    }
+
+    emit_log(&app, "info", MessageKey::CleanupImages);
+    let images_bytes = docker::prune(&app, &["image", "prune", "-f"]).await?;
+    emit_log(&app, "info", MessageKey::CleanupBuildCache);
+    let build_cache_bytes = docker::prune(&app, &["builder", "prune", "-f"]).await?;
+    let volumes_bytes = if prune_volumes {
+        emit_log(&app, "warning", MessageKey::CleanupVolumes);
+        Some(docker::prune(&app, &["volume", "prune", "-f"]).await?)
+    } else {
+        None
+    };
+
+    let total = images_bytes + build_cache_bytes + volumes_bytes.unwrap_or(0);
+    let reclaimed = format!("{:.1}", total as f64 / 1e6);
+    emit_log(&app, "success", MessageKey::CleanupComplete.with(json!({ "mb": reclaimed })));
+    if let Ok(usage) = usage::disk_usage(&project).await {
+        emit_log(&app, "info", MessageKey::CleanupUsageAfter.with(json!({ "usage": usage.summary() })));
+    }
+    Ok(CleanupResult { images_bytes, build_cache_bytes, volumes_bytes })
+}
+
+/// The project's volumes, empty before the first boot created any.
+#[tauri::command]
+async fn list_volumes(app: AppHandle) -> Result<Vec<volumes::VolumeInfo>, LauncherError> {
+    volumes::list(&resolve_project(&app)).await
+}
+
+/// Archives every project volume into `dest_dir`, one `.tar.gz` each.
+#[tauri::command]
+async fn backup_volumes(app: AppHandle, dest_dir: String) -> Result<Vec<backup::VolumeArchive>, LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    ensure_stack_stopped(&app, "back up volumes").await?;
+    let archives = backup::backup(&app, &resolve_project(&app), Path::new(&dest_dir)).await?;
+    let params = json!({ "count": archives.len(), "dir": dest_dir });
+    emit_log(&app, "success", MessageKey::BackupComplete.with(params));
+    Ok(archives)
+}
+
+/// Overwrites project volumes with the newest archives in `src_dir`.
+#[tauri::command]
+async fn restore_volumes(app: AppHandle, src_dir: String) -> Result<Vec<backup::VolumeArchive>, LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    ensure_stack_stopped(&app, "restore volumes").await?;
+    let restored = backup::restore(&app, &resolve_project(&app), Path::new(&src_dir)).await?;
+    emit_log(&app, "success", MessageKey::RestoreComplete.with(json!({ "count": restored.len() })));
+    Ok(restored)
+}
+
+#[tauri::command]
+async fn get_disk_usage(app: AppHandle) -> Result<usage::DiskUsage, LauncherError> {
+    let usage = usage::disk_usage(&resolve_project(&app)).await?;
+    emit_log(&app, "info", MessageKey::DiskUsage.with(json!({ "usage": usage.summary() })));
+    Ok(usage)
+}
+
+#[tauri::command]
+fn start_stats_stream(app: AppHandle) {
+    stats::start(&app);
+}
+
+#[tauri::command]
+fn stop_stats_stream(app: AppHandle) -> bool {
+    stats::stop(&app)
+}
+
+/// Backfill honours the live filter so the console stays consistent.
+#[tauri::command]
+fn get_log_history(app: AppHandle, limit: Option<usize>) -> Vec<LogEvent> {
+    let filter = app.state::<LogFilterState>().get();
+    let mut entries: Vec<LogEvent> = app
+        .state::<LogHistory>()
+        .recent(None)
+        .into_iter()
+        .filter(|event| filter.allows(event))
+        .collect();
+    if let Some(limit) = limit {
+        entries.drain(..entries.len().saturating_sub(limit));
+    }
+    entries
+}
+
+/// Matches returned by `search_logs` when no limit is given, and at most.
+const SEARCH_DEFAULT_LIMIT: usize = 200;
+const SEARCH_MAX_LIMIT: usize = 2000;
+
+#[derive(Serialize)]
+struct LogSearchResult {
+    matches: Vec<logs::LogMatch>,
+    /// Only filled by a deep search.
+    file_matches: Vec<logfile::FileMatch>,
+}
+
+/// Searches the retained history, ignoring the live filter, and with `deep` the
+/// log file and its archives too. Plain text matches case-insensitively unless
+/// `case_sensitive` is set; `level` keeps events at or above it.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn search_logs(
+    app: AppHandle,
+    query: String,
+    level: Option<LogLevel>,
+    source: Option<LogSource>,
+    regex: bool,
+    limit: Option<usize>,
+    case_sensitive: Option<bool>,
+    deep: Option<bool>,
+) -> Result<LogSearchResult, LauncherError> {
+    let search = logs::LogSearch::new(&query, regex, case_sensitive.unwrap_or(false), level, source)?;
+    let limit = limit.unwrap_or(SEARCH_DEFAULT_LIMIT).clamp(1, SEARCH_MAX_LIMIT);
+    let matches = app.state::<LogHistory>().search(&search, limit);
+    let file_matches = if deep.unwrap_or(false) {
+        // Files can run to megabytes, so they're read off the async runtime
+        let handle = app.clone();
+        tauri::async_runtime::spawn_blocking(move || logfile::search(&handle, &search, limit))
+            .await
+            .map_err(|e| LauncherError::TaskFailed { message: e.to_string() })??
+    } else {
+        Vec::new()
+    };
+    Ok(LogSearchResult { matches, file_matches })
+}
+
+/// Restricts the live console to the given sources and services; None clears a list.
+#[tauri::command]
+fn set_log_filter(app: AppHandle, sources: Option<Vec<LogSource>>, services: Option<Vec<String>>) {
+    let state = app.state::<LogFilterState>();
+    state.set(LogFilter {
+        sources: sources.unwrap_or_default(),
+        services: services.unwrap_or_default(),
+        ..state.get()
+    });
+}
+
+fn log_file_path(app: &AppHandle) -> Result<PathBuf, LauncherError> {
+    app.try_state::<logfile::LogFile>()
+        .map(|file| file.path().to_path_buf())
+        .ok_or_else(|| "the launcher log file is unavailable".into())
+}
+
+#[tauri::command]
+fn get_log_file_path(app: AppHandle) -> Result<String, LauncherError> {
+    Ok(log_file_path(&app)?.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn open_log_folder(app: AppHandle) -> Result<String, LauncherError> {
+    let path = log_file_path(&app)?;
+    let dir = path.parent().unwrap_or(&path).to_string_lossy().to_string();
+    app.opener()
+        .open_path(dir.as_str(), None::<&str>)
+        .map_err(|e| LauncherError::open_failed(&dir, e))?;
+    Ok(dir)
+}
+
+#[tauri::command]
+fn get_log_level(app: AppHandle) -> LogLevel {
+    app.state::<SettingsStore>().get().min_log_level
+}
+
+/// Lowering the level does not resend what was held back; `get_log_history`
+/// returns it under the new filter.
+#[tauri::command]
+fn set_log_level(app: AppHandle, level: LogLevel) -> Result<(), LauncherError> {
+    app.state::<LogFilterState>().set_min_level(level);
+    app.state::<SettingsStore>()
+        .update(|settings| settings.min_log_level = level)
+}
+
+#[tauri::command]
+fn clear_log_history(app: AppHandle) {
+    app.state::<LogHistory>().clear();
+}
+
+/// Writes the console history to `path`, or to a file picked in a save dialog.
+/// Returns the written path, or None when the dialog was dismissed.
+#[tauri::command]
+async fn export_logs(app: AppHandle, path: Option<String>) -> Result<Option<String>, LauncherError> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .add_filter("Log files", &["log", "txt"])
+                .set_file_name("abel-launcher.log")
+                .blocking_save_file();
+            match picked {
+                Some(picked) => picked.into_path().map_err(|e| LauncherError::DialogFailed { message: e.to_string() })?,
+                None => return Ok(None),
+            }
+        }
+    };
+
+    logs::export(&app, &app.state::<LogHistory>(), &path).await?;
+    emit_log(&app, "info", MessageKey::LogExported.with(json!({ "path": path.display().to_string() })));
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Writes the history to `path` as NDJSON for other tools, optionally only the
+/// events after `since` (RFC3339). Returns how many events were written.
+#[tauri::command]
+async fn export_logs_json(app: AppHandle, path: String, since: Option<String>) -> Result<usize, LauncherError> {
+    let since = since.as_deref().map(logs::parse_since).transpose()?;
+    let path = PathBuf::from(path);
+    let handle = app.clone();
+    let target = path.clone();
+    let written = tauri::async_runtime::spawn_blocking(move || {
+        logs::export_json(&handle.state::<LogHistory>(), &target, since)
+    })
+    .await
+    .map_err(|e| LauncherError::TaskFailed { message: e.to_string() })??;
+    let params = json!({ "count": written, "path": path.display().to_string() });
+    emit_log(&app, "info", MessageKey::LogHistoryExported.with(params));
+    Ok(written)
+}
+
+/// Zips everything a bug report needs into one file at `path`, or at a file picked
+/// in a save dialog, with secrets masked. None when the dialog was dismissed.
+#[tauri::command]
+async fn export_diagnostics(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<Option<diagnostics::DiagnosticsBundle>, LauncherError> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let name = format!("abel-diagnostics-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+            let picked = app
+                .dialog()
+                .file()
+                .add_filter("Zip archives", &["zip"])
+                .set_file_name(&name)
+                .blocking_save_file();
+            match picked {
+                Some(picked) => picked.into_path().map_err(|e| LauncherError::DialogFailed { message: e.to_string() })?,
+                None => return Ok(None),
+            }
+        }
+    };
+
+    emit_log(&app, "info", MessageKey::DiagnosticsCollecting);
+    let bundle = diagnostics::export(&app, &resolve_project(&app), &path, min_free_disk_bytes(&app)).await?;
+    let params = json!({ "path": bundle.path, "bytes": bundle.size });
+    emit_log(&app, "info", MessageKey::DiagnosticsExported.with(params));
+    Ok(Some(bundle))
+}
+
+#[tauri::command]
+fn read_env(app: AppHandle) -> Result<Vec<env::EnvLine>, LauncherError> {
+    env::read(&resolve_project_dir(&app))
+}
+
+#[tauri::command]
+fn write_env(app: AppHandle, entries: Vec<env::EnvLine>) -> Result<(), LauncherError> {
+    env::write(&resolve_project_dir(&app), &entries)?;
+    // Values may be secrets, so only the fact of the change is logged
+    emit_log(&app, "info", MessageKey::EnvFileSaved);
+    Ok(())
+}
+
+/// Stores `value` in the OS keyring. A `.env` line `KEY=@keyring` then takes it at boot.
+#[tauri::command]
+fn set_secret(app: AppHandle, key: String, value: String) -> Result<(), LauncherError> {
+    let key = key.trim().to_string();
+    if !env::valid_key(&key) {
+        return Err(LauncherError::invalid(format!("invalid variable name: {:?}", key)));
+    }
+    secrets::set(&key, &value)?;
+    app.state::<SettingsStore>().update(|settings| {
+        if !settings.secret_keys.contains(&key) {
+            settings.secret_keys.push(key.clone());
+            settings.secret_keys.sort();
+        }
+    })?;
+    emit_log(&app, "info", MessageKey::SecretStored.with(json!({ "key": key })));
+    Ok(())
+}
+
+/// Names only; values never leave the keyring except to compose.
+#[tauri::command]
+fn list_secrets(app: AppHandle) -> Vec<String> {
+    app.state::<SettingsStore>().get().secret_keys
+}
+
+/// Removes `key` from the keyring. With `restore_plaintext`, a `.env` line pointing
+/// at it gets the value back in plain text first, so the stack keeps working.
+#[tauri::command]
+fn delete_secret(app: AppHandle, key: String, restore_plaintext: Option<bool>) -> Result<(), LauncherError> {
+    if restore_plaintext.unwrap_or(false) {
+        let value = secrets::get(&key)?;
+        if secrets::write_plaintext(&resolve_project_dir(&app), &key, &value)? {
+            emit_log(&app, "warning", MessageKey::SecretWrittenBack.with(json!({ "key": key })));
+        }
+    }
+    secrets::delete(&key)?;
+    app.state::<SettingsStore>()
+        .update(|settings| settings.secret_keys.retain(|stored| *stored != key))?;
+    emit_log(&app, "info", MessageKey::SecretRemoved.with(json!({ "key": key })));
+    Ok(())
+}
+
+/// The first-run checklist, checked against the machine and project as they are now.
+#[tauri::command]
+async fn get_setup_state(app: AppHandle) -> setup::SetupState {
+    setup::state(&app).await
+}
+
+/// Completes a setup step that needs the launcher to act, e.g. writing the required
+/// `.env` keys or pulling a model, and returns the checklist after it.
+#[tauri::command]
+async fn complete_setup_step(
+    app: AppHandle,
+    step_id: setup::StepId,
+    payload: Option<serde_json::Value>,
+) -> Result<setup::SetupState, LauncherError> {
+    setup::complete(&app, step_id, payload.unwrap_or_default()).await
+}
+
+/// Copies the bundled compose file, `.env.example` and config into the app data
+/// directory, repairing missing or outdated files. User edits, `.env` above all,
+/// are only replaced with `force`, and kept as `.bak` when they are.
+#[tauri::command]
+fn install_project_files(app: AppHandle, force: bool) -> Result<install::InstallReport, LauncherError> {
+    // Held so the compose file isn't replaced under a boot that is reading it
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let report = install::install(&app, force)?;
+    install::log_report(&app, &report);
+    Ok(report)
+}
+
+#[tauri::command]
+fn get_project_dir(app: AppHandle) -> String {
+    resolve_project_dir(&app)
+}
+
+#[tauri::command]
+fn get_project_dir_info(app: AppHandle) -> settings::ProjectDirInfo {
+    project_dir_info(&app)
+}
+
+#[tauri::command]
+fn set_project_dir(app: AppHandle, path: String) -> Result<String, LauncherError> {
+    let dir = settings::validate_project_dir(&path)?.to_string_lossy().to_string();
+    app.state::<SettingsStore>()
+        .update(|settings| settings.set_current_project_dir(dir.clone()))?;
+
+    emit_log(&app, "info", MessageKey::ProjectDirSet.with(json!({ "dir": dir })));
+    events::resubscribe(&app);
+    let info = project_dir_info(&app);
+    if info.source != settings::ProjectDirSource::Setting {
+        emit_log(&app, "warning", MessageKey::ProjectDirOverridden.with(json!({ "dir": info.path })));
+    }
+    Ok(dir)
+}
+
+#[derive(Serialize)]
+struct EnvironmentList {
+    environments: Vec<settings::Environment>,
+    active: Option<String>,
+}
+
+#[tauri::command]
+fn list_environments(app: AppHandle) -> EnvironmentList {
+    let settings = app.state::<SettingsStore>().get();
+    EnvironmentList {
+        active: settings.active_environment().map(|environment| environment.name.clone()),
+        environments: settings.environments,
+    }
+}
+
+#[tauri::command]
+fn add_environment(
+    app: AppHandle,
+    name: String,
+    project_dir: String,
+    compose_files: Option<Vec<String>>,
+    profiles: Option<Vec<String>>,
+) -> Result<settings::Environment, LauncherError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(LauncherError::invalid("environment name must not be empty"));
+    }
+    let settings = app.state::<SettingsStore>().get();
+    if settings.environments.iter().any(|environment| environment.name.eq_ignore_ascii_case(&name)) {
+        return Err(LauncherError::invalid(format!("environment already exists: {}", name)));
+    }
+    let dir = settings::validate_project_dir(&project_dir)?;
+    let compose_files = compose_files.unwrap_or_default();
+    if let Some(missing) = compose_files.iter().find(|file| !dir.join(file).is_file()) {
+        return Err(LauncherError::invalid(format!("compose file does not exist: {}", missing)));
+    }
+
+    let environment = settings::Environment {
+        name,
+        project_dir: dir.to_string_lossy().to_string(),
+        compose_files,
+        profiles: profiles.unwrap_or_default(),
+    };
+    let added = environment.clone();
+    app.state::<SettingsStore>()
+        .update(|settings| settings.environments.push(added))?;
+    let params = json!({ "name": environment.name, "dir": environment.project_dir });
+    emit_log(&app, "info", MessageKey::EnvironmentAdded.with(params));
+    Ok(environment)
+}
+
+/// Holds an operation while the stack under the launcher changes, refusing while the
+/// current one has anything up.
+async fn guard_environment_switch(app: &AppHandle) -> Result<OperationGuard, LauncherError> {
+    let guard = OperationGuard::acquire(app, Operation::Updating)?;
+    let any_running = compose::service_status(&resolve_project(app))
+        .await
+        .map(|statuses| statuses.iter().any(ServiceStatus::is_running))
+        .unwrap_or(false);
+    if any_running || is_running(app) {
+        return Err(LauncherError::Busy { current_operation: Operation::Running });
+    }
+    Ok(guard)
+}
+
+/// Reports the stack the launcher now points at.
+async fn environment_switched(app: &AppHandle) {
+    events::resubscribe(app);
+    uptime::reconcile(app.clone()).await;
+    let status = observe_status(&resolve_project(app))
+        .await
+        .unwrap_or_else(|_| StatusEvent::new(Phase::Stopped));
+    emit_status(app, status);
+}
+
+#[tauri::command]
+async fn remove_environment(app: AppHandle, name: String) -> Result<(), LauncherError> {
+    let settings = app.state::<SettingsStore>().get();
+    if !settings.environments.iter().any(|environment| environment.name == name) {
+        return Err(LauncherError::invalid(format!("unknown environment: {}", name)));
+    }
+    let was_active = settings.active_environment.as_deref() == Some(name.as_str());
+    let guard = if was_active { Some(guard_environment_switch(&app).await?) } else { None };
+
+    app.state::<SettingsStore>().update(|settings| {
+        settings.environments.retain(|environment| environment.name != name);
+        if was_active {
+            settings.active_environment = None;
+        }
+    })?;
+    emit_log(&app, "info", MessageKey::EnvironmentRemoved.with(json!({ "name": name })));
+    if was_active {
+        drop(guard);
+        environment_switched(&app).await;
+    }
+    Ok(())
+}
+
+/// Points every compose command at `name`, or back at the plain settings with none.
+#[tauri::command]
+async fn set_active_environment(app: AppHandle, name: Option<String>) -> Result<(), LauncherError> {
+    let settings = app.state::<SettingsStore>().get();
+    if let Some(name) = &name {
+        if !settings.environments.iter().any(|environment| &environment.name == name) {
+            return Err(LauncherError::invalid(format!("unknown environment: {}", name)));
+        }
+    }
+    if settings.active_environment == name {
+        return Ok(());
+    }
+
+    let guard = guard_environment_switch(&app).await?;
+    app.state::<SettingsStore>()
+        .update(|settings| settings.active_environment = name.clone())?;
+    emit_log(&app, "info", MessageKey::EnvironmentActive.with(json!({ "name": name })));
+    drop(guard);
+    environment_switched(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+fn open_project_dir(app: AppHandle, subpath: Option<String>, create: Option<bool>) -> Result<String, LauncherError> {
+    let dir = settings::project_subpath(&resolve_project_dir(&app), subpath.as_deref())?;
+    if create.unwrap_or(false) {
+        std::fs::create_dir_all(&dir)?;
+    }
+    if !dir.is_dir() {
+        return Err(LauncherError::invalid(format!("directory does not exist: {}", dir.display())));
+    }
+
+    let dir = dir.to_string_lossy().to_string();
+    app.opener()
+        .open_path(dir.as_str(), None::<&str>)
+        .map_err(|e| LauncherError::open_failed(&dir, e))?;
+    emit_log(&app, "info", MessageKey::ProjectDirOpened.with(json!({ "path": dir })));
+    Ok(dir)
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ConfigFile {
+    Compose,
+    Env,
+}
 
-static IS_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Every file whose edits only apply after the stack is brought up again.
+fn config_file_paths(app: &AppHandle) -> Vec<PathBuf> {
+    let project = resolve_project(app);
+    let mut paths = project.compose_file_paths();
+    paths.push(Path::new(&project.dir).join(env::ENV_FILE));
+    paths
+}
 
-#[derive(Clone, Serialize)]
-struct LogEvent {
-    message: String,
-    level: String,
-    timestamp: String,
+#[tauri::command]
+fn open_config_file(app: AppHandle, kind: ConfigFile) -> Result<String, LauncherError> {
+    let project = resolve_project(&app);
+    let path = match kind {
+        ConfigFile::Compose => project
+            .compose_file_paths()
+            .into_iter()
+            .next()
+            .ok_or_else(|| LauncherError::ComposeFileNotFound { searched_dir: project.dir.clone() })?,
+        ConfigFile::Env => Path::new(&project.dir).join(env::ENV_FILE),
+    };
+    // An editor asked to open a missing file may refuse instead of creating it
+    if !path.exists() {
+        std::fs::File::create(&path)?;
+    }
+
+    let path = path.to_string_lossy().to_string();
+    app.opener()
+        .open_path(path.as_str(), None::<&str>)
+        .map_err(|e| LauncherError::open_failed(&path, e))?;
+    emit_log(&app, "info", MessageKey::ProjectFileOpened.with(json!({ "path": path })));
+    Ok(path)
 }
 
-#[derive(Clone, Serialize)]
-struct StatusEvent {
-    running: bool,
-    starting: bool,
+/// Config files changed since the stack was last brought up by this launcher.
+/// Empty when it hasn't been yet, since there is nothing to compare against.
+#[tauri::command]
+fn config_files_modified_since_start(app: AppHandle) -> Vec<String> {
+    let Some(last_up) = *app.state::<BootControl>().last_up.lock().unwrap() else {
+        return Vec::new();
+    };
+    config_file_paths(&app)
+        .into_iter()
+        .filter(|path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified > last_up)
+        })
+        .map(|path| path.to_string_lossy().to_string())
+        .collect()
+}
+
+#[tauri::command]
+fn get_compose_files(app: AppHandle) -> Vec<String> {
+    app.state::<SettingsStore>().get().current_compose_files()
 }
 
-fn get_timestamp() -> String {
-    chrono::Local::now().format("%H:%M:%S.%3f").to_string()
+/// An empty list restores compose's own file lookup.
+#[tauri::command]
+fn set_compose_files(app: AppHandle, files: Vec<String>) -> Result<(), LauncherError> {
+    let dir = resolve_project_dir(&app);
+    for file in &files {
+        if !Path::new(&dir).join(file).is_file() {
+            return Err(LauncherError::invalid(format!("compose file does not exist: {}", file)));
+        }
+    }
+    app.state::<SettingsStore>()
+        .update(|settings| settings.set_current_compose_files(files.clone()))?;
+
+    if files.is_empty() {
+        emit_log(&app, "info", MessageKey::ComposeFilesReset);
+    } else {
+        emit_log(&app, "info", MessageKey::ComposeFilesSet.with(json!({ "files": files.join(", ") })));
+    }
+    Ok(())
 }
 
-fn get_project_dir() -> String {
-    std::env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|| ".".to_string())
+#[tauri::command]
+async fn list_profiles(app: AppHandle) -> Result<Vec<String>, LauncherError> {
+    compose::profiles(&resolve_project(&app)).await
 }
 
 #[tauri::command]
-async fn check_docker() -> Result<bool, String> {
-    let output = Command::new("docker")
-        .arg("info")
-        .output()
-        .map_err(|e| e.to_string())?;
+fn get_active_profiles(app: AppHandle) -> Vec<String> {
+    app.state::<SettingsStore>().get().current_profiles()
+}
+
+/// Whether running containers are still on the old limits.
+#[derive(Serialize)]
+struct LimitsChange {
+    restart_required: bool,
+}
 
-    Ok(output.status.success())
+/// Logs the limits now set, described by `limits`, or None when they were cleared.
+async fn limits_changed(app: &AppHandle, project: &Project, limits: Option<serde_json::Value>) -> LimitsChange {
+    let restart_required = compose::service_status(project)
+        .await
+        .map(|statuses| statuses.iter().any(ServiceStatus::is_running))
+        .unwrap_or(false);
+    let (level, key) = match (&limits, restart_required) {
+        (Some(_), true) => ("warning", MessageKey::LimitsSetRestart),
+        (Some(_), false) => ("info", MessageKey::LimitsSet),
+        (None, true) => ("warning", MessageKey::LimitsClearedRestart),
+        (None, false) => ("info", MessageKey::LimitsCleared),
+    };
+    emit_log(app, level, key.with(limits.unwrap_or_default()));
+    LimitsChange { restart_required }
 }
 
 #[tauri::command]
-async fn check_status(app: tauri::AppHandle) -> Result<bool, String> {
-    let project_dir = get_project_dir();
+fn get_resource_limits(app: AppHandle) -> Result<std::collections::BTreeMap<String, limits::ResourceLimits>, LauncherError> {
+    limits::get(&resolve_project_dir(&app))
+}
 
-    let output = Command::new("docker-compose")
-        .args(["ps", "-q"])
-        .current_dir(&project_dir)
-        .output()
-        .map_err(|e| e.to_string())?;
+/// Caps `service` through the launcher's override file; with neither `cpus` nor
+/// `memory` its limits are removed. The user's compose files are never written.
+#[tauri::command]
+async fn set_resource_limits(
+    app: AppHandle,
+    service: String,
+    cpus: Option<f64>,
+    memory: Option<String>,
+) -> Result<LimitsChange, LauncherError> {
+    // Held so the override isn't rewritten under a boot that is reading it
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let project = resolve_project(&app);
+    validate_service(&project, &service).await?;
+    let limits = limits::ResourceLimits { cpus, memory: memory.filter(|memory| !memory.trim().is_empty()) };
+    limits::set(&project.dir, &service, &limits).await?;
 
-    let running = !String::from_utf8_lossy(&output.stdout).trim().is_empty();
-    IS_RUNNING.store(running, Ordering::SeqCst);
+    let described = json!({ "service": service, "cpus": limits.cpus, "memory": limits.memory });
+    Ok(limits_changed(&app, &project, Some(described)).await)
+}
 
-    Ok(running)
+#[tauri::command]
+async fn clear_resource_limits(app: AppHandle) -> Result<LimitsChange, LauncherError> {
+    let _guard = OperationGuard::acquire(&app, Operation::Updating)?;
+    let project = resolve_project(&app);
+    limits::clear(&project.dir)?;
+    Ok(limits_changed(&app, &project, None).await)
+}
+
+#[derive(Serialize)]
+struct ProfileChange {
+    /// Running containers keep the old profile set until the stack is restarted.
+    restart_required: bool,
 }
 
 #[tauri::command]
-async fn start_services(app: tauri::AppHandle) -> Result<(), String> {
-    let project_dir = get_project_dir();
+async fn set_active_profiles(app: AppHandle, profiles: Vec<String>) -> Result<ProfileChange, LauncherError> {
+    let project = resolve_project(&app);
+    let known = compose::profiles(&project).await?;
+    if let Some(unknown) = profiles.iter().find(|profile| !known.contains(profile)) {
+        return Err(LauncherError::invalid(format!("unknown profile: {}", unknown)));
+    }
 
-    // Emit starting status
-    app.emit("status", StatusEvent { running: false, starting: true }).ok();
-    app.emit("log", LogEvent {
-        message: "INITIATING BOOT SEQUENCE...".to_string(),
-        level: "info".to_string(),
-        timestamp: get_timestamp(),
-    }).ok();
-
-    let output = Command::new("docker-compose")
-        .args(["up", "-d", "--build"])
-        .current_dir(&project_dir)
-        .output()
-        .map_err(|e| e.to_string())?;
-
-    if output.status.success() {
-        IS_RUNNING.store(true, Ordering::SeqCst);
-        app.emit("log", LogEvent {
-            message: "ALL SYSTEMS OPERATIONAL - A.B.E.L. ONLINE".to_string(),
-            level: "success".to_string(),
-            timestamp: get_timestamp(),
-        }).ok();
-        app.emit("status", StatusEvent { running: true, starting: false }).ok();
+    // Checked against the old profile set, which is what is actually running
+    let restart_required = compose::service_status(&project)
+        .await
+        .map(|statuses| statuses.iter().any(ServiceStatus::is_running))
+        .unwrap_or(false)
+        || is_running(&app);
+    app.state::<SettingsStore>()
+        .update(|settings| settings.set_current_profiles(profiles.clone()))?;
+
+    let label = profiles.join(", ");
+    if restart_required {
+        emit_log(&app, "warning", MessageKey::ProfilesSetRestart.with(json!({ "profiles": label })));
     } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        app.emit("log", LogEvent {
-            message: format!("BOOT SEQUENCE FAILED: {}", error),
-            level: "error".to_string(),
-            timestamp: get_timestamp(),
-        }).ok();
-        app.emit("status", StatusEvent { running: false, starting: false }).ok();
+        emit_log(&app, "info", MessageKey::ProfilesSet.with(json!({ "profiles": label })));
     }
+    Ok(ProfileChange { restart_required })
+}
 
-    Ok(())
+#[tauri::command]
+fn get_settings(app: AppHandle) -> settings::Settings {
+    app.state::<SettingsStore>().get()
+}
+
+/// Sets the fields in `patch`, each checked the way its own command would check it.
+/// Any invalid field fails the whole patch. Returns the settings as saved.
+#[tauri::command]
+fn update_settings(
+    app: AppHandle,
+    patch: serde_json::Map<String, serde_json::Value>,
+) -> Result<settings::Settings, LauncherError> {
+    let store = app.state::<SettingsStore>();
+    let next = store.get().patched(&patch, &resolve_project_dir(&app))?;
+    let keys: Vec<String> = patch.keys().cloned().collect();
+    if keys.is_empty() {
+        return Ok(next);
+    }
+
+    // Fields that act on something besides the file take effect first, so a failure saves nothing
+    if patch.contains_key("toggle_hotkey") {
+        hotkey::register(&app, next.toggle_hotkey.as_deref())?;
+    }
+    if patch.contains_key("status_api_port") {
+        statusapi::apply(&app, next.status_api_port)?;
+    }
+    if patch.contains_key("min_log_level") {
+        app.state::<LogFilterState>().set_min_level(next.min_log_level);
+    }
+    if patch.contains_key("proxy") {
+        proxy::configure(&next.proxy);
+    }
+    if patch.contains_key("container_runtime") {
+        runtime::configure(&app, next.container_runtime);
+        events::resubscribe(&app);
+    }
+    if patch.contains_key("engine_api") {
+        engine::configure(next.engine_api);
+        events::resubscribe(&app);
+    }
+    // Only the patched fields, so a concurrent write to any other one isn't lost
+    store.update(|settings| settings.take_fields(&next, &keys))?;
+    emit_log(&app, "info", MessageKey::SettingsUpdated.with(json!({ "keys": keys.join(", ") })));
+    Ok(store.get())
+}
+
+#[tauri::command]
+fn get_start_options(app: AppHandle) -> StartOptions {
+    app.state::<SettingsStore>().get().start_options
+}
+
+/// What a plain start boots with, e.g. without `build` to skip rebuilding every time.
+#[tauri::command]
+fn set_start_options(app: AppHandle, options: StartOptions) -> Result<(), LauncherError> {
+    options.validate()?;
+    emit_log(&app, "info", MessageKey::BootOptionsSet.with(json!({ "options": options.describe() })));
+    app.state::<SettingsStore>()
+        .update(|settings| settings.start_options = options)
+}
+
+#[tauri::command]
+fn get_health_timeout(app: AppHandle) -> u64 {
+    app.state::<SettingsStore>().get().health_timeout_secs
+}
+
+#[tauri::command]
+fn set_health_timeout(app: AppHandle, secs: u64) -> Result<(), LauncherError> {
+    if secs == 0 || secs > settings::MAX_TIMEOUT_SECS {
+        let message = format!("health timeout must be between 1 and {} seconds", settings::MAX_TIMEOUT_SECS);
+        return Err(LauncherError::invalid(message));
+    }
+    app.state::<SettingsStore>()
+        .update(|settings| settings.health_timeout_secs = secs)
+}
+
+#[tauri::command]
+fn get_boot_timeout(app: AppHandle) -> u64 {
+    app.state::<SettingsStore>().get().boot_timeout_secs
+}
+
+#[tauri::command]
+fn set_boot_timeout(app: AppHandle, secs: u64) -> Result<(), LauncherError> {
+    if secs == 0 {
+        return Err(LauncherError::invalid("boot timeout must be at least 1 second"));
+    }
+    app.state::<SettingsStore>()
+        .update(|settings| settings.boot_timeout_secs = secs)
+}
+
+#[tauri::command]
+fn get_stop_timeout(app: AppHandle) -> u64 {
+    app.state::<SettingsStore>().get().stop_timeout_secs
+}
+
+#[tauri::command]
+fn set_stop_timeout(app: AppHandle, secs: u64) -> Result<(), LauncherError> {
+    if secs > settings::MAX_TIMEOUT_SECS {
+        let message = format!("stop timeout must be at most {} seconds", settings::MAX_TIMEOUT_SECS);
+        return Err(LauncherError::invalid(message));
+    }
+    app.state::<SettingsStore>()
+        .update(|settings| settings.stop_timeout_secs = secs)
+}
+
+#[tauri::command]
+fn get_poll_interval(app: AppHandle) -> u64 {
+    app.state::<SettingsStore>().get().poll_interval_secs
+}
+
+#[tauri::command]
+fn set_poll_interval(app: AppHandle, secs: u64) -> Result<(), LauncherError> {
+    if secs == 0 {
+        return Err(LauncherError::invalid("poll interval must be at least 1 second"));
+    }
+    app.state::<SettingsStore>()
+        .update(|settings| settings.poll_interval_secs = secs)
+}
+
+#[tauri::command]
+fn get_auto_start(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().auto_start
+}
+
+#[tauri::command]
+fn set_auto_start(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.auto_start = enabled)
+}
+
+/// Read back from the platform's registration, not from settings.
+#[tauri::command]
+async fn get_launch_at_login() -> Result<bool, LauncherError> {
+    login::is_enabled().await
+}
+
+/// Returns whether logging in now starts the launcher, as read back afterwards.
+#[tauri::command]
+async fn set_launch_at_login(app: AppHandle, enabled: bool) -> Result<bool, LauncherError> {
+    let registered = login::set_enabled(enabled).await?;
+    emit_log(
+        &app,
+        "info",
+        if registered { MessageKey::LaunchAtLoginEnabled } else { MessageKey::LaunchAtLoginDisabled },
+    );
+    Ok(registered)
+}
+
+#[tauri::command]
+fn get_on_close(app: AppHandle) -> tray::CloseAction {
+    app.state::<SettingsStore>().get().on_close
+}
+
+#[tauri::command]
+fn set_on_close(app: AppHandle, action: tray::CloseAction) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.on_close = action)
+}
+
+#[tauri::command]
+fn get_auto_launch_docker(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().auto_launch_docker
+}
+
+#[tauri::command]
+fn set_auto_launch_docker(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.auto_launch_docker = enabled)
+}
+
+#[tauri::command]
+fn get_ansi_colors(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().ansi_colors
+}
+
+#[tauri::command]
+fn set_ansi_colors(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.ansi_colors = enabled)
 }
 
 #[tauri::command]
-async fn stop_services(app: tauri::AppHandle) -> Result<(), String> {
-    let project_dir = get_project_dir();
+fn get_notifications_enabled(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().notifications_enabled
+}
+
+#[tauri::command]
+fn set_notifications_enabled(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.notifications_enabled = enabled)
+}
 
-    app.emit("status", StatusEvent { running: true, starting: true }).ok();
-    app.emit("log", LogEvent {
-        message: "INITIATING SHUTDOWN SEQUENCE...".to_string(),
-        level: "warning".to_string(),
-        timestamp: get_timestamp(),
-    }).ok();
+#[tauri::command]
+fn get_notify_when_focused(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().notify_when_focused
+}
 
-    let output = Command::new("docker-compose")
-        .args(["down"])
-        .current_dir(&project_dir)
-        .output()
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+fn set_notify_when_focused(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.notify_when_focused = enabled)
+}
 
-    IS_RUNNING.store(false, Ordering::SeqCst);
+#[tauri::command]
+fn get_proxy(app: AppHandle) -> proxy::ProxySettings {
+    app.state::<SettingsStore>().get().proxy
+}
 
-    if output.status.success() {
-        app.emit("log", LogEvent {
-            message: "SHUTDOWN COMPLETE - ENTERING STANDBY".to_string(),
-            level: "info".to_string(),
-            timestamp: get_timestamp(),
-        }).ok();
+/// Applies to every docker and compose process started afterwards. Empty fields set
+/// no variables at all.
+#[tauri::command]
+fn set_proxy(app: AppHandle, proxy: proxy::ProxySettings) -> Result<(), LauncherError> {
+    proxy.validate()?;
+    app.state::<SettingsStore>()
+        .update(|settings| settings.proxy = proxy.clone())?;
+    proxy::configure(&proxy);
+    if proxy.http.trim().is_empty() && proxy.https.trim().is_empty() {
+        emit_log(&app, "info", MessageKey::ProxyCleared);
     } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        app.emit("log", LogEvent {
-            message: format!("SHUTDOWN ERROR: {}", error),
-            level: "error".to_string(),
-            timestamp: get_timestamp(),
-        }).ok();
+        emit_log(&app, "info", MessageKey::ProxySaved);
+    }
+    Ok(())
+}
+
+/// Tries `proxy`, or the saved settings without one, against Docker Hub's registry.
+#[tauri::command]
+async fn test_proxy(app: AppHandle, proxy: Option<proxy::ProxySettings>) -> Result<proxy::ProxyTest, LauncherError> {
+    let proxy = proxy.unwrap_or_else(|| app.state::<SettingsStore>().get().proxy);
+    proxy::test(&proxy).await
+}
+
+#[tauri::command]
+fn get_hotkey(app: AppHandle) -> Option<String> {
+    app.state::<SettingsStore>().get().toggle_hotkey
+}
+
+/// Registers `accelerator` as the toggle shortcut; null or empty unregisters it. A
+/// combo another app holds fails with `hotkey_unavailable` and changes nothing.
+#[tauri::command]
+fn set_hotkey(app: AppHandle, accelerator: Option<String>) -> Result<(), LauncherError> {
+    let accelerator = accelerator.map(|accelerator| accelerator.trim().to_string()).filter(|a| !a.is_empty());
+    hotkey::register(&app, accelerator.as_deref())?;
+    app.state::<SettingsStore>()
+        .update(|settings| settings.toggle_hotkey = accelerator.clone())?;
+    match accelerator {
+        Some(accelerator) => {
+            emit_log(&app, "info", MessageKey::HotkeySet.with(json!({ "hotkey": accelerator })))
+        }
+        None => emit_log(&app, "info", MessageKey::HotkeyCleared),
+    }
+    Ok(())
+}
+
+/// The failure the status events report as `last_error`, for a window opened after it.
+#[tauri::command]
+fn get_last_error() -> Option<LastError> {
+    LAST_ERROR.lock().unwrap().clone()
+}
+
+#[tauri::command]
+fn get_status_api(app: AppHandle) -> statusapi::StatusApiInfo {
+    statusapi::info(&app)
+}
+
+/// Serves the status API on 127.0.0.1:`port`, or shuts it down with null. A port in
+/// use fails with `status_api_unavailable` and leaves the server where it was.
+#[tauri::command]
+fn set_status_api_port(app: AppHandle, port: Option<u16>) -> Result<(), LauncherError> {
+    if port == Some(0) {
+        return Err(LauncherError::invalid("the status API needs a fixed port"));
+    }
+    statusapi::apply(&app, port)?;
+    app.state::<SettingsStore>()
+        .update(|settings| settings.status_api_port = port)
+}
+
+/// Null or empty removes the token, which refuses `POST /start` and `POST /stop`.
+#[tauri::command]
+fn set_status_api_token(app: AppHandle, token: Option<String>) -> Result<(), LauncherError> {
+    let token = token.map(|token| token.trim().to_string()).filter(|token| !token.is_empty());
+    app.state::<SettingsStore>()
+        .update(|settings| settings.status_api_token = token)
+}
+
+#[tauri::command]
+fn get_schedule(app: AppHandle) -> Option<schedule::Schedule> {
+    app.state::<SettingsStore>().get().schedule
+}
+
+/// Null clears the schedule. Takes effect at the next boundary, not immediately.
+#[tauri::command]
+fn set_schedule(app: AppHandle, schedule: Option<schedule::Schedule>) -> Result<(), LauncherError> {
+    if let Some(schedule) = &schedule {
+        schedule.validate()?;
     }
+    let message = match &schedule {
+        Some(schedule) if schedule.enabled => MessageKey::ScheduleSet.with(json!({
+            "start": schedule.start,
+            "stop": schedule.stop,
+            "weekdays": schedule.weekdays.join(", "),
+        })),
+        _ => MessageKey::ScheduleDisabled.into(),
+    };
+    app.state::<SettingsStore>()
+        .update(|settings| settings.schedule = schedule)?;
+    emit_log(&app, "info", message);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_restore_state(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().restore_state
+}
+
+#[tauri::command]
+fn set_restore_state(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.restore_state = enabled)
+}
+
+#[tauri::command]
+fn get_auto_restart(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().auto_restart
+}
+
+#[tauri::command]
+fn set_auto_restart(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.auto_restart = enabled)
+}
+
+#[tauri::command]
+fn get_telemetry_status(app: AppHandle) -> telemetry::TelemetryStatus {
+    telemetry::status(&app)
+}
 
-    app.emit("status", StatusEvent { running: false, starting: false }).ok();
+/// Opting out also drops whatever was queued and not yet sent.
+#[tauri::command]
+fn set_telemetry_enabled(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.telemetry_enabled = enabled)?;
+    if !enabled {
+        telemetry::discard(&app);
+    }
     Ok(())
 }
 
 #[tauri::command]
-fn get_running() -> bool {
-    IS_RUNNING.load(Ordering::SeqCst)
+fn get_update_check_on_startup(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().update_check_on_startup
+}
+
+#[tauri::command]
+fn set_update_check_on_startup(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.update_check_on_startup = enabled)
+}
+
+/// Compares this build with the latest GitHub release, from a day-old cache unless `force`.
+#[tauri::command]
+async fn check_for_updates(app: AppHandle, force: Option<bool>) -> update::UpdateCheck {
+    update::check(&app, force.unwrap_or(false)).await
+}
+
+/// Opens the newer release's installer or page. Installing it is left to the user.
+#[tauri::command]
+async fn open_update_download(app: AppHandle) -> Result<String, LauncherError> {
+    let check = update::check(&app, false).await;
+    let url = match (check.state, check.download_url) {
+        (update::UpdateState::Available, Some(url)) => url,
+        _ => return Err(LauncherError::invalid("no launcher update is available")),
+    };
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| LauncherError::open_failed(&url, e))?;
+    Ok(url)
+}
+
+async fn startup_update_check(app: AppHandle) {
+    let check = update::check(&app, false).await;
+    let Some(latest) = check.latest_version.filter(|_| check.state == update::UpdateState::Available) else {
+        return;
+    };
+    let params = json!({ "version": latest, "current": update::CURRENT_VERSION });
+    emit_log(&app, "info", MessageKey::LauncherUpdateAvailable.with(params));
+    notify::send(
+        &app,
+        Notice::Requested,
+        "A.B.E.L. launcher update",
+        format!("Version {} is available, you are running {}", latest, update::CURRENT_VERSION),
+    );
+}
+
+/// Seconds A.B.E.L. has been online, or null while it is stopped or degraded.
+#[tauri::command]
+fn get_uptime(app: AppHandle) -> Option<u64> {
+    uptime::secs(&app)
+}
+
+#[tauri::command]
+fn get_restart_stats(app: AppHandle) -> Vec<autorestart::RestartStats> {
+    app.state::<AutoRestart>().stats()
+}
+
+#[tauri::command]
+fn get_auto_open(app: AppHandle) -> bool {
+    app.state::<SettingsStore>().get().auto_open
+}
+
+#[tauri::command]
+fn set_auto_open(app: AppHandle, enabled: bool) -> Result<(), LauncherError> {
+    app.state::<SettingsStore>()
+        .update(|settings| settings.auto_open = enabled)
+}
+
+#[tauri::command]
+fn get_ready_url(app: AppHandle) -> String {
+    app.state::<SettingsStore>().get().ready_url
+}
+
+#[tauri::command]
+fn set_ready_url(app: AppHandle, url: String) -> Result<(), LauncherError> {
+    let url = url.trim().to_string();
+    if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(LauncherError::invalid(format!("not an http(s) URL: {}", url)));
+    }
+    app.state::<SettingsStore>()
+        .update(|settings| settings.ready_url = url)
+}
+
+#[tauri::command]
+fn get_ready_timeout(app: AppHandle) -> u64 {
+    app.state::<SettingsStore>().get().ready_timeout_secs
+}
+
+#[tauri::command]
+fn set_ready_timeout(app: AppHandle, secs: u64) -> Result<(), LauncherError> {
+    if secs == 0 || secs > settings::MAX_TIMEOUT_SECS {
+        let message = format!("ready timeout must be between 1 and {} seconds", settings::MAX_TIMEOUT_SECS);
+        return Err(LauncherError::invalid(message));
+    }
+    app.state::<SettingsStore>()
+        .update(|settings| settings.ready_timeout_secs = secs)
+}
+
+#[tauri::command]
+fn get_state(app: AppHandle) -> Operation {
+    app.state::<Mutex<LauncherState>>().lock().unwrap().operation()
+}
+
+/// Arguments and working directory of a launch that found this instance running.
+#[derive(Clone, Serialize)]
+struct SecondInstanceEvent {
+    args: Vec<String>,
+    cwd: String,
 }
 
 fn main() {
-    tauri::Builder::default()
+    let args: Vec<String> = std::env::args().collect();
+    let minimized = login::started_minimized(&args);
+    tauri::Builder::<AppRuntime>::new()
+        // Registered first so a second launch exits before setting anything else up
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            // A link launch is forwarded to the deep link handler, which decides about the window
+            if !args.iter().any(|arg| deeplink::is_link(arg)) {
+                tray::show_main_window(app);
+                emit_log(app, "info", MessageKey::LauncherAlreadyRunning);
+            }
+            if let Some(dir) = settings::project_dir_arg(&args) {
+                // Relative to where the second launch was started, not to this process
+                let dir = Path::new(&cwd).join(dir).to_string_lossy().to_string();
+                emit_log(app, "info", MessageKey::ProjectDirArgument.with(json!({ "dir": dir })));
+                *app.state::<ProjectDirArg>().0.lock().unwrap() = Some(dir);
+            }
+            app.emit("second-instance", SecondInstanceEvent { args, cwd }).ok();
+        }))
+        .manage(Mutex::new(LauncherState::default()))
+        .manage(ProjectDirArg(Mutex::new(settings::project_dir_arg(&args))))
+        .manage(LogHistory::default())
+        .manage(LogFilterState::default())
+        .manage(LogFollower::default())
+        .manage(BootControl::default())
+        .manage(AutoRestart::default())
+        .manage(uptime::Uptime::default())
+        .manage(hotkey::Hotkey::default())
+        .manage(statusapi::StatusApi::default())
+        .manage(telemetry::Telemetry::default())
+        .manage(PullControl::default())
+        .manage(StopControl::default())
+        .manage(stats::StatsStream::default())
+        .manage(runtime::ActiveRuntime::default())
+        .setup(move |app| {
+            if minimized {
+                // Started by the login registration: stay in the tray
+                if let Some(window) = app.get_webview_window("main") {
+                    window.hide().ok();
+                }
+            }
+            app.manage(SettingsStore::load(app.handle()));
+            proxy::configure(&app.state::<SettingsStore>().get().proxy);
+            runtime::configure(app.handle(), app.state::<SettingsStore>().get().container_runtime);
+            context::configure(app.state::<SettingsStore>().get().docker_context.as_deref());
+            remote::configure(app.state::<SettingsStore>().get().docker_host.as_deref());
+            engine::configure(app.state::<SettingsStore>().get().engine_api);
+            logfile::start(app.handle());
+            process::start_log_batcher(app.handle());
+            install::on_startup(app.handle());
+            if let Some(warning) = app.state::<SettingsStore>().load_warning() {
+                let params = json!({ "warning": warning });
+                emit_log(app.handle(), "warning", MessageKey::SettingsLoadWarning.with(params));
+            }
+            tauri::async_runtime::spawn(uptime::reconcile(app.handle().clone()));
+            let min_level = app.state::<SettingsStore>().get().min_log_level;
+            app.state::<LogFilterState>().set_min_level(min_level);
+            tray::create(app.handle())?;
+            monitor::start(app.handle());
+            events::start(app.handle());
+            schedule::start(app.handle());
+            telemetry::start(app.handle());
+            hotkey::init(app.handle());
+            deeplink::init(app.handle());
+            statusapi::init(app.handle());
+
+            let settings = app.state::<SettingsStore>().get();
+            if settings.auto_start {
+                tauri::async_runtime::spawn(auto_start(app.handle().clone()));
+            } else if settings.restore_state && settings.wanted_running {
+                tauri::async_runtime::spawn(restore_running_state(app.handle().clone()));
+            }
+            if app.state::<SettingsStore>().get().update_check_on_startup {
+                tauri::async_runtime::spawn(startup_update_check(app.handle().clone()));
+            }
+            Ok(())
+        })
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().with_handler(hotkey::handle).build())
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                tray::close_requested(window, api);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
-            check_docker,
+            check_runtime,
+            check_wsl,
+            check_backend_health,
+            check_gpu,
+            check_connectivity,
+            validate_compose,
+            diagnose_network,
+            run_doctor,
+            check_disk_space,
+            get_disk_usage,
+            get_docker_info,
+            get_compose_info,
+            list_contexts,
+            set_docker_context,
+            set_docker_host,
+            test_remote_connection,
             check_status,
+            get_service_status,
+            list_containers,
+            check_ports,
+            start_docker_desktop,
             start_services,
+            cancel_start,
             stop_services,
-            get_running,
+            restart_services,
+            start_service,
+            stop_service,
+            restart_service,
+            rebuild_service,
+            open_shell,
+            get_web_ui_url,
+            open_web_ui,
+            follow_logs,
+            stop_follow_logs,
+            pull_images,
+            update_services,
+            cleanup_docker,
+            cancel_pull,
+            start_stats_stream,
+            stop_stats_stream,
+            get_log_history,
+            search_logs,
+            clear_log_history,
+            set_log_filter,
+            get_log_level,
+            set_log_level,
+            get_log_file_path,
+            open_log_folder,
+            export_logs,
+            export_logs_json,
+            export_diagnostics,
+            read_env,
+            write_env,
+            set_secret,
+            list_secrets,
+            delete_secret,
+            get_project_dir,
+            install_project_files,
+            get_setup_state,
+            complete_setup_step,
+            get_project_dir_info,
+            open_project_dir,
+            open_config_file,
+            config_files_modified_since_start,
+            set_project_dir,
+            list_environments,
+            add_environment,
+            remove_environment,
+            set_active_environment,
+            get_compose_files,
+            set_compose_files,
+            list_profiles,
+            get_active_profiles,
+            set_active_profiles,
+            get_resource_limits,
+            set_resource_limits,
+            clear_resource_limits,
+            get_start_options,
+            set_start_options,
+            get_settings,
+            update_settings,
+            get_health_timeout,
+            set_health_timeout,
+            get_boot_timeout,
+            set_boot_timeout,
+            get_stop_timeout,
+            set_stop_timeout,
+            get_poll_interval,
+            set_poll_interval,
+            get_auto_start,
+            set_auto_start,
+            get_launch_at_login,
+            set_launch_at_login,
+            get_on_close,
+            set_on_close,
+            get_auto_launch_docker,
+            set_auto_launch_docker,
+            get_auto_open,
+            set_auto_open,
+            get_auto_restart,
+            set_auto_restart,
+            get_restart_stats,
+            get_proxy,
+            set_proxy,
+            test_proxy,
+            get_hotkey,
+            set_hotkey,
+            get_last_error,
+            list_models,
+            pull_model,
+            delete_model,
+            get_status_api,
+            set_status_api_port,
+            set_status_api_token,
+            get_schedule,
+            set_schedule,
+            get_restore_state,
+            set_restore_state,
+            get_telemetry_status,
+            set_telemetry_enabled,
+            get_update_check_on_startup,
+            set_update_check_on_startup,
+            check_for_updates,
+            open_update_download,
+            get_versions,
+            copy_versions_to_clipboard,
+            copy_error_report,
+            list_volumes,
+            backup_volumes,
+            restore_volumes,
+            get_uptime,
+            get_notifications_enabled,
+            set_notifications_enabled,
+            get_notify_when_focused,
+            set_notify_when_focused,
+            get_ansi_colors,
+            set_ansi_colors,
+            get_ready_url,
+            set_ready_url,
+            get_ready_timeout,
+            set_ready_timeout,
+            get_state,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            tauri::RunEvent::ExitRequested { code, api, .. } => shutdown::exit_requested(app, &api, code),
+            tauri::RunEvent::Exit => {
+                monitor::shutdown(app);
+                events::shutdown(app);
+                hotkey::shutdown(app);
+                statusapi::shutdown(app);
+                logfile::shutdown(app);
+            }
+            _ => {}
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runtime::tests::MockRuntime;
+    use std::sync::Arc;
+
+    #[test]
+    fn timestamps_are_rfc3339_to_the_millisecond() {
+        use chrono::{Offset, TimeZone};
+
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let wall = day.and_hms_nano_opt(14, 30, 5, 123_987_654).unwrap();
+        let at = chrono::Local.from_local_datetime(&wall).earliest().unwrap();
+        let offset = at.offset().fix().to_string();
+        // Truncated, not rounded, and the offset spelled out even at UTC
+        assert_eq!(format_timestamp(&at), format!("2024-05-01T14:30:05.123{}", offset));
+        assert!(!format_timestamp(&at).ends_with('Z'));
+        let utc = chrono::DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&chrono::Local);
+        assert!(format_timestamp(&utc).contains(".000"));
+        assert_eq!(chrono::DateTime::parse_from_rfc3339(&format_timestamp(&utc)).unwrap(), utc);
+
+        let event = LogEvent { timestamp: at, ..log_event(LogSource::Launcher, None, "info", String::new()) };
+        assert_eq!(serde_json::to_value(&event).unwrap()["timestamp"], format_timestamp(&at));
+        assert_eq!(display_time(&at), "14:30:05.123");
+    }
+
+    /// Runs `statuses`, each at its offset in ms, through the debounce the way
+    /// [`send_status`] does, including the sends it schedules. Returns what reached
+    /// the frontend and when.
+    fn debounced(statuses: &[(u64, Phase)]) -> Vec<(u64, Phase)> {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut sent = StatusSent { status: None, at: None, pending: false };
+        let mut last = None;
+        let mut scheduled: Option<u64> = None;
+        let mut emitted = Vec::new();
+
+        let mut send = |sent: &mut StatusSent, status: &StatusEvent, ms: u64, scheduled: &mut Option<u64>| {
+            let urgent = matches!(status.phase, Phase::Failed | Phase::DaemonLost);
+            match sent.decide(status, urgent, at(ms)) {
+                StatusSend::Unchanged | StatusSend::Scheduled => {}
+                StatusSend::Now => {
+                    emitted.push((ms, status.phase));
+                    sent.status = Some(status.clone());
+                    sent.at = Some(at(ms));
+                }
+                StatusSend::After(wait) => {
+                    sent.pending = true;
+                    *scheduled = Some(ms + wait.as_millis() as u64);
+                }
+            }
+        };
+        for &(ms, phase) in statuses {
+            if let Some(due) = scheduled.filter(|due| *due <= ms) {
+                scheduled = None;
+                sent.pending = false;
+                send(&mut sent, last.as_ref().unwrap(), due, &mut scheduled);
+            }
+            let status = StatusEvent::new(phase);
+            send(&mut sent, &status, ms, &mut scheduled);
+            last = Some(status);
+        }
+        if let Some(due) = scheduled {
+            sent.pending = false;
+            send(&mut sent, last.as_ref().unwrap(), due, &mut scheduled);
+        }
+        emitted
+    }
+
+    #[test]
+    fn identical_statuses_are_sent_once() {
+        let repeated = [(0, Phase::Running), (10, Phase::Running), (100, Phase::Running), (5000, Phase::Running)];
+        assert_eq!(debounced(&repeated), [(0, Phase::Running)]);
+    }
+
+    #[test]
+    fn changes_inside_the_gap_are_sent_once_it_ends() {
+        let alternating = [(0, Phase::Starting), (40, Phase::Running), (80, Phase::Degraded), (120, Phase::Running)];
+        assert_eq!(debounced(&alternating), [(0, Phase::Starting), (250, Phase::Running)]);
+        // Flapping back to what the frontend has sends nothing more
+        let flapping = [(0, Phase::Running), (50, Phase::Degraded), (100, Phase::Running)];
+        assert_eq!(debounced(&flapping), [(0, Phase::Running)]);
+        let spaced = [(0, Phase::Starting), (300, Phase::Running), (600, Phase::Stopping)];
+        assert_eq!(debounced(&spaced), spaced);
+    }
+
+    #[test]
+    fn failures_bypass_the_gap() {
+        let failing = [(0, Phase::Starting), (20, Phase::Failed), (30, Phase::Failed), (40, Phase::Stopped)];
+        assert_eq!(debounced(&failing), [(0, Phase::Starting), (20, Phase::Failed), (270, Phase::Stopped)]);
+        let lost = [(0, Phase::Running), (1, Phase::DaemonLost)];
+        assert_eq!(debounced(&lost), lost);
+    }
+
+    /// An app managing the state the stack commands use, with its project in a fresh
+    /// directory and every engine and compose command going to `runtime`.
+    fn stack_app(name: &str, runtime: Arc<MockRuntime>) -> (tauri::App<AppRuntime>, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("abel-main-{}-{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("compose.yaml"), "services: {}\n").unwrap();
+        let settings = settings::Settings {
+            notifications_enabled: false,
+            ready_url: String::new(),
+            stop_timeout_secs: 10,
+            ..Default::default()
+        };
+
+        let app = tauri::test::mock_app();
+        app.manage(Mutex::new(LauncherState::default()));
+        app.manage(ProjectDirArg(Mutex::new(Some(dir.to_string_lossy().to_string()))));
+        app.manage(SettingsStore::in_memory(settings));
+        app.manage(LogHistory::default());
+        app.manage(BootControl::default());
+        app.manage(StopControl::default());
+        app.manage(AutoRestart::default());
+        app.manage(uptime::Uptime::default());
+        app.manage(telemetry::Telemetry::default());
+        app.manage(stats::StatsStream::default());
+        app.manage(runtime::ActiveRuntime::new(runtime));
+        (app, dir)
+    }
+
+    /// Whether `expected` appear in `calls` in this order, with anything in between.
+    fn in_order(calls: &[String], expected: &[&str]) -> bool {
+        let mut calls = calls.iter();
+        expected.iter().all(|wanted| calls.any(|call| call == wanted))
+    }
+
+    #[test]
+    fn start_stop_and_status_go_through_the_runtime() {
+        let runtime = Arc::new(MockRuntime::new(&["backend", "db"]));
+        let (app, dir) = stack_app("lifecycle", runtime.clone());
+        let app = app.handle().clone();
+        let ps = "compose ps --all --format json";
+
+        tauri::async_runtime::block_on(async {
+            assert!(!check_status(app.clone()).await.unwrap());
+            assert_eq!(runtime.take_calls(), ["compose config --services", ps]);
+
+            start_services(app.clone(), None).await.unwrap();
+            let calls = runtime.take_calls();
+            let probe = "docker info --format {{.ServerVersion}}";
+            let boot = ["compose config --quiet", probe, "compose up -d --build", ps];
+            assert!(in_order(&calls, &boot), "{calls:?}");
+            assert_eq!(calls.iter().filter(|call| call.starts_with("compose up")).count(), 1);
+            assert_eq!(app.state::<Mutex<LauncherState>>().lock().unwrap().operation(), Operation::Running);
+
+            assert!(check_status(app.clone()).await.unwrap());
+            assert_eq!(runtime.take_calls(), ["compose config --services", ps]);
+
+            stop_services(app.clone()).await.unwrap();
+            let calls = runtime.take_calls();
+            assert!(in_order(&calls, &["compose down -t 10", ps]), "{calls:?}");
+            assert!(!calls.iter().any(|call| call.starts_with("docker kill")));
+            assert!(!check_status(app.clone()).await.unwrap());
+        });
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn a_boot_is_refused_while_the_daemon_is_down() {
+        let runtime = Arc::new(MockRuntime::new(&["backend"]).daemon_down());
+        let (app, dir) = stack_app("daemon-down", runtime.clone());
+        let app = app.handle().clone();
+
+        let result = tauri::async_runtime::block_on(start_services(app.clone(), None));
+        assert!(matches!(result, Err(LauncherError::DaemonUnreachable { .. })));
+        let calls = runtime.take_calls();
+        assert!(in_order(&calls, &["compose config --quiet", "docker info --format {{.ServerVersion}}"]));
+        assert!(!calls.iter().any(|call| call.starts_with("compose up")), "{calls:?}");
+        // The refusal settled the operation, so nothing is left starting
+        assert_eq!(app.state::<Mutex<LauncherState>>().lock().unwrap().operation(), Operation::Idle);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn concurrent_starts_spawn_one_child() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("abel-main-compose-bin-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let spawns = dir.join("spawns");
+        std::fs::remove_file(&spawns).ok();
+        // Slow enough that the second start comes in while the first is still in `up`
+        let script = dir.join("compose");
+        std::fs::write(&script, format!("#!/bin/sh\necho \"$@\" >> '{}'\nsleep 1\n", spawns.display())).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let runtime = Arc::new(MockRuntime::new(&["backend"]).streaming_through(script));
+        let (app, project_dir) = stack_app("concurrent", runtime);
+        let starts: Vec<_> = (0..2)
+            .map(|_| {
+                let app = app.handle().clone();
+                std::thread::spawn(move || tauri::async_runtime::block_on(start_services(app, None)))
+            })
+            .collect();
+        let results: Vec<_> = starts.into_iter().map(|start| start.join().unwrap()).collect();
+
+        assert_eq!(std::fs::read_to_string(&spawns).unwrap().lines().collect::<Vec<_>>(), ["up -d --build"]);
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1, "{results:?}");
+        assert!(results.iter().any(|result| matches!(
+            result,
+            Err(LauncherError::Busy { current_operation: Operation::Starting })
+        )));
+        // The winner's guard settled the operation once its boot was through
+        assert_eq!(app.state::<Mutex<LauncherState>>().lock().unwrap().operation(), Operation::Running);
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::remove_dir_all(&project_dir).ok();
+    }
 }
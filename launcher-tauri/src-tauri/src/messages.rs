@@ -0,0 +1,643 @@
+use serde_json::{Map, Value};
+
+/// Every message the launcher logs itself. The key goes out with the event for the
+/// frontend to translate, `params` filled in; the English text is sent as `message`
+/// for the console and whatever has no translation.
+#[derive(Clone, Copy, PartialEq)]
+pub enum MessageKey {
+    BootRefused,
+    BootRefusedComposeInvalid,
+    BootStart,
+    BootOptions,
+    BootAborted,
+    BootFailed,
+    BootTimedOut,
+    BootOnline,
+    BootCancelled,
+    BootCleanupFailed,
+    BootCancelDone,
+    BootCancelling,
+    AutoStartSkipped,
+    AutoStartSkippedComposeInvalid,
+    AutoStartBooting,
+    StateRestoreSkipped,
+    StateRestoreSkippedComposeInvalid,
+    StateRestoreRunning,
+    StateRestoreBooting,
+    HealthAwaiting,
+    CoreWaiting,
+    HealthTimedOut,
+    CoreNotReady,
+    LowDiskSpace,
+    RegistryUnreachable,
+    OfflinePull,
+    PreflightWarning,
+    ShutdownHung,
+    ShutdownKilling,
+    ShutdownStart,
+    ShutdownComplete,
+    ShutdownFailed,
+    RestartStart,
+    RestartShutdownFailed,
+    RestartBooting,
+    RestartOnline,
+    RestartBootFailed,
+    ExitStopping,
+    ExitStopIncomplete,
+    ExitStopFailed,
+    ExitStopTimedOut,
+    ExitStopped,
+    ExitCancelled,
+    ExitCancelledError,
+    AutoRestartGaveUp,
+    AutoRestartScheduled,
+    AutoRestartCancelled,
+    AutoRestartSkipped,
+    AutoRestarted,
+    AutoRestartFailed,
+    VolumeSkipped,
+    VolumeBackingUp,
+    VolumeSaved,
+    BackupComplete,
+    VolumeRestoring,
+    VolumeRestored,
+    RestoreComplete,
+    DeepLinkIgnored,
+    DeepLinkReceived,
+    DeepLinkWebUiFailed,
+    ContainerStarted,
+    ContainerStopped,
+    ContainerExited,
+    ContainerDiedWithCode,
+    ContainerDied,
+    ContainerHealthy,
+    ContainerUnhealthy,
+    HotkeyNotRegistered,
+    HotkeyIgnored,
+    HotkeyStopping,
+    HotkeyStarting,
+    HotkeySet,
+    HotkeyCleared,
+    ProjectFileInstalled,
+    ProjectFileUpgraded,
+    ProjectFileChange,
+    ProjectFileBackedUp,
+    ProjectFileKept,
+    ProjectFilesNotInstalled,
+    ServiceRecovered,
+    ServiceCrashed,
+    DaemonLost,
+    DaemonReturned,
+    LogLinesDropped,
+    TransientRetry,
+    ScheduledStart,
+    ScheduledStartFailed,
+    ScheduledShutdown,
+    ScheduledShutdownFailed,
+    ScheduleSet,
+    ScheduleDisabled,
+    SetupEnvSaved,
+    StatsStopped,
+    StatusApiStopped,
+    StatusApiListening,
+    StatusApiNotStarted,
+    StatusApiStarting,
+    StatusApiStopping,
+    WindowHidden,
+    WebUiOpenFailed,
+    WebUiOpened,
+    DoctorCheck,
+    NetworkCheckFailed,
+    VersionsCopied,
+    ErrorReportCopied,
+    DockerContextSet,
+    ContextLeftRunning,
+    DockerHostSet,
+    HostLeftRunning,
+    DockerHostAnswered,
+    DockerHostUnreachable,
+    EngineLaunching,
+    EngineLaunchFailed,
+    DaemonOnline,
+    DaemonLaunchTimedOut,
+    DaemonWaiting,
+    ServiceStarting,
+    ServiceStarted,
+    ServiceStartFailed,
+    ServiceStopping,
+    ServiceStopped,
+    ServiceStopFailed,
+    ServiceRestarting,
+    ServiceRestarted,
+    ServiceRestartFailed,
+    ServiceRebuilding,
+    ServiceRebuildingNoCache,
+    ServiceRebuilt,
+    ServiceRebuiltUnhealthy,
+    ServiceRebuildFailed,
+    ShellOpened,
+    ModelPulling,
+    ModelPulled,
+    ModelPullFailed,
+    ModelDeleted,
+    LogsAttached,
+    LogsEnded,
+    LogsDetached,
+    LogExported,
+    LogHistoryExported,
+    PullStart,
+    PullCancelled,
+    PullComplete,
+    PullFailed,
+    UpdateStart,
+    UpdatePullFailed,
+    UpdateRecreating,
+    UpdateRecreateFailed,
+    UpdateUpToDate,
+    UpdateComplete,
+    CleanupUsageBefore,
+    CleanupImages,
+    CleanupBuildCache,
+    CleanupVolumes,
+    CleanupComplete,
+    CleanupUsageAfter,
+    DiskUsage,
+    DiagnosticsCollecting,
+    DiagnosticsExported,
+    EnvFileSaved,
+    SecretStored,
+    SecretWrittenBack,
+    SecretRemoved,
+    ProjectDirSet,
+    ProjectDirOverridden,
+    ProjectDirOpened,
+    ProjectFileOpened,
+    ProjectDirArgument,
+    EnvironmentAdded,
+    EnvironmentRemoved,
+    EnvironmentActive,
+    ComposeFilesReset,
+    ComposeFilesSet,
+    LimitsSet,
+    LimitsSetRestart,
+    LimitsCleared,
+    LimitsClearedRestart,
+    ProfilesSet,
+    ProfilesSetRestart,
+    SettingsUpdated,
+    BootOptionsSet,
+    LaunchAtLoginEnabled,
+    LaunchAtLoginDisabled,
+    ProxyCleared,
+    ProxySaved,
+    SettingsLoadWarning,
+    LauncherUpdateAvailable,
+    LauncherAlreadyRunning,
+}
+
+impl MessageKey {
+    /// The key and the English text, `{name}` standing for the param of that name.
+    /// Params carry raw values; the text puts one in capitals with `{name|upper}` and
+    /// gives `{name|or:TEXT}` for a param that is null or empty.
+    fn entry(self) -> (&'static str, &'static str) {
+        match self {
+            MessageKey::BootRefused => ("boot.refused", "BOOT REFUSED - {error|upper}"),
+            MessageKey::BootRefusedComposeInvalid => (
+                "boot.refused_compose_invalid",
+                "BOOT REFUSED - COMPOSE FILE INVALID: {issue|upper}{location}",
+            ),
+            MessageKey::BootStart => ("boot.start", "INITIATING BOOT SEQUENCE..."),
+            MessageKey::BootOptions => ("boot.options", "BOOT OPTIONS: {options}"),
+            MessageKey::BootAborted => ("boot.aborted", "BOOT SEQUENCE ABORTED - {error|upper}"),
+            MessageKey::BootFailed => ("boot.failed", "BOOT SEQUENCE FAILED: {error}"),
+            MessageKey::BootTimedOut => ("boot.timed_out", "BOOT SEQUENCE TIMED OUT - NO PROGRESS FOR {secs} SECONDS"),
+            MessageKey::BootOnline => ("boot.online", "ALL SYSTEMS OPERATIONAL - A.B.E.L. ONLINE"),
+            MessageKey::BootCancelled => (
+                "boot.cancelled",
+                "BOOT CANCELLED - REMOVING PARTIALLY STARTED CONTAINERS...",
+            ),
+            MessageKey::BootCleanupFailed => ("boot.cleanup_failed", "CLEANUP FAILED: {error}"),
+            MessageKey::BootCancelDone => ("boot.cancel_done", "BOOT SEQUENCE ABORTED"),
+            MessageKey::BootCancelling => ("boot.cancelling", "CANCELLING BOOT SEQUENCE..."),
+            MessageKey::AutoStartSkipped => ("auto_start.skipped", "AUTO-START SKIPPED - {error|upper}"),
+            MessageKey::AutoStartSkippedComposeInvalid => (
+                "auto_start.skipped_compose_invalid",
+                "AUTO-START SKIPPED - COMPOSE FILE INVALID: {issue|upper}{location}",
+            ),
+            MessageKey::AutoStartBooting => ("auto_start.booting", "AUTO-START ENABLED - BOOTING"),
+            MessageKey::StateRestoreSkipped => ("restore_state.skipped", "STATE RESTORE SKIPPED - {error|upper}"),
+            MessageKey::StateRestoreSkippedComposeInvalid => (
+                "restore_state.skipped_compose_invalid",
+                "STATE RESTORE SKIPPED - COMPOSE FILE INVALID: {issue|upper}{location}",
+            ),
+            MessageKey::StateRestoreRunning => (
+                "restore_state.already_running",
+                "RESTORING LAST STATE - SERVICES ALREADY RUNNING",
+            ),
+            MessageKey::StateRestoreBooting => (
+                "restore_state.booting",
+                "RESTORING LAST STATE - A.B.E.L. WAS RUNNING WHEN THE LAUNCHER CLOSED - BOOTING",
+            ),
+            MessageKey::HealthAwaiting => ("health.awaiting", "AWAITING HEALTH CHECKS: {services}"),
+            MessageKey::CoreWaiting => ("health.core_waiting", "WAITING FOR A.B.E.L. CORE... ({secs}s)"),
+            MessageKey::HealthTimedOut => ("health.timed_out", "HEALTH CHECK TIMEOUT - NOT HEALTHY: {services}"),
+            MessageKey::CoreNotReady => (
+                "health.core_not_ready",
+                "A.B.E.L. CORE NOT READY AFTER {secs} SECONDS - CONTAINERS LEFT UP",
+            ),
+            MessageKey::LowDiskSpace => ("preflight.low_disk_space", "LOW DISK SPACE - {error|upper}"),
+            MessageKey::RegistryUnreachable => (
+                "preflight.registry_unreachable",
+                "REGISTRY {registry|upper} - {reason|upper} - NEEDED FOR {images|upper}",
+            ),
+            MessageKey::OfflinePull => ("preflight.offline", "OFFLINE - {error|upper} - THE BOOT MAY HANG ON PULLS"),
+            MessageKey::PreflightWarning => ("preflight.warning", "PREFLIGHT: {message|upper}"),
+            MessageKey::ShutdownHung => ("shutdown.hung", "SHUTDOWN HUNG - KILLING DOCKER-COMPOSE"),
+            MessageKey::ShutdownKilling => ("shutdown.killing", "FORCE-KILLING {count} REMAINING CONTAINERS..."),
+            MessageKey::ShutdownStart => ("shutdown.start", "INITIATING SHUTDOWN SEQUENCE..."),
+            MessageKey::ShutdownComplete => ("shutdown.complete", "SHUTDOWN COMPLETE - ENTERING STANDBY"),
+            MessageKey::ShutdownFailed => ("shutdown.failed", "SHUTDOWN ERROR: {error}"),
+            MessageKey::RestartStart => ("restart.start", "INITIATING RESTART SEQUENCE..."),
+            MessageKey::RestartShutdownFailed => (
+                "restart.shutdown_failed",
+                "RESTART ABORTED - SHUTDOWN PHASE FAILED: {error}",
+            ),
+            MessageKey::RestartBooting => (
+                "restart.booting",
+                "SHUTDOWN PHASE COMPLETE - REINITIATING BOOT SEQUENCE...",
+            ),
+            MessageKey::RestartOnline => ("restart.online", "RESTART COMPLETE - A.B.E.L. ONLINE"),
+            MessageKey::RestartBootFailed => ("restart.boot_failed", "RESTART FAILED - BOOT PHASE FAILED: {error}"),
+            MessageKey::ExitStopping => ("exit.stopping", "STOP ON EXIT - TAKING SERVICES DOWN BEFORE QUITTING..."),
+            MessageKey::ExitStopIncomplete => (
+                "exit.stop_incomplete",
+                "SERVICES COULD NOT BE STOPPED - EXITING ANYWAY",
+            ),
+            MessageKey::ExitStopFailed => ("exit.stop_failed", "STOP FAILED: {error|upper} - EXITING ANYWAY"),
+            MessageKey::ExitStopTimedOut => (
+                "exit.stop_timed_out",
+                "SHUTDOWN TIMED OUT AFTER {secs}S - EXITING ANYWAY",
+            ),
+            MessageKey::ExitStopped => ("exit.stopped", "SERVICES STOPPED - EXITING"),
+            MessageKey::ExitCancelled => ("exit.cancelled", "EXIT CANCELLED - SERVICES COULD NOT BE STOPPED"),
+            MessageKey::ExitCancelledError => ("exit.cancelled_error", "EXIT CANCELLED: {error}"),
+            MessageKey::AutoRestartGaveUp => (
+                "autorestart.gave_up",
+                "AUTO-RESTART GAVE UP ON {service|upper} - {crashes} CRASHES IN 10 MINUTES",
+            ),
+            MessageKey::AutoRestartScheduled => (
+                "autorestart.scheduled",
+                "AUTO-RESTARTING {service|upper} IN {secs}s (ATTEMPT {attempt}/{max})",
+            ),
+            MessageKey::AutoRestartCancelled => (
+                "autorestart.cancelled",
+                "AUTO-RESTART OF {service|upper} CANCELLED - STACK WAS STOPPED",
+            ),
+            MessageKey::AutoRestartSkipped => (
+                "autorestart.skipped",
+                "AUTO-RESTART OF {service|upper} SKIPPED - ANOTHER OPERATION IS RUNNING",
+            ),
+            MessageKey::AutoRestarted => ("autorestart.succeeded", "SERVICE {service|upper} AUTO-RESTARTED"),
+            MessageKey::AutoRestartFailed => ("autorestart.failed", "AUTO-RESTART OF {service|upper} FAILED: {error}"),
+            MessageKey::VolumeSkipped => ("backup.volume_skipped", "VOLUME {volume} NOT CREATED YET - SKIPPED"),
+            MessageKey::VolumeBackingUp => ("backup.volume_started", "BACKING UP VOLUME {volume} ({index}/{total})..."),
+            MessageKey::VolumeSaved => ("backup.volume_saved", "VOLUME {volume} SAVED - {size_mb} MB"),
+            MessageKey::BackupComplete => ("backup.complete", "BACKUP COMPLETE - {count} VOLUMES SAVED TO {dir}"),
+            MessageKey::VolumeRestoring => ("restore.volume_started", "RESTORING VOLUME {volume} ({index}/{total})..."),
+            MessageKey::VolumeRestored => ("restore.volume_restored", "VOLUME {volume} RESTORED FROM {file}"),
+            MessageKey::RestoreComplete => ("restore.complete", "RESTORE COMPLETE - {count} VOLUMES RESTORED"),
+            MessageKey::DeepLinkIgnored => ("deeplink.ignored", "DEEP LINK IGNORED - UNKNOWN ACTION: {url}"),
+            MessageKey::DeepLinkReceived => ("deeplink.received", "EXTERNAL REQUEST {url} - {action}"),
+            MessageKey::DeepLinkWebUiFailed => ("deeplink.web_ui_failed", "WEB UI NOT OPENED - {error|upper}"),
+            MessageKey::ContainerStarted => ("container.started", "CONTAINER {container|upper} STARTED"),
+            MessageKey::ContainerStopped => ("container.stopped", "CONTAINER {container|upper} STOPPED"),
+            MessageKey::ContainerExited => ("container.exited", "CONTAINER {container|upper} EXITED"),
+            MessageKey::ContainerDiedWithCode => (
+                "container.died_with_code",
+                "CONTAINER {container|upper} DIED WITH EXIT CODE {code}",
+            ),
+            MessageKey::ContainerDied => ("container.died", "CONTAINER {container|upper} DIED"),
+            MessageKey::ContainerHealthy => ("container.healthy", "CONTAINER {container|upper} IS HEALTHY"),
+            MessageKey::ContainerUnhealthy => ("container.unhealthy", "CONTAINER {container|upper} IS UNHEALTHY"),
+            MessageKey::HotkeyNotRegistered => ("hotkey.not_registered", "HOTKEY NOT REGISTERED - {error|upper}"),
+            MessageKey::HotkeyIgnored => ("hotkey.ignored", "HOTKEY IGNORED - AN OPERATION IS IN PROGRESS"),
+            MessageKey::HotkeyStopping => ("hotkey.stopping", "HOTKEY - STOPPING"),
+            MessageKey::HotkeyStarting => ("hotkey.starting", "HOTKEY - STARTING"),
+            MessageKey::HotkeySet => ("hotkey.set", "HOTKEY SET: {hotkey|upper}"),
+            MessageKey::HotkeyCleared => ("hotkey.cleared", "HOTKEY CLEARED"),
+            MessageKey::ProjectFileInstalled => ("install.file_installed", "PROJECT FILE INSTALLED: {path}"),
+            MessageKey::ProjectFileUpgraded => (
+                "install.file_upgraded",
+                "PROJECT FILE UPGRADED: {path} ({from|or:NONE} -> {to})",
+            ),
+            MessageKey::ProjectFileChange => ("install.file_change", "  {change}"),
+            MessageKey::ProjectFileBackedUp => (
+                "install.file_backed_up",
+                "PROJECT FILE {path} HAD LOCAL CHANGES, KEPT AS {backup}",
+            ),
+            MessageKey::ProjectFileKept => (
+                "install.file_kept",
+                "PROJECT FILE {path} HAS LOCAL CHANGES - NOT UPGRADED TO {version}",
+            ),
+            MessageKey::ProjectFilesNotInstalled => ("install.failed", "PROJECT FILES NOT INSTALLED - {error}"),
+            MessageKey::ServiceRecovered => ("monitor.service_recovered", "SERVICE {service} RECOVERED"),
+            MessageKey::ServiceCrashed => ("monitor.service_crashed", "SERVICE CRASHED - {crash|upper}"),
+            MessageKey::DaemonLost => ("monitor.daemon_lost", "DOCKER DAEMON LOST - {error|upper}"),
+            MessageKey::DaemonReturned => (
+                "monitor.daemon_returned",
+                "DOCKER DAEMON IS BACK - RECONCILING CONTAINER STATE",
+            ),
+            MessageKey::LogLinesDropped => (
+                "log.lines_dropped",
+                "OUTPUT TOO FAST - {count} LINES NOT SHOWN, THE LOG HISTORY HAS THEM",
+            ),
+            MessageKey::TransientRetry => (
+                "retry.transient",
+                "{what} HIT A TRANSIENT DOCKER ERROR - RETRYING IN {ms}MS (ATTEMPT {attempt}/{max})",
+            ),
+            MessageKey::ScheduledStart => ("schedule.start", "SCHEDULED START {time}"),
+            MessageKey::ScheduledStartFailed => (
+                "schedule.start_failed",
+                "SCHEDULED START {time} FAILED - {error|upper}",
+            ),
+            MessageKey::ScheduledShutdown => ("schedule.shutdown", "SCHEDULED SHUTDOWN {time}"),
+            MessageKey::ScheduledShutdownFailed => (
+                "schedule.shutdown_failed",
+                "SCHEDULED SHUTDOWN {time} FAILED - {error|upper}",
+            ),
+            MessageKey::ScheduleSet => ("schedule.set", "SCHEDULE SET: {start} - {stop} ON {weekdays|upper}"),
+            MessageKey::ScheduleDisabled => ("schedule.disabled", "SCHEDULE DISABLED"),
+            MessageKey::SetupEnvSaved => ("setup.env_saved", "SETUP - REQUIRED SETTINGS SAVED TO .ENV"),
+            MessageKey::StatsStopped => ("stats.stopped", "STATS STREAM STOPPED - NO RUNNING CONTAINERS"),
+            MessageKey::StatusApiStopped => ("status_api.stopped", "STATUS API STOPPED ON http://{address}"),
+            MessageKey::StatusApiListening => ("status_api.listening", "STATUS API LISTENING ON http://{address}"),
+            MessageKey::StatusApiNotStarted => ("status_api.not_started", "STATUS API NOT STARTED - {error|upper}"),
+            MessageKey::StatusApiStarting => ("status_api.starting", "EXTERNAL REQUEST {method} {path} - STARTING"),
+            MessageKey::StatusApiStopping => ("status_api.stopping", "EXTERNAL REQUEST {method} {path} - STOPPING"),
+            MessageKey::WindowHidden => ("tray.window_hidden", "WINDOW HIDDEN - SERVICES KEEP RUNNING IN THE TRAY"),
+            MessageKey::WebUiOpenFailed => ("web_ui.open_failed", "COULD NOT OPEN WEB UI: {error}"),
+            MessageKey::WebUiOpened => ("web_ui.opened", "WEB UI OPENED: {url}"),
+            MessageKey::DoctorCheck => ("doctor.check", "DOCTOR: {check|upper} - {message|upper}"),
+            MessageKey::NetworkCheckFailed => ("network.check_failed", "NETWORK CHECK FAILED - {message|upper}"),
+            MessageKey::VersionsCopied => ("versions.copied", "VERSIONS COPIED TO CLIPBOARD"),
+            MessageKey::ErrorReportCopied => ("error_report.copied", "ERROR REPORT COPIED TO CLIPBOARD"),
+            MessageKey::DockerContextSet => ("docker.context_set", "DOCKER CONTEXT: {context|upper|or:CLI DEFAULT}"),
+            MessageKey::ContextLeftRunning => (
+                "docker.context_left_running",
+                "SERVICES ARE STILL RUNNING ON THE PREVIOUS CONTEXT - THE LAUNCHER NO LONGER SEES THEM",
+            ),
+            MessageKey::DockerHostSet => ("docker.host_set", "DOCKER HOST: {host}"),
+            MessageKey::HostLeftRunning => (
+                "docker.host_left_running",
+                "SERVICES ARE STILL RUNNING ON THE PREVIOUS DOCKER HOST - THE LAUNCHER NO LONGER SEES THEM",
+            ),
+            MessageKey::DockerHostAnswered => (
+                "docker.host_answered",
+                "DOCKER HOST {host} ANSWERED IN {ms}MS - VERSION {version}",
+            ),
+            MessageKey::DockerHostUnreachable => ("docker.host_unreachable", "DOCKER HOST {host} IS NOT REACHABLE"),
+            MessageKey::EngineLaunching => ("docker.launching", "LAUNCHING {engine}..."),
+            MessageKey::EngineLaunchFailed => ("docker.launch_failed", "COULD NOT LAUNCH {engine}: {error}"),
+            MessageKey::DaemonOnline => ("docker.daemon_online", "DOCKER DAEMON ONLINE - VERSION {version}"),
+            MessageKey::DaemonLaunchTimedOut => (
+                "docker.daemon_timed_out",
+                "DOCKER DAEMON DID NOT START WITHIN {secs} SECONDS",
+            ),
+            MessageKey::DaemonWaiting => ("docker.daemon_waiting", "WAITING FOR DOCKER DAEMON... ({secs}s)"),
+            MessageKey::ServiceStarting => ("service.starting", "STARTING SERVICE {service|upper}..."),
+            MessageKey::ServiceStarted => ("service.started", "SERVICE {service|upper} ONLINE"),
+            MessageKey::ServiceStartFailed => (
+                "service.start_failed",
+                "SERVICE {service|upper} FAILED TO START: {error}",
+            ),
+            MessageKey::ServiceStopping => ("service.stopping", "STOPPING SERVICE {service|upper}..."),
+            MessageKey::ServiceStopped => ("service.stopped", "SERVICE {service|upper} STOPPED"),
+            MessageKey::ServiceStopFailed => ("service.stop_failed", "SERVICE {service|upper} FAILED TO STOP: {error}"),
+            MessageKey::ServiceRestarting => ("service.restarting", "RESTARTING SERVICE {service|upper}..."),
+            MessageKey::ServiceRestarted => ("service.restarted", "SERVICE {service|upper} RESTARTED"),
+            MessageKey::ServiceRestartFailed => (
+                "service.restart_failed",
+                "SERVICE {service|upper} FAILED TO COME BACK: {error}",
+            ),
+            MessageKey::ServiceRebuilding => ("service.rebuilding", "REBUILDING SERVICE {service|upper}..."),
+            MessageKey::ServiceRebuildingNoCache => (
+                "service.rebuilding_no_cache",
+                "REBUILDING SERVICE {service|upper} WITHOUT CACHE...",
+            ),
+            MessageKey::ServiceRebuilt => ("service.rebuilt", "SERVICE {service|upper} REBUILT"),
+            MessageKey::ServiceRebuiltUnhealthy => (
+                "service.rebuilt_unhealthy",
+                "SERVICE {service|upper} REBUILT BUT NOT HEALTHY",
+            ),
+            MessageKey::ServiceRebuildFailed => (
+                "service.rebuild_failed",
+                "REBUILD OF {service|upper} FAILED: {error}",
+            ),
+            MessageKey::ShellOpened => ("service.shell_opened", "SHELL OPENED IN {service|upper} ({terminal})"),
+            MessageKey::ModelPulling => ("model.pulling", "PULLING MODEL: {model}"),
+            MessageKey::ModelPulled => ("model.pulled", "MODEL PULLED: {model}"),
+            MessageKey::ModelPullFailed => ("model.pull_failed", "MODEL PULL FAILED: {error}"),
+            MessageKey::ModelDeleted => ("model.deleted", "MODEL DELETED: {model}"),
+            MessageKey::LogsAttached => (
+                "logs.attached",
+                "ATTACHED TO CONTAINER LOGS: {service|upper|or:ALL SERVICES}",
+            ),
+            MessageKey::LogsEnded => ("logs.ended", "CONTAINER LOG STREAM ENDED: {service|upper|or:ALL SERVICES}"),
+            MessageKey::LogsDetached => ("logs.detached", "DETACHED FROM CONTAINER LOGS"),
+            MessageKey::LogExported => ("logs.exported", "CONSOLE LOG EXPORTED: {path}"),
+            MessageKey::LogHistoryExported => (
+                "logs.history_exported",
+                "LOG HISTORY EXPORTED AS NDJSON: {count} EVENTS TO {path}",
+            ),
+            MessageKey::PullStart => ("pull.start", "PULLING {count} IMAGES..."),
+            MessageKey::PullCancelled => (
+                "pull.cancelled",
+                "PULL CANCELLED - UPDATED: {updated|or:NONE} | CURRENT: {current|or:NONE} | FAILED: {failed|or:NONE}",
+            ),
+            MessageKey::PullComplete => (
+                "pull.complete",
+                "PULL COMPLETE - UPDATED: {updated|or:NONE} | CURRENT: {current|or:NONE} | FAILED: {failed|or:NONE}",
+            ),
+            MessageKey::PullFailed => (
+                "pull.failed",
+                concat!(
+                    "PULL FINISHED WITH ERRORS - UPDATED: {updated|or:NONE} | CURRENT: {current|or:NONE}",
+                    " | FAILED: {failed|or:NONE}",
+                ),
+            ),
+            MessageKey::UpdateStart => ("update.start", "INITIATING UPDATE SEQUENCE - PULLING IMAGES..."),
+            MessageKey::UpdatePullFailed => ("update.pull_failed", "UPDATE ABORTED - PULL FAILED: {error}"),
+            MessageKey::UpdateRecreating => ("update.recreating", "IMAGES PULLED - RECREATING CHANGED CONTAINERS..."),
+            MessageKey::UpdateRecreateFailed => (
+                "update.recreate_failed",
+                "UPDATE FAILED - RECREATE PHASE FAILED: {error}",
+            ),
+            MessageKey::UpdateUpToDate => ("update.up_to_date", "UPDATE COMPLETE - ALREADY UP TO DATE"),
+            MessageKey::UpdateComplete => (
+                "update.complete",
+                "UPDATE COMPLETE - {count} SERVICES RECREATED: {services}",
+            ),
+            MessageKey::CleanupUsageBefore => ("cleanup.usage_before", "DISK USAGE BEFORE CLEANUP - {usage}"),
+            MessageKey::CleanupImages => ("cleanup.images", "PRUNING DANGLING IMAGES..."),
+            MessageKey::CleanupBuildCache => ("cleanup.build_cache", "PRUNING BUILD CACHE..."),
+            MessageKey::CleanupVolumes => ("cleanup.volumes", "PRUNING UNUSED VOLUMES..."),
+            MessageKey::CleanupComplete => ("cleanup.complete", "CLEANUP COMPLETE - {mb} MB RECLAIMED"),
+            MessageKey::CleanupUsageAfter => ("cleanup.usage_after", "DISK USAGE AFTER CLEANUP - {usage}"),
+            MessageKey::DiskUsage => ("disk.usage", "DISK USAGE - {usage}"),
+            MessageKey::DiagnosticsCollecting => ("diagnostics.collecting", "COLLECTING DIAGNOSTICS..."),
+            MessageKey::DiagnosticsExported => ("diagnostics.exported", "DIAGNOSTICS EXPORTED: {path} ({bytes} BYTES)"),
+            MessageKey::EnvFileSaved => ("env.saved", "ENVIRONMENT FILE SAVED - RESTART SERVICES TO APPLY"),
+            MessageKey::SecretStored => ("secret.stored", "SECRET STORED IN KEYRING: {key}"),
+            MessageKey::SecretWrittenBack => ("secret.written_back", "SECRET {key} WRITTEN BACK TO .ENV IN PLAIN TEXT"),
+            MessageKey::SecretRemoved => ("secret.removed", "SECRET REMOVED FROM KEYRING: {key}"),
+            MessageKey::ProjectDirSet => ("project.dir_set", "PROJECT DIRECTORY SET: {dir}"),
+            MessageKey::ProjectDirOverridden => (
+                "project.dir_overridden",
+                "PROJECT DIRECTORY OVERRIDDEN FOR THIS SESSION - USING {dir}",
+            ),
+            MessageKey::ProjectDirOpened => ("project.opened", "OPENED {path}"),
+            MessageKey::ProjectFileOpened => (
+                "project.file_opened",
+                "OPENED {path} - RESTART SERVICES FOR CHANGES TO APPLY",
+            ),
+            MessageKey::ProjectDirArgument => ("project.dir_argument", "PROJECT DIRECTORY OVERRIDDEN: {dir}"),
+            MessageKey::EnvironmentAdded => ("environment.added", "ENVIRONMENT ADDED: {name} ({dir})"),
+            MessageKey::EnvironmentRemoved => ("environment.removed", "ENVIRONMENT REMOVED: {name}"),
+            MessageKey::EnvironmentActive => ("environment.active", "ACTIVE ENVIRONMENT: {name|upper|or:DEFAULT}"),
+            MessageKey::ComposeFilesReset => ("compose_files.reset", "COMPOSE FILES RESET - USING DEFAULT LOOKUP"),
+            MessageKey::ComposeFilesSet => ("compose_files.set", "COMPOSE FILES SET: {files}"),
+            MessageKey::LimitsSet => (
+                "limits.set",
+                "RESOURCE LIMITS FOR {service|upper}: CPUS {cpus|or:UNLIMITED}, MEMORY {memory|upper|or:UNLIMITED}",
+            ),
+            MessageKey::LimitsSetRestart => (
+                "limits.set_restart",
+                concat!(
+                    "RESOURCE LIMITS FOR {service|upper}: CPUS {cpus|or:UNLIMITED}, MEMORY {memory|upper|or:UNLIMITED}",
+                    " - RESTART SERVICES TO APPLY",
+                ),
+            ),
+            MessageKey::LimitsCleared => ("limits.cleared", "RESOURCE LIMITS CLEARED"),
+            MessageKey::LimitsClearedRestart => (
+                "limits.cleared_restart",
+                "RESOURCE LIMITS CLEARED - RESTART SERVICES TO APPLY",
+            ),
+            MessageKey::ProfilesSet => ("profiles.set", "ACTIVE PROFILES SET: {profiles|or:NONE}"),
+            MessageKey::ProfilesSetRestart => (
+                "profiles.set_restart",
+                "ACTIVE PROFILES SET: {profiles|or:NONE} - RESTART SERVICES TO APPLY",
+            ),
+            MessageKey::SettingsUpdated => ("settings.updated", "SETTINGS UPDATED: {keys|upper}"),
+            MessageKey::BootOptionsSet => ("settings.boot_options_set", "DEFAULT BOOT OPTIONS SET: {options}"),
+            MessageKey::LaunchAtLoginEnabled => ("settings.launch_at_login_enabled", "LAUNCH AT LOGIN ENABLED"),
+            MessageKey::LaunchAtLoginDisabled => ("settings.launch_at_login_disabled", "LAUNCH AT LOGIN DISABLED"),
+            MessageKey::ProxyCleared => ("settings.proxy_cleared", "PROXY CLEARED"),
+            MessageKey::ProxySaved => ("settings.proxy_saved", "PROXY SETTINGS SAVED - APPLIES TO NEW DOCKER COMMANDS"),
+            MessageKey::SettingsLoadWarning => ("settings.load_warning", "SETTINGS - {warning|upper}"),
+            MessageKey::LauncherUpdateAvailable => (
+                "launcher.update_available",
+                "LAUNCHER UPDATE AVAILABLE - {version} (RUNNING {current})",
+            ),
+            MessageKey::LauncherAlreadyRunning => (
+                "launcher.already_running",
+                "LAUNCHER ALREADY RUNNING - FOCUSING EXISTING WINDOW",
+            ),
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        self.entry().0
+    }
+
+    /// The message with `params`, an object of the values its text refers to.
+    pub fn with(self, params: Value) -> Message {
+        let params = match params {
+            Value::Object(params) => params,
+            _ => Map::new(),
+        };
+        Message { key: self, params }
+    }
+}
+
+/// A [`MessageKey`] with the values for its text.
+pub struct Message {
+    pub key: MessageKey,
+    pub params: Map<String, Value>,
+}
+
+impl From<MessageKey> for Message {
+    fn from(key: MessageKey) -> Self {
+        Message { key, params: Map::new() }
+    }
+}
+
+impl Message {
+    /// The English text with the params filled in. A placeholder without a param is
+    /// left as it is.
+    pub fn english(&self) -> String {
+        let mut text = self.key.entry().1;
+        let mut english = String::with_capacity(text.len());
+        while let Some((before, rest)) = text.split_once('{') {
+            let Some((placeholder, after)) = rest.split_once('}') else {
+                break;
+            };
+            english.push_str(before);
+            let mut filters = placeholder.split('|');
+            let name = filters.next().unwrap_or_default();
+            match self.params.get(name) {
+                Some(param) => english.push_str(&fill(param, filters)),
+                None => english.push_str(&format!("{{{}}}", placeholder)),
+            }
+            text = after;
+        }
+        english.push_str(text);
+        english
+    }
+
+    pub fn params(&self) -> Option<Value> {
+        (!self.params.is_empty()).then(|| Value::Object(self.params.clone()))
+    }
+}
+
+/// `param` as text, through the filters of its placeholder in order.
+fn fill<'a>(param: &Value, filters: impl Iterator<Item = &'a str>) -> String {
+    let mut value = match param {
+        Value::String(value) => value.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    };
+    for filter in filters {
+        match filter.strip_prefix("or:") {
+            Some(fallback) if value.is_empty() => value = fallback.to_string(),
+            Some(_) => {}
+            None if filter == "upper" => value = value.to_uppercase(),
+            None => {}
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn params_stay_raw_and_the_text_formats_them() {
+        let message = MessageKey::BootAborted.with(json!({ "error": "ports already in use: 8000 (backend)" }));
+        assert_eq!(message.english(), "BOOT SEQUENCE ABORTED - PORTS ALREADY IN USE: 8000 (BACKEND)");
+        assert_eq!(message.params(), Some(json!({ "error": "ports already in use: 8000 (backend)" })));
+
+        let limits = json!({ "service": "ollama", "cpus": 2.5, "memory": null });
+        assert_eq!(
+            MessageKey::LimitsSet.with(limits).english(),
+            "RESOURCE LIMITS FOR OLLAMA: CPUS 2.5, MEMORY UNLIMITED"
+        );
+        let message = MessageKey::DockerContextSet.with(json!({ "context": null }));
+        assert_eq!(message.english(), "DOCKER CONTEXT: CLI DEFAULT");
+        assert_eq!(MessageKey::ProfilesSet.with(json!({ "profiles": "" })).english(), "ACTIVE PROFILES SET: NONE");
+        // Paths keep their case
+        assert_eq!(MessageKey::ProjectDirOpened.with(json!({ "path": "/home/Abel" })).english(), "OPENED /home/Abel");
+        assert_eq!(Message::from(MessageKey::ServiceStarting).english(), "STARTING SERVICE {service|upper}...");
+    }
+}
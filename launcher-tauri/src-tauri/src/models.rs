@@ -0,0 +1,239 @@
+use crate::compose::{self, Project};
+use crate::env::{self, EnvLine};
+use crate::error::LauncherError;
+use crate::ports;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use std::time::Duration;
+use crate::AppHandle;
+use tauri::Emitter;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// A pull can take an hour, so it has no overall limit, only one on going silent.
+const PULL_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+/// The variable the backend reads its model from.
+const ACTIVE_MODEL_KEY: &str = "OLLAMA_MODEL";
+
+/// An installed model, as `/api/tags` lists it.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    /// RFC3339, as Ollama reports it.
+    #[serde(default)]
+    pub modified_at: String,
+}
+
+/// Emitted as `model-pull-progress` while a pull runs.
+#[derive(Clone, Serialize)]
+pub struct PullProgress {
+    pub model: String,
+    /// Ollama's own wording, e.g. `pulling manifest` or `verifying sha256 digest`.
+    pub status: String,
+    pub completed: Option<u64>,
+    pub total: Option<u64>,
+    pub percent: Option<u8>,
+}
+
+/// One NDJSON line of `/api/pull`.
+#[derive(Deserialize)]
+struct PullLine {
+    #[serde(default)]
+    status: String,
+    total: Option<u64>,
+    completed: Option<u64>,
+    error: Option<String>,
+}
+
+/// The model server runs on this machine, so system proxies are bypassed.
+fn client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| Client::builder().connect_timeout(CONNECT_TIMEOUT).no_proxy().build().unwrap_or_default())
+}
+
+/// Where the model service's API is published. Refuses up front when the service
+/// isn't running, rather than letting a request time out against nothing.
+async fn base_url(project: &Project, service: &str) -> Result<String, LauncherError> {
+    let config = compose::config_json(project).await?;
+    if config.pointer(&format!("/services/{}", service)).is_none() {
+        let known = compose::expected_services(project).await.unwrap_or_default();
+        return Err(LauncherError::UnknownService { service: service.to_string(), known });
+    }
+    let running = compose::service_status(project)
+        .await?
+        .iter()
+        .any(|status| status.service == service && status.is_running());
+    if !running {
+        return Err(LauncherError::ServiceNotRunning { service: service.to_string() });
+    }
+    ports::service_url(&config, service).ok_or_else(|| LauncherError::NoPublishedPort { service: service.to_string() })
+}
+
+fn request_error(service: &str, e: reqwest::Error) -> LauncherError {
+    if e.is_connect() {
+        // Running, but the API isn't listening yet or any more
+        return LauncherError::ServiceNotRunning { service: service.to_string() };
+    }
+    LauncherError::ModelServer { status: None, message: e.to_string() }
+}
+
+/// Turns a non-2xx answer into an error carrying Ollama's `{"error": ...}` message.
+async fn check(response: Response) -> Result<Response, LauncherError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&body)
+        .ok()
+        .and_then(|body| body.get("error").and_then(Value::as_str).map(str::to_string))
+        .unwrap_or_else(|| if body.trim().is_empty() { status.to_string() } else { body.trim().to_string() });
+    Err(LauncherError::ModelServer { status: Some(status.as_u16()), message })
+}
+
+fn validate_name(name: &str) -> Result<&str, LauncherError> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(LauncherError::invalid(format!("not a model name: {:?}", name)));
+    }
+    Ok(name)
+}
+
+/// `llama3` and `llama3:latest` name the same model.
+fn same_model(a: &str, b: &str) -> bool {
+    let full = |name: &str| {
+        let name = name.trim().to_lowercase();
+        if name.rsplit('/').next().is_some_and(|last| last.contains(':')) {
+            name
+        } else {
+            format!("{}:latest", name)
+        }
+    };
+    full(a) == full(b)
+}
+
+/// The model the backend is configured with in the project's `.env`, if set there.
+fn active_model(project_dir: &str) -> Result<Option<String>, LauncherError> {
+    Ok(env::read(project_dir)?.into_iter().find_map(|line| match line {
+        EnvLine::Entry { key, value } if key == ACTIVE_MODEL_KEY && !value.trim().is_empty() => Some(value),
+        _ => None,
+    }))
+}
+
+pub async fn list(project: &Project, service: &str) -> Result<Vec<ModelInfo>, LauncherError> {
+    #[derive(Deserialize)]
+    struct Tags {
+        #[serde(default)]
+        models: Vec<ModelInfo>,
+    }
+
+    let url = format!("{}/api/tags", base_url(project, service).await?);
+    let response = client()
+        .get(&url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| request_error(service, e))?;
+    let body = check(response).await?.text().await.map_err(|e| request_error(service, e))?;
+    let tags: Tags = serde_json::from_str(&body)
+        .map_err(|e| LauncherError::ModelServer { status: None, message: format!("unexpected model list: {}", e) })?;
+    Ok(tags.models)
+}
+
+fn percent(completed: Option<u64>, total: Option<u64>) -> Option<u8> {
+    match (completed, total) {
+        (Some(completed), Some(total)) if total > 0 => Some((completed.min(total) * 100 / total) as u8),
+        _ => None,
+    }
+}
+
+/// Downloads `name`, emitting `model-pull-progress` as the NDJSON stream reports it.
+/// Only changes of status or whole percent are emitted; Ollama reports far more often.
+pub async fn pull(app: &AppHandle, project: &Project, service: &str, name: &str) -> Result<(), LauncherError> {
+    let name = validate_name(name)?;
+    let url = format!("{}/api/pull", base_url(project, service).await?);
+    // `name` is what releases before `model` was introduced read
+    let body = json!({ "model": name, "name": name, "stream": true });
+    let response = client()
+        .post(&url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| request_error(service, e))?;
+    let mut response = check(response).await?;
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut last: Option<(String, Option<u8>)> = None;
+    loop {
+        let chunk = tokio::time::timeout(PULL_IDLE_TIMEOUT, response.chunk())
+            .await
+            .map_err(|_| LauncherError::ModelServer {
+                status: None,
+                message: format!("no progress for {} seconds", PULL_IDLE_TIMEOUT.as_secs()),
+            })?
+            .map_err(|e| request_error(service, e))?;
+        let done = chunk.is_none();
+        match chunk {
+            Some(chunk) => buffer.extend_from_slice(&chunk),
+            // A last line without its newline still counts
+            None if !buffer.is_empty() => buffer.push(b'\n'),
+            None => {}
+        }
+        while let Some(end) = buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let Ok(line) = serde_json::from_slice::<PullLine>(&line) else {
+                continue;
+            };
+            if let Some(error) = line.error {
+                return Err(LauncherError::ModelServer { status: None, message: error });
+            }
+            let percent = percent(line.completed, line.total);
+            let changed = last.as_ref().is_none_or(|(status, last)| *status != line.status || *last != percent);
+            if changed {
+                last = Some((line.status.clone(), percent));
+                let progress = PullProgress {
+                    model: name.to_string(),
+                    status: line.status,
+                    completed: line.completed,
+                    total: line.total,
+                    percent,
+                };
+                app.emit("model-pull-progress", progress).ok();
+            }
+        }
+        if done {
+            break;
+        }
+    }
+
+    // The stream ends with `success`; ending without it means the pull was cut off
+    match last {
+        Some((status, _)) if status == "success" => Ok(()),
+        _ => Err(LauncherError::ModelServer { status: None, message: "the pull ended before completing".to_string() }),
+    }
+}
+
+/// Removes `name`, unless it's the model the backend is configured to use.
+pub async fn delete(project: &Project, service: &str, name: &str) -> Result<(), LauncherError> {
+    let name = validate_name(name)?;
+    if let Some(active) = active_model(&project.dir)?.filter(|active| same_model(active, name)) {
+        return Err(LauncherError::ActiveModel { model: active });
+    }
+    let url = format!("{}/api/delete", base_url(project, service).await?);
+    let response = client()
+        .delete(&url)
+        .timeout(REQUEST_TIMEOUT)
+        .header(CONTENT_TYPE, "application/json")
+        .body(json!({ "model": name, "name": name }).to_string())
+        .send()
+        .await
+        .map_err(|e| request_error(service, e))?;
+    check(response).await?;
+    Ok(())
+}
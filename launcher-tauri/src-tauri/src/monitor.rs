@@ -0,0 +1,232 @@
+use crate::autorestart;
+use crate::compose::{self, Project, ServiceStatus};
+use crate::error::LauncherError;
+use crate::events;
+use crate::logs::LogSource;
+use crate::messages::MessageKey;
+use crate::notify::{self, Notice};
+use crate::state::LauncherState;
+use crate::uptime;
+use crate::{
+    docker, emit_log_from, emit_status, observe_services, resolve_project, Phase, SettingsStore, StatusEvent,
+    LAST_STATUS,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::AppHandle;
+use tauri::{Emitter, Manager};
+use tokio::sync::{watch, Notify};
+
+/// Lines of a crashed container's output sent along with its `service-crashed` event.
+const CRASH_LOG_LINES: usize = 50;
+/// Poll interval while `docker events` reports changes as they happen. The poll is
+/// then only a safety net for events the stream missed.
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Signals the status poller to stop, or to poll right away.
+pub struct Monitor {
+    shutdown: watch::Sender<bool>,
+    wake: Arc<Notify>,
+}
+
+/// A service whose container stopped while no operation was in flight.
+#[derive(Clone, Serialize)]
+pub struct CrashEvent {
+    pub service: String,
+    /// The container's state now, or None when it is gone altogether.
+    pub state: Option<String>,
+    pub exit_code: Option<i64>,
+    /// The container's last lines of output, oldest first.
+    pub logs: Vec<String>,
+}
+
+impl CrashEvent {
+    fn describe(&self) -> String {
+        match (self.state.as_deref(), self.exit_code) {
+            (Some("restarting"), Some(code)) => format!("{} is restarting after exiting with code {}", self.service, code),
+            (None, _) => format!("{} container was removed", self.service),
+            (_, Some(code)) => format!("{} exited with code {}", self.service, code),
+            (Some(state), None) => format!("{} is {}", self.service, state),
+        }
+    }
+}
+
+/// What the poller remembers between rounds to tell crashes from deliberate stops.
+#[derive(Default)]
+struct Watch {
+    /// Container id of every service that was running at the last idle poll. Cleared
+    /// while an operation runs, so the stack it leaves behind becomes the new baseline.
+    running: Option<HashMap<String, String>>,
+    /// Services seen crashing that have not come back, with what happened to them.
+    crashed: BTreeMap<String, String>,
+    /// Project the baseline was taken in. Switching environments starts a new one.
+    project_dir: Option<String>,
+    /// The daemon went away under a running stack and has not answered since.
+    daemon_lost: bool,
+}
+
+pub fn start(app: &AppHandle) {
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let wake = Arc::new(Notify::new());
+    app.manage(Monitor { shutdown, wake: wake.clone() });
+    tauri::async_runtime::spawn(poll_loop(app.clone(), shutdown_rx, wake));
+}
+
+pub fn shutdown(app: &AppHandle) {
+    if let Some(monitor) = app.try_state::<Monitor>() {
+        monitor.shutdown.send(true).ok();
+        monitor.wake.notify_one();
+    }
+}
+
+/// Polls now instead of at the end of the interval.
+pub fn nudge(app: &AppHandle) {
+    if let Some(monitor) = app.try_state::<Monitor>() {
+        monitor.wake.notify_one();
+    }
+}
+
+async fn crash_report(project: &Project, service: String, container_id: &str, now: Option<&ServiceStatus>) -> CrashEvent {
+    CrashEvent {
+        exit_code: docker::exit_code(container_id).await,
+        logs: compose::tail_logs(project, &service, CRASH_LOG_LINES).await.unwrap_or_default(),
+        state: now.map(|status| status.state.clone()),
+        service,
+    }
+}
+
+/// Compares this round's containers against the last, reporting crashes and recoveries.
+async fn check_services(app: &AppHandle, project: &Project, watch: &mut Watch, services: &[ServiceStatus]) {
+    let running: HashMap<String, String> = services
+        .iter()
+        .filter(|status| status.is_running())
+        .map(|status| (status.service.clone(), status.container_id.clone()))
+        .collect();
+
+    watch.crashed.retain(|service, _| {
+        let recovered = running.contains_key(service);
+        if recovered {
+            let message = MessageKey::ServiceRecovered.with(json!({ "service": service }));
+            emit_log_from(app, LogSource::Launcher, Some(service), "info", message);
+        }
+        !recovered
+    });
+
+    let Some(before) = watch.running.replace(running.clone()) else {
+        return;
+    };
+    for (service, container_id) in before {
+        if running.contains_key(&service) {
+            continue;
+        }
+        let now = services.iter().find(|status| status.service == service);
+        let crash = crash_report(project, service, &container_id, now).await;
+        let description = crash.describe();
+        let message = MessageKey::ServiceCrashed.with(json!({ "crash": description }));
+        emit_log_from(app, LogSource::Launcher, Some(&crash.service), "error", message);
+        notify::send(app, Notice::Unexpected, "A.B.E.L. service crashed", description.clone());
+        uptime::clear(app);
+        watch.crashed.insert(crash.service.clone(), description);
+        if app.state::<SettingsStore>().get().auto_restart {
+            autorestart::schedule(app, project.clone(), crash.service.clone());
+        }
+        app.emit("service-crashed", crash).ok();
+    }
+}
+
+/// Shows the stack as `DaemonLost` rather than stopped: containers outlive a daemon
+/// that crashed or is restarting. Only a stack that was up is reported.
+fn daemon_lost(app: &AppHandle, watch: &mut Watch, error: &LauncherError) {
+    if watch.daemon_lost {
+        return;
+    }
+    let last = LAST_STATUS.lock().unwrap().clone();
+    let Some(last) = last.filter(|last| matches!(last.phase, Phase::Running | Phase::Degraded)) else {
+        return;
+    };
+    watch.daemon_lost = true;
+    let message = MessageKey::DaemonLost.with(json!({ "error": error.to_string() }));
+    emit_log_from(app, LogSource::Launcher, None, "error", message);
+    notify::send(
+        app,
+        Notice::Unexpected,
+        "Docker stopped responding",
+        "A.B.E.L. may still be running. Its state is checked again once Docker is back.",
+    );
+    emit_status(
+        app,
+        StatusEvent { phase: Phase::DaemonLost, error: Some(error.to_string()), healthy: false, running: true, ..last },
+    );
+}
+
+/// Containers may have stopped, or been restarted by their policy, while the daemon
+/// was gone; a fresh baseline keeps those from being reported as crashes.
+async fn daemon_returned(app: &AppHandle, watch: &mut Watch) {
+    watch.daemon_lost = false;
+    watch.running = None;
+    emit_log_from(app, LogSource::Launcher, None, "info", MessageKey::DaemonReturned);
+    uptime::reconcile(app.clone()).await;
+}
+
+async fn poll_loop(app: AppHandle, shutdown: watch::Receiver<bool>, wake: Arc<Notify>) {
+    let mut watch = Watch::default();
+    loop {
+        let mut interval = Duration::from_secs(app.state::<SettingsStore>().get().poll_interval_secs.max(1));
+        if events::is_live(&app) {
+            interval = interval.max(RECONCILE_INTERVAL);
+        }
+        // Either the interval elapses, an event woke the poll or a shutdown was requested
+        tokio::time::timeout(interval, wake.notified()).await.ok();
+        if *shutdown.borrow() {
+            break;
+        }
+
+        // Commands in flight own the status; polling now would contradict them
+        let busy = || app.state::<Mutex<LauncherState>>().lock().unwrap().is_busy();
+        if busy() {
+            watch = Watch::default();
+            continue;
+        }
+
+        let project = resolve_project(&app);
+        if watch.project_dir.as_ref() != Some(&project.dir) {
+            watch = Watch { project_dir: Some(project.dir.clone()), ..Watch::default() };
+        }
+        let (mut status, services) = match observe_services(&project).await {
+            Ok(observed) => observed,
+            Err(error @ LauncherError::DaemonUnreachable { .. }) => {
+                daemon_lost(&app, &mut watch, &error);
+                continue;
+            }
+            Err(_) => continue,
+        };
+        if watch.daemon_lost {
+            daemon_returned(&app, &mut watch).await;
+        }
+        if busy() {
+            watch = Watch::default();
+            continue;
+        }
+
+        check_services(&app, &project, &mut watch, &services).await;
+        // A crash keeps the stack degraded, even with nothing left up, until the service returns
+        if !watch.crashed.is_empty() && matches!(status.phase, Phase::Stopped | Phase::Degraded) {
+            let error = watch.crashed.values().cloned().collect::<Vec<_>>().join("; ");
+            status = StatusEvent { phase: Phase::Degraded, error: Some(error), ..status };
+        }
+
+        let changed = {
+            let last = LAST_STATUS.lock().unwrap();
+            // A failure stays on screen until containers actually change, instead of decaying to Stopped
+            let failure_kept = status.phase == Phase::Stopped
+                && last.as_ref().is_some_and(|last| last.phase == Phase::Failed && last.services_up == 0);
+            !failure_kept && last.as_ref() != Some(&status)
+        };
+        if changed {
+            emit_status(&app, status);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::compose::{self, Project, ServiceStatus};
+use crate::docker;
+use crate::error::LauncherError;
+use crate::ports;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    /// A network the compose file declares exists on the daemon.
+    NetworkExists,
+    /// A running service's container is attached to each network it joins.
+    Attached,
+    /// A published host port accepts TCP connections.
+    PortOpen,
+}
+
+/// One pass/fail line of the report. Failures say what to look at.
+#[derive(Clone, Serialize)]
+pub struct NetworkCheck {
+    pub kind: CheckKind,
+    pub passed: bool,
+    pub message: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct AttachedContainer {
+    pub container: String,
+    /// Without the `/16` suffix docker appends.
+    pub ipv4: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct NetworkInfo {
+    pub name: String,
+    pub driver: Option<String>,
+    pub containers: Vec<AttachedContainer>,
+}
+
+/// Result of `diagnose_network`.
+#[derive(Clone, Serialize)]
+pub struct NetworkReport {
+    pub networks: Vec<NetworkInfo>,
+    pub ports: Vec<ports::PortProbe>,
+    pub checks: Vec<NetworkCheck>,
+    pub passed: bool,
+}
+
+/// Daemon-side name of each declared network, keyed the way services refer to them.
+/// `config` always declares `default` unless every service opts out of it.
+fn declared_networks(config: &Value, project_name: &str) -> Vec<(String, String)> {
+    let Some(networks) = config.get("networks").and_then(Value::as_object) else {
+        return vec![("default".to_string(), format!("{}_default", project_name))];
+    };
+    networks
+        .iter()
+        .map(|(key, definition)| {
+            let name = definition
+                .get("name")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}_{}", project_name, key));
+            (key.clone(), name)
+        })
+        .collect()
+}
+
+/// Network keys a service joins: a list or a map under `networks`, else `default`.
+/// Services on `network_mode` join none of the project's networks.
+fn service_networks(definition: &Value) -> Vec<String> {
+    if definition.get("network_mode").is_some() {
+        return Vec::new();
+    }
+    match definition.get("networks") {
+        Some(Value::Object(networks)) => networks.keys().cloned().collect(),
+        Some(Value::Array(networks)) => networks.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+        _ => vec!["default".to_string()],
+    }
+}
+
+/// `network inspect` prints the networks that exist and exits non-zero over the rest.
+async fn inspect(names: &[String]) -> Vec<Value> {
+    let Ok(output) = docker::command()
+        .args(["network", "inspect"])
+        .args(names)
+        .kill_on_drop(true)
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    match serde_json::from_slice(&output.stdout) {
+        Ok(Value::Array(entries)) => entries,
+        _ => Vec::new(),
+    }
+}
+
+/// Attached containers by full id, from an inspected network's `Containers`.
+fn attached(network: &Value) -> Vec<(String, AttachedContainer)> {
+    let Some(containers) = network.get("Containers").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    containers
+        .iter()
+        .map(|(id, container)| {
+            let field = |key: &str| container.get(key).and_then(Value::as_str).filter(|value| !value.is_empty());
+            let attached = AttachedContainer {
+                container: field("Name").unwrap_or(id).to_string(),
+                ipv4: field("IPv4Address").map(|address| address.split('/').next().unwrap_or(address).to_string()),
+            };
+            (id.clone(), attached)
+        })
+        .collect()
+}
+
+fn check(kind: CheckKind, passed: bool, message: String) -> NetworkCheck {
+    NetworkCheck { kind, passed, message }
+}
+
+pub async fn diagnose(project: &Project) -> Result<NetworkReport, LauncherError> {
+    let config = compose::config_json(project).await?;
+    let project_name = compose::project_name(&config, project);
+    let declared = declared_networks(&config, &project_name);
+    let statuses = compose::service_status(project).await.unwrap_or_default();
+    let running: Vec<&ServiceStatus> = statuses.iter().filter(|status| status.is_running()).collect();
+
+    let names: Vec<String> = declared.iter().map(|(_, name)| name.clone()).collect();
+    let inspected = inspect(&names).await;
+    let find = |name: &str| inspected.iter().find(|network| network.get("Name").and_then(Value::as_str) == Some(name));
+
+    let mut checks = Vec::new();
+    let mut networks = Vec::new();
+    for (_, name) in &declared {
+        match find(name) {
+            Some(network) => {
+                checks.push(check(CheckKind::NetworkExists, true, format!("network {} exists", name)));
+                networks.push(NetworkInfo {
+                    name: name.clone(),
+                    driver: network.get("Driver").and_then(Value::as_str).map(str::to_string),
+                    containers: attached(network).into_iter().map(|(_, container)| container).collect(),
+                });
+            }
+            // Expected before the first boot; compose creates networks on `up`
+            None if running.is_empty() => {}
+            None => checks.push(check(
+                CheckKind::NetworkExists,
+                false,
+                format!("network {} does not exist - stop and boot A.B.E.L. again to recreate it", name),
+            )),
+        }
+    }
+
+    let services = config.get("services").and_then(Value::as_object);
+    for status in &running {
+        let Some(definition) = services.and_then(|services| services.get(&status.service)) else {
+            continue;
+        };
+        for key in service_networks(definition) {
+            let Some((_, name)) = declared.iter().find(|(declared, _)| *declared == key) else {
+                continue;
+            };
+            let Some(network) = find(name) else {
+                continue;
+            };
+            let is_attached = attached(network)
+                .iter()
+                .any(|(id, _)| !status.container_id.is_empty() && id.starts_with(&status.container_id));
+            let message = if is_attached {
+                format!("{} container is attached to network {}", status.service, name)
+            } else {
+                format!(
+                    "{} container is not attached to network {} - restart the service so compose reconnects it",
+                    status.service, name
+                )
+            };
+            checks.push(check(CheckKind::Attached, is_attached, message));
+        }
+    }
+
+    let running_names: Vec<String> = running.iter().map(|status| status.service.clone()).collect();
+    let ports = ports::probe_published(&config, &running_names).await;
+    for probe in &ports {
+        let message = if probe.open {
+            format!("{} accepts connections on {}", probe.service, probe.address)
+        } else {
+            format!(
+                "{} publishes {} but nothing accepts connections there - check its logs and any firewall",
+                probe.service, probe.address
+            )
+        };
+        checks.push(check(CheckKind::PortOpen, probe.open, message));
+    }
+
+    Ok(NetworkReport {
+        passed: checks.iter().all(|check| check.passed),
+        networks,
+        ports,
+        checks,
+    })
+}
@@ -0,0 +1,41 @@
+use crate::{logfile, SettingsStore};
+use crate::AppHandle;
+use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
+
+/// Why a notification is being sent, which decides when it may be skipped.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Notice {
+    /// The outcome of something the user started, like a boot or a shutdown. Skipped
+    /// while the window has focus, since the console already shows it.
+    Requested,
+    /// Something nobody asked for, like a container exiting on its own. Always sent.
+    Unexpected,
+}
+
+fn window_focused(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+/// Shows a native notification, subject to the notification settings.
+pub fn send(app: &AppHandle, notice: Notice, title: &str, body: impl Into<String>) {
+    let settings = app.state::<SettingsStore>().get();
+    if !settings.notifications_enabled {
+        return;
+    }
+    if notice == Notice::Requested && !settings.notify_when_focused && window_focused(app) {
+        return;
+    }
+    let shown = app.notification().builder().title(title).body(body).show();
+    if let Err(e) = shown {
+        logfile::record_internal(app, format!("notification not shown: {}", e));
+    }
+}
+
+/// A failed boot, summarised by the first line of its error.
+pub fn boot_failed(app: &AppHandle, error: &str) {
+    let first_line = error.lines().next().unwrap_or_default();
+    send(app, Notice::Requested, "A.B.E.L. boot failed", first_line);
+}
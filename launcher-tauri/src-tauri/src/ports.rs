@@ -0,0 +1,220 @@
+use crate::compose::{self, Project, ServiceStatus};
+use crate::error::LauncherError;
+use crate::remote;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// How long a published port gets to accept a connection before it counts as closed.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A host port the compose file publishes that something else already holds.
+#[derive(Clone, Debug, Serialize)]
+pub struct PortConflict {
+    pub service: String,
+    pub port: u16,
+    pub protocol: String,
+    /// Name of the process listening on the port, when the OS tells us.
+    pub process: Option<String>,
+}
+
+struct PublishedPort {
+    service: String,
+    host_ip: String,
+    port: u16,
+    protocol: String,
+}
+
+/// `published` is a number or a string, and may be a range like `8000-8010`.
+fn published_range(value: &Value) -> Vec<u16> {
+    if let Some(port) = value.as_u64() {
+        return u16::try_from(port).into_iter().collect();
+    }
+    let Some(text) = value.as_str() else {
+        return Vec::new();
+    };
+    match text.split_once('-') {
+        Some((start, end)) => match (start.trim().parse::<u16>(), end.trim().parse::<u16>()) {
+            (Ok(start), Ok(end)) if start <= end => (start..=end).collect(),
+            _ => Vec::new(),
+        },
+        None => text.trim().parse().into_iter().collect(),
+    }
+}
+
+/// Host ports from `config --format json`, which normalizes every short-form mapping
+/// into `{ target, published, protocol, host_ip }`.
+fn published_ports(config: &Value) -> Vec<PublishedPort> {
+    let Some(services) = config.get("services").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    let mut ports = Vec::new();
+    for (service, definition) in services {
+        let Some(mappings) = definition.get("ports").and_then(Value::as_array) else {
+            continue;
+        };
+        for mapping in mappings {
+            let Some(published) = mapping.get("published") else {
+                continue;
+            };
+            let field = |key: &str| mapping.get(key).and_then(Value::as_str).filter(|s| !s.is_empty());
+            for port in published_range(published) {
+                ports.push(PublishedPort {
+                    service: service.clone(),
+                    host_ip: field("host_ip").unwrap_or("0.0.0.0").to_string(),
+                    port,
+                    protocol: field("protocol").unwrap_or("tcp").to_lowercase(),
+                });
+            }
+        }
+    }
+    ports
+}
+
+/// Browser URL for the first TCP port `service` publishes. A wildcard bind is
+/// reached through localhost, or the remote docker host; a specific `host_ip` is
+/// used as-is.
+pub fn service_url(config: &Value, service: &str) -> Option<String> {
+    let port = published_ports(config)
+        .into_iter()
+        .find(|port| port.service == service && port.protocol == "tcp")?;
+    let host = match port.host_ip.as_str() {
+        "0.0.0.0" | "::" | "" => remote::published_host().unwrap_or_else(|| "localhost".to_string()),
+        ip if ip.contains(':') => format!("[{}]", ip),
+        ip => ip.to_string(),
+    };
+    Some(format!("http://{}:{}", host, port.port))
+}
+
+/// A published TCP port of a running service and whether the host can connect to it.
+#[derive(Clone, Serialize)]
+pub struct PortProbe {
+    pub service: String,
+    pub address: String,
+    pub open: bool,
+}
+
+/// Where the host reaches a port: wildcard binds through loopback, or on the remote
+/// docker host.
+fn connect_host(host_ip: &str) -> String {
+    let remote = remote::published_host().map(|host| host.trim_matches(['[', ']']).to_string());
+    match host_ip {
+        "0.0.0.0" | "" => remote.unwrap_or_else(|| "127.0.0.1".to_string()),
+        "::" => remote.unwrap_or_else(|| "::1".to_string()),
+        ip => ip.to_string(),
+    }
+}
+
+fn accepts_connection(host: &str, port: u16) -> bool {
+    (host, port)
+        .to_socket_addrs()
+        .map(|mut addresses| addresses.any(|address| TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).is_ok()))
+        .unwrap_or(false)
+}
+
+/// Connects to every TCP port the `running` services publish.
+pub async fn probe_published(config: &Value, running: &[String]) -> Vec<PortProbe> {
+    let mut probes = Vec::new();
+    for port in published_ports(config) {
+        if port.protocol != "tcp" || !running.contains(&port.service) {
+            continue;
+        }
+        let host = connect_host(&port.host_ip);
+        let address = match host.contains(':') {
+            true => format!("[{}]:{}", host, port.port),
+            false => format!("{}:{}", host, port.port),
+        };
+        let number = port.port;
+        // Blocking connects, off the async workers
+        let open = tauri::async_runtime::spawn_blocking(move || accepts_connection(&host, number))
+            .await
+            .unwrap_or(false);
+        probes.push(PortProbe { service: port.service, address, open });
+    }
+    probes
+}
+
+/// Only "address in use" counts; e.g. a privileged port is compose's problem to report.
+fn in_use(port: &PublishedPort) -> bool {
+    let address = (port.host_ip.as_str(), port.port);
+    let result = match port.protocol.as_str() {
+        "udp" => UdpSocket::bind(address).map(drop),
+        _ => TcpListener::bind(address).map(drop),
+    };
+    matches!(result, Err(e) if e.kind() == ErrorKind::AddrInUse)
+}
+
+#[cfg(unix)]
+async fn port_owner(port: u16, protocol: &str) -> Option<String> {
+    let filter = match protocol {
+        "udp" => format!("-iUDP:{}", port),
+        _ => format!("-iTCP:{}", port),
+    };
+    let mut command = Command::new("lsof");
+    command.args(["-nP", &filter, "-Fc"]);
+    if protocol != "udp" {
+        command.arg("-sTCP:LISTEN");
+    }
+    let output = command.output().await.ok()?;
+    // `-Fc` prints `p<pid>` / `c<command>` field lines
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix('c'))
+        .map(str::to_string)
+}
+
+#[cfg(windows)]
+async fn port_owner(port: u16, protocol: &str) -> Option<String> {
+    let proto = if protocol == "udp" { "UDP" } else { "TCP" };
+    let netstat = Command::new("netstat").args(["-ano", "-p", proto]).output().await.ok()?;
+    let suffix = format!(":{}", port);
+    let pid = String::from_utf8_lossy(&netstat.stdout).lines().find_map(|line| {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let local = columns.get(1)?;
+        (local.ends_with(&suffix) && (proto == "UDP" || line.contains("LISTENING")))
+            .then(|| columns.last().map(|pid| pid.to_string()))
+            .flatten()
+    })?;
+
+    let tasklist = Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/FO", "CSV", "/NH"])
+        .output()
+        .await
+        .ok()?;
+    // `"name.exe","1234",...`
+    String::from_utf8_lossy(&tasklist.stdout)
+        .split(',')
+        .next()
+        .map(|name| name.trim().trim_matches('"').to_string())
+        .filter(|name| !name.is_empty() && !name.starts_with("INFO"))
+}
+
+/// Published host ports that are already taken. Ports of services whose containers
+/// are running are skipped, since those are held by the stack itself.
+pub async fn find_conflicts(project: &Project) -> Result<Vec<PortConflict>, LauncherError> {
+    // The ports are bound on the remote machine, where nothing here can look
+    if remote::published_host().is_some() {
+        return Ok(Vec::new());
+    }
+    let config = compose::config_json(project).await?;
+    let statuses = compose::service_status(project).await.unwrap_or_default();
+    let running = |service: &str| statuses.iter().any(|s: &ServiceStatus| s.service == service && s.is_running());
+
+    let mut conflicts = Vec::new();
+    for port in published_ports(&config) {
+        if running(&port.service) || !in_use(&port) {
+            continue;
+        }
+        conflicts.push(PortConflict {
+            process: port_owner(port.port, &port.protocol).await,
+            service: port.service,
+            port: port.port,
+            protocol: port.protocol,
+        });
+    }
+    Ok(conflicts)
+}
@@ -0,0 +1,482 @@
+use crate::error::LauncherError;
+use crate::logs::LogSource;
+use crate::messages::MessageKey;
+use crate::settings::SettingsStore;
+use crate::{ansi, display_time, emit_log, logfile, record_event, LogEvent};
+use serde_json::json;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::progress::ProgressTracker;
+use crate::AppHandle;
+use tauri::{Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Child;
+use tokio::sync::mpsc;
+
+/// Outcome of a child process whose output was streamed to the console.
+pub struct StreamedExit {
+    pub success: bool,
+    pub code: Option<i32>,
+    pub last_stderr: Option<String>,
+    /// The idle timeout the child was killed for exceeding, when it went silent that long.
+    pub timed_out: Option<Duration>,
+}
+
+impl StreamedExit {
+    pub fn describe(&self) -> String {
+        self.error().to_string()
+    }
+
+    /// The failure this exit represents, classified by its last stderr line.
+    pub fn error(&self) -> LauncherError {
+        // The launcher's own kill, whose signal says nothing about the command
+        if let Some(timeout) = self.timed_out {
+            return LauncherError::TimedOut { secs: timeout.as_secs() };
+        }
+        LauncherError::from_failure(self.code, self.last_stderr.as_deref().unwrap_or_default())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// One line of child output, already cleaned by [`ansi::sanitize`].
+pub struct OutputLine {
+    pub stream: OutputStream,
+    pub text: String,
+    pub color: Option<&'static str>,
+}
+
+// Lines are read from the pipes in small batches; the bounded channel pushes back on
+// the readers (and thus the child's pipes) when full.
+pub const LOG_CHANNEL_CAPACITY: usize = 512;
+pub const LOG_BATCH_SIZE: usize = 64;
+// They reach the frontend as `log_batch` events, at most one per interval, so model
+// loading printing thousands of lines a second can't make the webview unresponsive.
+pub const LOG_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+pub const LOG_EVENT_BATCH_MAX: usize = 500;
+/// Lines waiting for a flush. Past this the console drops them; the history and the
+/// log file still have them.
+pub const LOG_QUEUE_CAPACITY: usize = 5000;
+
+/// Child process lines on their way to the frontend.
+pub struct LogBatcher {
+    tx: mpsc::Sender<LogEvent>,
+    /// Lines dropped since the last flush.
+    dropped: AtomicU64,
+}
+
+impl LogBatcher {
+    fn new() -> (Self, mpsc::Receiver<LogEvent>) {
+        let (tx, rx) = mpsc::channel(LOG_QUEUE_CAPACITY);
+        (LogBatcher { tx, dropped: AtomicU64::new(0) }, rx)
+    }
+
+    /// Queues `event` for the next flush, or counts it as dropped when the queue is full.
+    fn queue(&self, event: LogEvent) {
+        if self.tx.try_send(event).is_err() {
+            self.dropped.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Lines dropped since the last call.
+    fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::SeqCst)
+    }
+}
+
+pub fn start_log_batcher(app: &AppHandle) {
+    let (batcher, rx) = LogBatcher::new();
+    app.manage(batcher);
+    tauri::async_runtime::spawn(flush_loop(app.clone(), rx));
+}
+
+/// Records a child process line and queues it for the next `log_batch`.
+pub fn emit_line(app: &AppHandle, event: LogEvent) {
+    if !record_event(app, &event) {
+        return;
+    }
+    if let Some(batcher) = app.try_state::<LogBatcher>() {
+        batcher.queue(event);
+    }
+}
+
+/// Waits for queued lines and takes what is there, up to [`LOG_EVENT_BATCH_MAX`].
+async fn next_batch(rx: &mut mpsc::Receiver<LogEvent>, batch: &mut Vec<LogEvent>) -> usize {
+    rx.recv_many(batch, LOG_EVENT_BATCH_MAX).await
+}
+
+async fn flush_loop(app: AppHandle, mut rx: mpsc::Receiver<LogEvent>) {
+    let mut batch = Vec::with_capacity(LOG_EVENT_BATCH_MAX);
+    while next_batch(&mut rx, &mut batch).await > 0 {
+        if let Err(e) = app.emit("log_batch", &batch) {
+            logfile::record_internal(&app, format!("log batch not delivered: {}", e));
+        }
+        batch.clear();
+        let dropped = app.state::<LogBatcher>().take_dropped();
+        if dropped > 0 {
+            emit_log(&app, "warning", MessageKey::LogLinesDropped.with(json!({ "count": dropped })));
+        }
+        tokio::time::sleep(LOG_FLUSH_INTERVAL).await;
+    }
+}
+
+pub fn stderr_level(line: &str) -> &'static str {
+    let lower = line.to_ascii_lowercase();
+    if lower.contains("error") || lower.contains("failed") {
+        "error"
+    } else {
+        "warning"
+    }
+}
+
+async fn forward_lines<R>(reader: R, stream: OutputStream, tx: mpsc::Sender<OutputLine>)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        // read_until also hands back a final chunk without a trailing newline at EOF
+        match reader.read_until(b'\n', &mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let clean = ansi::sanitize(&String::from_utf8_lossy(&buf));
+                let text = clean.text.trim_end();
+                if text.is_empty() {
+                    continue;
+                }
+                let line = OutputLine { stream, text: text.to_string(), color: clean.color };
+                if tx.send(line).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Pid of a running child that another command may need to kill.
+#[derive(Default)]
+pub struct ChildSlot(Mutex<Option<u32>>);
+
+/// Clears the slot once the tracked child has exited.
+pub struct Tracked<'a>(&'a ChildSlot);
+
+impl Drop for Tracked<'_> {
+    fn drop(&mut self) {
+        self.0 .0.lock().unwrap().take();
+    }
+}
+
+impl ChildSlot {
+    fn track(&self, pid: u32) -> Tracked<'_> {
+        *self.0.lock().unwrap() = Some(pid);
+        Tracked(self)
+    }
+
+    /// Kills the tracked child and its descendants. Returns false when nothing was tracked.
+    pub fn kill_tree(&self) -> bool {
+        let Some(pid) = *self.0.lock().unwrap() else {
+            return false;
+        };
+        kill_tree(pid);
+        true
+    }
+}
+
+#[cfg(windows)]
+fn kill_tree(pid: u32) {
+    Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .output()
+        .ok();
+}
+
+#[cfg(unix)]
+fn kill_tree(pid: u32) {
+    // Tracked children lead their own process group, so signal the group
+    Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", pid)])
+        .output()
+        .ok();
+}
+
+pub type LineReceiver = mpsc::Receiver<OutputLine>;
+
+/// Spawns `command` with both pipes attached to a shared line channel.
+pub fn spawn_piped(command: &mut tokio::process::Command) -> Result<(Child, LineReceiver), LauncherError> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| LauncherError::spawn(command.as_std().get_program(), e))?;
+
+    let (tx, rx) = mpsc::channel(LOG_CHANNEL_CAPACITY);
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(forward_lines(stdout, OutputStream::Stdout, tx.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(forward_lines(stderr, OutputStream::Stderr, tx));
+    }
+
+    Ok((child, rx))
+}
+
+/// Whether colour hints survive onto `LogEvent`s, read once per batch.
+pub fn keep_colors(app: &AppHandle) -> bool {
+    app.try_state::<SettingsStore>().is_some_and(|settings| settings.get().ansi_colors)
+}
+
+/// Emits every line of a batch, returning the last stderr line seen.
+pub fn emit_batch(app: &AppHandle, source: LogSource, batch: &mut Vec<OutputLine>) -> Option<String> {
+    let keep_colors = keep_colors(app);
+    let mut last_stderr = None;
+    for line in batch.drain(..) {
+        let level = match line.stream {
+            OutputStream::Stdout => "info",
+            OutputStream::Stderr => stderr_level(&line.text),
+        };
+        let now = chrono::Local::now();
+        emit_line(
+            app,
+            LogEvent {
+                message: line.text.clone(),
+                level: level.to_string(),
+                timestamp: now,
+                display_time: display_time(&now),
+                source,
+                service: None,
+                color: line.color.filter(|_| keep_colors).map(str::to_string),
+                key: None,
+                params: None,
+            },
+        );
+        if line.stream == OutputStream::Stderr {
+            last_stderr = Some(line.text);
+        }
+    }
+    last_stderr
+}
+
+/// Extra controls for a streamed child process.
+#[derive(Clone, Copy, Default)]
+pub struct RunOptions<'a> {
+    /// Records the child so another command can kill it.
+    pub slot: Option<&'a ChildSlot>,
+    /// Kill the child once no output line arrived for this long.
+    pub idle_timeout: Option<Duration>,
+    /// Parse build output into `progress` events.
+    pub track_progress: bool,
+}
+
+/// Hands the child's output to `on_batch` until both pipes close. Returns true when
+/// it stopped instead because no line arrived within `idle_timeout`; the child is
+/// then still running.
+async fn drain<F>(rx: &mut LineReceiver, idle_timeout: Option<Duration>, mut on_batch: F) -> bool
+where
+    F: FnMut(&mut Vec<OutputLine>),
+{
+    let mut batch = Vec::with_capacity(LOG_BATCH_SIZE);
+    loop {
+        // The idle timer restarts with every batch, so busy builds never trip it
+        let received = match idle_timeout {
+            Some(idle) => match tokio::time::timeout(idle, rx.recv_many(&mut batch, LOG_BATCH_SIZE)).await {
+                Ok(received) => received,
+                Err(_) => return true,
+            },
+            None => rx.recv_many(&mut batch, LOG_BATCH_SIZE).await,
+        };
+        if received == 0 {
+            return false;
+        }
+        on_batch(&mut batch);
+        batch.clear();
+    }
+}
+
+/// Spawns `command` and emits one `LogEvent` per output line until the process exits,
+/// with cancellation, idle timeout and progress parsing on request.
+pub async fn run_with(
+    app: &AppHandle,
+    mut command: tokio::process::Command,
+    options: RunOptions<'_>,
+) -> Result<StreamedExit, LauncherError> {
+    let RunOptions { slot, idle_timeout, track_progress } = options;
+    if slot.is_some() {
+        // Own process group so the whole tree (e.g. the compose plugin under `docker`) can be killed
+        #[cfg(unix)]
+        command.process_group(0);
+    }
+    let (mut child, mut rx) = spawn_piped(&mut command)
+        .inspect_err(|e| logfile::record_internal(app, format!("could not spawn compose: {}", e)))?;
+    let _tracked = slot.and_then(|slot| child.id().map(|pid| slot.track(pid)));
+    let mut progress = track_progress.then(ProgressTracker::default);
+
+    let mut last_stderr = None;
+    let timed_out = drain(&mut rx, idle_timeout, |batch| {
+        if let Some(tracker) = progress.as_mut() {
+            for line in batch.iter() {
+                if let Some(event) = tracker.feed(&line.text) {
+                    app.emit("progress", event).ok();
+                }
+            }
+        }
+        // Every streamed child is a compose invocation
+        if let Some(line) = emit_batch(app, LogSource::Compose, batch) {
+            last_stderr = Some(line);
+        }
+    })
+    .await;
+    if timed_out && !slot.is_some_and(ChildSlot::kill_tree) {
+        child.start_kill().ok();
+    }
+
+    let status = child.wait().await?;
+    let success = status.success() && !timed_out;
+    if let (true, Some(tracker)) = (success, progress.as_mut()) {
+        for event in tracker.finish() {
+            app.emit("progress", event).ok();
+        }
+    }
+
+    Ok(StreamedExit {
+        success,
+        code: status.code(),
+        last_stderr,
+        timed_out: idle_timeout.filter(|_| timed_out),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use crate::logs::LogHistory;
+    #[cfg(unix)]
+    use futures_util::future::join;
+    #[cfg(unix)]
+    use std::time::Instant;
+
+    /// Runs `script` under `sh` through [`run_with`], on an app of its own so the
+    /// console history holds only its lines. Returns those lines and the exit.
+    #[cfg(unix)]
+    async fn run_script(script: &str, idle_timeout: Option<Duration>) -> (Vec<String>, StreamedExit) {
+        let app = tauri::test::mock_app();
+        app.manage(LogHistory::default());
+        let mut command = tokio::process::Command::new("sh");
+        command.args(["-c", script]);
+        let options = RunOptions { idle_timeout, ..Default::default() };
+        let exit = run_with(app.handle(), command, options).await.unwrap();
+        let lines = app.state::<LogHistory>().recent(None).into_iter().map(|event| event.message).collect();
+        (lines, exit)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn idle_timeout_kills_a_silent_child_but_not_a_busy_one() {
+        let idle = Duration::from_millis(500);
+        let started = Instant::now();
+        let ((slow, slow_exit), (fast, fast_exit)) = tauri::async_runtime::block_on(join(
+            run_script("echo pulling; exec sleep 30", Some(idle)),
+            // Longer than the timeout in all, but never silent for that long
+            run_script("for i in 1 2 3 4 5 6 7 8; do echo step $i; sleep 0.1; done", Some(idle)),
+        ));
+
+        assert_eq!(slow_exit.timed_out, Some(idle));
+        assert!(!slow_exit.success);
+        assert_eq!(slow, ["pulling"]);
+        assert_eq!(fast_exit.timed_out, None);
+        assert!(fast_exit.success);
+        assert_eq!(fast, (1..=8).map(|i| format!("step {}", i)).collect::<Vec<_>>());
+        // The sleep was killed rather than waited out
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_long_child_does_not_hold_up_a_short_one() {
+        let started = Instant::now();
+        let finished = Mutex::new(Vec::new());
+        let timed = |name: &'static str, script: &'static str| {
+            let finished = &finished;
+            async move {
+                let run = run_script(script, None).await;
+                finished.lock().unwrap().push((name, started.elapsed()));
+                run
+            }
+        };
+        let ((long, long_exit), (short, short_exit)) = tauri::async_runtime::block_on(join(
+            timed("long", "echo pulling; sleep 2; echo pulled"),
+            timed("short", "echo ready"),
+        ));
+
+        assert!(long_exit.success && short_exit.success);
+        assert_eq!(long, ["pulling", "pulled"]);
+        assert_eq!(short, ["ready"]);
+        let finished = finished.into_inner().unwrap();
+        assert_eq!(finished.iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["short", "long"]);
+        assert!(finished[0].1 < Duration::from_secs(2), "{:?}", finished);
+        assert!(finished[1].1 >= Duration::from_secs(2), "{:?}", finished);
+    }
+
+    fn line(i: usize) -> LogEvent {
+        crate::log_event(LogSource::Compose, None, "info", format!("line {}", i))
+    }
+
+    #[test]
+    fn a_full_queue_drops_and_counts_lines() {
+        let (batcher, _rx) = LogBatcher::new();
+        for i in 0..LOG_QUEUE_CAPACITY + 7 {
+            batcher.queue(line(i));
+        }
+        assert_eq!(batcher.take_dropped(), 7);
+        assert_eq!(batcher.take_dropped(), 0);
+    }
+
+    #[test]
+    fn batches_are_flushed_at_the_maximum() {
+        let (batcher, mut rx) = LogBatcher::new();
+        for i in 0..LOG_QUEUE_CAPACITY + 1 {
+            batcher.queue(line(i));
+        }
+        let mut batch = Vec::new();
+        let sizes: Vec<usize> = tauri::async_runtime::block_on(async {
+            let mut sizes = Vec::new();
+            for _ in 0..LOG_QUEUE_CAPACITY / LOG_EVENT_BATCH_MAX {
+                sizes.push(next_batch(&mut rx, &mut batch).await);
+                batch.clear();
+            }
+            sizes
+        });
+        assert_eq!(sizes, vec![LOG_EVENT_BATCH_MAX; LOG_QUEUE_CAPACITY / LOG_EVENT_BATCH_MAX]);
+        assert_eq!(batcher.take_dropped(), 1);
+
+        // What arrives later goes out without waiting for a full batch, oldest first
+        for i in 0..3 {
+            batcher.queue(line(i));
+        }
+        assert_eq!(tauri::async_runtime::block_on(next_batch(&mut rx, &mut batch)), 3);
+        let messages: Vec<&str> = batch.iter().map(|event| event.message.as_str()).collect();
+        assert_eq!(messages, ["line 0", "line 1", "line 2"]);
+        assert_eq!(batcher.take_dropped(), 0);
+    }
+
+    #[test]
+    fn a_timed_out_exit_reports_the_timeout() {
+        let exit = StreamedExit {
+            success: false,
+            code: None,
+            last_stderr: Some("Pulling fs layer".to_string()),
+            timed_out: Some(Duration::from_secs(900)),
+        };
+        assert_eq!(exit.describe(), "no output for 900 seconds, the command was stopped");
+        assert!(matches!(exit.error(), LauncherError::TimedOut { secs: 900 }));
+    }
+}
@@ -0,0 +1,216 @@
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProgressEvent {
+    pub service: String,
+    pub step: u32,
+    pub total: u32,
+    pub description: String,
+}
+
+/// `#12 [backend 4/9] RUN pip install ...`, `=> CACHED [backend builder 2/5] COPY ...`
+/// or `#5 [4/9] ...` when the target is not named.
+fn buildkit_step() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(?:#\d+\s+|=>\s+)?(?:CACHED\s+)?\[(?:(?P<target>[^\]]*?)\s+)?(?P<step>\d+)/(?P<total>\d+)\]\s+(?P<desc>.+?)\s*$").unwrap()
+    })
+}
+
+/// Classic builder: `Step 3/9 : RUN pip install ...`
+fn classic_step() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^Step (?P<step>\d+)/(?P<total>\d+) : (?P<desc>.+?)\s*$").unwrap())
+}
+
+/// Compose announcing which service the classic builder output belongs to.
+fn building_service() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(?:#\d+\s+)?Building (?P<service>[\w.-]+)\s*$").unwrap())
+}
+
+/// `Service backend  Built`, `✔ backend  Built` (compose v2).
+fn service_built() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(?:[✔✓]\s+)?(?:Service\s+)?(?P<service>[\w.-]+)\s+Built\s*$").unwrap())
+}
+
+#[derive(Default)]
+struct ServiceProgress {
+    step: u32,
+    total: u32,
+    done: bool,
+}
+
+/// Turns build output into progress events. Lines in an unknown format are simply
+/// ignored, so an unrecognized builder degrades to plain logs.
+#[derive(Default)]
+pub struct ProgressTracker {
+    current_service: Option<String>,
+    services: HashMap<String, ServiceProgress>,
+}
+
+impl ProgressTracker {
+    pub fn feed(&mut self, line: &str) -> Option<ProgressEvent> {
+        let line = line.trim();
+
+        if let Some(caps) = buildkit_step().captures(line) {
+            let service = caps
+                .name("target")
+                .and_then(|target| target.as_str().split_whitespace().next())
+                .map(str::to_string)
+                .or_else(|| self.current_service.clone())?;
+            return self.step(service, &caps["step"], &caps["total"], &caps["desc"]);
+        }
+
+        if let Some(caps) = classic_step().captures(line) {
+            let service = self.current_service.clone()?;
+            return self.step(service, &caps["step"], &caps["total"], &caps["desc"]);
+        }
+
+        if let Some(caps) = building_service().captures(line) {
+            self.current_service = Some(caps["service"].to_string());
+            return None;
+        }
+
+        if line.starts_with("Successfully built ") {
+            let service = self.current_service.clone()?;
+            return self.complete(&service);
+        }
+
+        if let Some(caps) = service_built().captures(line) {
+            return self.complete(&caps["service"]);
+        }
+
+        None
+    }
+
+    /// Completion events for every service whose build started but never reported done.
+    pub fn finish(&mut self) -> Vec<ProgressEvent> {
+        let mut pending: Vec<String> = self
+            .services
+            .iter()
+            .filter(|(_, progress)| !progress.done)
+            .map(|(service, _)| service.clone())
+            .collect();
+        pending.sort();
+        pending.iter().filter_map(|service| self.complete(service)).collect()
+    }
+
+    fn step(&mut self, service: String, step: &str, total: &str, description: &str) -> Option<ProgressEvent> {
+        let step = step.parse().ok()?;
+        let total = total.parse().ok()?;
+        let progress = self.services.entry(service.clone()).or_default();
+        // BuildKit repeats a step on every status update; only report movement
+        if progress.step == step && progress.total == total {
+            return None;
+        }
+        *progress = ServiceProgress { step, total, done: false };
+
+        Some(ProgressEvent {
+            service,
+            step,
+            total,
+            description: description.to_string(),
+        })
+    }
+
+    fn complete(&mut self, service: &str) -> Option<ProgressEvent> {
+        let progress = self.services.get_mut(service)?;
+        if progress.done {
+            return None;
+        }
+        progress.done = true;
+        progress.step = progress.total;
+
+        Some(ProgressEvent {
+            service: service.to_string(),
+            step: progress.total,
+            total: progress.total,
+            description: "BUILD COMPLETE".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(service: &str, step: u32, total: u32, description: &str) -> Option<ProgressEvent> {
+        Some(ProgressEvent { service: service.to_string(), step, total, description: description.to_string() })
+    }
+
+    fn complete(service: &str, total: u32) -> Option<ProgressEvent> {
+        event(service, total, total, "BUILD COMPLETE")
+    }
+
+    #[test]
+    fn buildkit_steps_name_their_target() {
+        let mut tracker = ProgressTracker::default();
+        assert_eq!(tracker.feed("#12 [backend 4/9] RUN pip install"), event("backend", 4, 9, "RUN pip install"));
+        assert_eq!(
+            tracker.feed("=> CACHED [frontend builder 2/5] COPY package.json ."),
+            event("frontend", 2, 5, "COPY package.json .")
+        );
+    }
+
+    #[test]
+    fn repeated_buildkit_steps_are_reported_once() {
+        let mut tracker = ProgressTracker::default();
+        assert!(tracker.feed("#12 [backend 4/9] RUN pip install").is_some());
+        assert_eq!(tracker.feed("#12 [backend 4/9] RUN pip install"), None);
+        assert!(tracker.feed("#13 [backend 5/9] COPY . .").is_some());
+    }
+
+    #[test]
+    fn unnamed_steps_belong_to_the_announced_service() {
+        let mut tracker = ProgressTracker::default();
+        assert_eq!(tracker.feed("#5 [4/9] RUN make"), None);
+        assert_eq!(tracker.feed("#1 Building worker"), None);
+        assert_eq!(tracker.feed("#5 [4/9] RUN make"), event("worker", 4, 9, "RUN make"));
+    }
+
+    #[test]
+    fn classic_builder_steps_and_completion() {
+        let mut tracker = ProgressTracker::default();
+        assert_eq!(tracker.feed("Step 3/9 : RUN pip install"), None);
+        assert_eq!(tracker.feed("Building backend"), None);
+        assert_eq!(tracker.feed("Step 3/9 : RUN pip install  "), event("backend", 3, 9, "RUN pip install"));
+        assert_eq!(tracker.feed("Successfully built 3f2a9c1d"), complete("backend", 9));
+        assert_eq!(tracker.feed("Successfully built 3f2a9c1d"), None);
+    }
+
+    #[test]
+    fn compose_reports_services_built() {
+        let mut tracker = ProgressTracker::default();
+        tracker.feed("#12 [backend 4/9] RUN pip install");
+        tracker.feed("#20 [frontend 1/3] FROM node:20");
+        assert_eq!(tracker.feed("✔ backend  Built"), complete("backend", 9));
+        assert_eq!(tracker.feed("Service frontend  Built"), complete("frontend", 3));
+        // A service that never reported a step has nothing to complete
+        assert_eq!(tracker.feed("✔ db  Built"), None);
+    }
+
+    #[test]
+    fn finish_completes_what_never_reported_done() {
+        let mut tracker = ProgressTracker::default();
+        tracker.feed("#20 [frontend 1/3] FROM node:20");
+        tracker.feed("#12 [backend 4/9] RUN pip install");
+        tracker.feed("#30 [worker 2/2] COPY . .");
+        tracker.feed("✔ worker  Built");
+        assert_eq!(tracker.finish(), vec![complete("backend", 9).unwrap(), complete("frontend", 3).unwrap()]);
+        assert!(tracker.finish().is_empty());
+    }
+
+    #[test]
+    fn other_lines_are_ignored() {
+        let mut tracker = ProgressTracker::default();
+        for line in ["#12 DONE 3.4s", "Container abel-backend  Started", "", "[+] Running 3/3", "Step x/9 : RUN"] {
+            assert_eq!(tracker.feed(line), None, "{line}");
+        }
+        assert!(tracker.finish().is_empty());
+    }
+}
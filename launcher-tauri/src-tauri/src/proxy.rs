@@ -0,0 +1,137 @@
+use crate::error::LauncherError;
+use reqwest::{Client, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+const TEST_URL: &str = "https://registry-1.docker.io/v2/";
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Proxy the docker and compose CLIs are run with. Image pulls are made by the
+/// daemon, which keeps proxy settings of its own; builds see these as build args.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    pub http: String,
+    pub https: String,
+    /// Comma-separated hosts that bypass the proxy.
+    pub no_proxy: String,
+    /// Also pass the proxies to image builds as `--build-arg`.
+    pub build_args: bool,
+}
+
+impl ProxySettings {
+    pub fn validate(&self) -> Result<(), LauncherError> {
+        for url in [&self.http, &self.https] {
+            let url = url.trim();
+            if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(LauncherError::invalid(format!("not an http(s) proxy URL: {}", url)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Environment for child processes. Each value is set under both spellings,
+    /// since tools disagree on which one they read. Empty settings give nothing.
+    fn variables(&self) -> Vec<(&'static str, String)> {
+        let mut variables = Vec::new();
+        for (value, names) in [
+            (&self.http, ["HTTP_PROXY", "http_proxy"]),
+            (&self.https, ["HTTPS_PROXY", "https_proxy"]),
+            (&self.no_proxy, ["NO_PROXY", "no_proxy"]),
+        ] {
+            let value = value.trim();
+            if !value.is_empty() {
+                variables.extend(names.map(|name| (name, value.to_string())));
+            }
+        }
+        variables
+    }
+}
+
+/// The saved settings, kept here so spawn sites don't each need the app handle.
+static ACTIVE: Mutex<ProxySettings> = Mutex::new(ProxySettings {
+    http: String::new(),
+    https: String::new(),
+    no_proxy: String::new(),
+    build_args: false,
+});
+
+/// Takes effect for every process spawned from here on.
+pub fn configure(settings: &ProxySettings) {
+    *ACTIVE.lock().unwrap() = settings.clone();
+}
+
+/// Adds the proxy environment to a docker or compose command.
+pub fn apply(command: &mut Command) -> &mut Command {
+    command.envs(ACTIVE.lock().unwrap().variables())
+}
+
+/// `KEY=value` pairs for `compose build --build-arg`, empty unless enabled.
+pub fn build_args() -> Vec<String> {
+    let settings = ACTIVE.lock().unwrap();
+    if !settings.build_args {
+        return Vec::new();
+    }
+    settings
+        .variables()
+        .into_iter()
+        .filter(|(name, _)| name.chars().all(|c| !c.is_ascii_lowercase()))
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect()
+}
+
+/// Result of `test_proxy`.
+#[derive(Clone, Serialize)]
+pub struct ProxyTest {
+    pub success: bool,
+    /// False when no proxy is set and the request went out directly.
+    pub proxied: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+fn proxy_error(e: reqwest::Error) -> LauncherError {
+    LauncherError::invalid(format!("proxy URL is not usable: {}", e))
+}
+
+/// One HEAD against Docker Hub's registry API through `settings`. Any HTTP answer,
+/// the usual 401 included, means the registry was reached.
+pub async fn test(settings: &ProxySettings) -> Result<ProxyTest, LauncherError> {
+    settings.validate()?;
+    let no_proxy = NoProxy::from_string(settings.no_proxy.trim());
+    // System proxies are left out so the result speaks for these settings alone
+    let mut builder = Client::builder().timeout(TEST_TIMEOUT).no_proxy();
+    let http = settings.http.trim();
+    let https = settings.https.trim();
+    if !http.is_empty() {
+        builder = builder.proxy(Proxy::http(http).map_err(proxy_error)?.no_proxy(no_proxy.clone()));
+    }
+    if !https.is_empty() {
+        builder = builder.proxy(Proxy::https(https).map_err(proxy_error)?.no_proxy(no_proxy));
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+
+    let started = Instant::now();
+    let result = client.head(TEST_URL).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let proxied = !https.is_empty();
+    Ok(match result {
+        Ok(response) => ProxyTest {
+            success: true,
+            proxied,
+            status: Some(response.status().as_u16()),
+            latency_ms,
+            error: None,
+        },
+        Err(e) => ProxyTest {
+            success: false,
+            proxied,
+            status: None,
+            latency_ms,
+            error: Some(if e.is_timeout() { "no answer before the timeout".to_string() } else { e.to_string() }),
+        },
+    })
+}
@@ -0,0 +1,137 @@
+use crate::docker;
+use crate::error::LauncherError;
+use crate::runtime::{self, RuntimeKind};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::process::Command;
+
+/// Schemes `docker_host` accepts.
+const SCHEMES: [&str; 2] = ["ssh://", "tcp://"];
+/// How published ports are addressed when the daemon is local.
+const LOOPBACK_HOSTS: [&str; 4] = ["localhost", "127.0.0.1", "[::1]", "0.0.0.0"];
+
+/// Result of `test_remote_connection`.
+#[derive(Clone, Serialize)]
+pub struct RemoteTest {
+    pub docker_host: String,
+    pub reachable: bool,
+    pub server_version: Option<String>,
+    /// Round trip of `docker info`, connection setup included.
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+static DOCKER_HOST: Mutex<Option<String>> = Mutex::new(None);
+
+/// Takes effect for every process spawned from here on. None talks to the local daemon.
+pub fn configure(docker_host: Option<&str>) {
+    *DOCKER_HOST.lock().unwrap() = docker_host.map(str::to_string);
+}
+
+pub fn docker_host() -> Option<String> {
+    DOCKER_HOST.lock().unwrap().clone()
+}
+
+/// `user@host:port` for ssh, `host:port` for tcp, as docker parses them.
+fn authority(docker_host: &str) -> Option<&str> {
+    let rest = SCHEMES.iter().find_map(|scheme| docker_host.strip_prefix(scheme))?;
+    let authority = rest.split('/').next().unwrap_or(rest);
+    (!authority.is_empty()).then_some(authority)
+}
+
+pub fn validate(docker_host: &str) -> Result<(), LauncherError> {
+    if authority(docker_host).and_then(|authority| hostname(authority)).is_none() {
+        return Err(LauncherError::invalid(format!(
+            "not a remote docker host: {} (use ssh://user@host or tcp://host:2376)",
+            docker_host
+        )));
+    }
+    Ok(())
+}
+
+fn hostname(authority: &str) -> Option<&str> {
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host = match host.strip_prefix('[') {
+        // `[::1]:2376`: the brackets stay, as a URL needs them
+        Some(v6) => &host[..v6.find(']')? + 2],
+        None => host.split(':').next().unwrap_or(host),
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// The machine published ports are on, when the daemon is remote.
+pub fn published_host() -> Option<String> {
+    if runtime::current() != RuntimeKind::Docker {
+        return None;
+    }
+    let docker_host = docker_host()?;
+    hostname(authority(&docker_host)?).map(str::to_string)
+}
+
+/// `url` with a loopback host swapped for the remote machine, e.g. the readiness
+/// endpoint `http://localhost:8000/health`.
+pub fn localize(url: &str) -> String {
+    let Some(remote) = published_host() else {
+        return url.to_string();
+    };
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(end);
+    let (host, port) = match authority.rfind(':').filter(|colon| !authority[*colon..].contains(']')) {
+        Some(colon) => authority.split_at(colon),
+        None => (authority, ""),
+    };
+    if !LOOPBACK_HOSTS.contains(&host) {
+        return url.to_string();
+    }
+    format!("{}://{}{}{}", scheme, remote, port, path)
+}
+
+/// Points a docker or compose command at the remote daemon.
+///
+/// Only the daemon moves. Compose still runs here, reading the compose file, `.env`
+/// and build contexts from the local project dir and sending builds over the
+/// connection, while containers, volumes and published ports live on the remote
+/// machine. Bind mounts such as `./backend:/app` are resolved there, so they only
+/// work where the same path exists on the server. Podman reaches remote machines
+/// through its own connections, which this doesn't cover.
+pub fn apply(command: &mut Command) -> &mut Command {
+    if runtime::current() != RuntimeKind::Docker {
+        return command;
+    }
+    if let Some(docker_host) = docker_host() {
+        // Set after the context, which a host always overrides
+        command.env("DOCKER_HOST", docker_host).env_remove("DOCKER_CONTEXT");
+    }
+    command
+}
+
+/// Runs `docker info` through `docker_host`, timing the round trip.
+pub async fn test(docker_host: &str) -> RemoteTest {
+    let started = Instant::now();
+    let output = docker::command()
+        .env("DOCKER_HOST", docker_host)
+        .env_remove("DOCKER_CONTEXT")
+        .args(["info", "--format", "{{.ServerVersion}}"])
+        .kill_on_drop(true)
+        .output()
+        .await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let (server_version, error) = match output {
+        Ok(output) if output.status.success() => {
+            (Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|v| !v.is_empty()), None)
+        }
+        Ok(output) => (None, Some(String::from_utf8_lossy(&output.stderr).trim().to_string())),
+        Err(e) => (None, Some(LauncherError::spawn("docker", e).to_string())),
+    };
+    RemoteTest {
+        docker_host: docker_host.to_string(),
+        reachable: server_version.is_some(),
+        server_version,
+        latency_ms,
+        error,
+    }
+}
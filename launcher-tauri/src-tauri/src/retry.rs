@@ -0,0 +1,178 @@
+use crate::docker::DockerStatus;
+use crate::emit_log_from;
+use crate::error::LauncherError;
+use crate::logs::LogSource;
+use crate::messages::MessageKey;
+use crate::runtime::ContainerRuntime;
+use serde_json::json;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::AppHandle;
+
+/// Attempts in all, the first included.
+const MAX_ATTEMPTS: u32 = 4;
+/// Doubled after every retry: 0.5s, 1s, 2s.
+const FIRST_DELAY: Duration = Duration::from_millis(500);
+/// How long after the launcher starts the daemon a missing socket or pipe still
+/// means "not up yet" rather than "stopped".
+const LAUNCH_SETTLE: Duration = Duration::from_secs(180);
+
+/// Set when the launcher starts Docker Desktop itself.
+static LAUNCHED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// A piece of docker CLI stderr that clears up on its own within seconds.
+struct Signature {
+    text: &'static str,
+    /// Also what a stopped daemon says, so only transient right after a start
+    /// the launcher triggered.
+    after_launch_only: bool,
+}
+
+/// Matched against lowercased stderr.
+const TRANSIENT: [Signature; 10] = [
+    // Linux and macOS: the socket exists but nothing accepts on it yet
+    Signature { text: "sock: connect: connection refused", after_launch_only: false },
+    Signature { text: "sock: read: connection reset by peer", after_launch_only: false },
+    // Windows: the engine behind the named pipe is still coming up
+    Signature { text: "all pipe instances are busy", after_launch_only: false },
+    Signature { text: "the pipe is being closed", after_launch_only: false },
+    Signature { text: "the pipe has been ended", after_launch_only: false },
+    // The socket or pipe not existing yet reads just like a stopped daemon
+    Signature { text: "docker.sock: connect: no such file or directory", after_launch_only: true },
+    Signature { text: "pipe/docker_engine: the system cannot find the file specified", after_launch_only: true },
+    Signature {
+        text: "pipe/dockerdesktoplinuxengine: the system cannot find the file specified",
+        after_launch_only: true,
+    },
+    Signature { text: "is the docker daemon running", after_launch_only: true },
+    Signature { text: "docker daemon is not running", after_launch_only: true },
+];
+
+/// Notes that the launcher just started the daemon.
+pub fn daemon_launched() {
+    *LAUNCHED_AT.lock().unwrap() = Some(Instant::now());
+}
+
+fn recently_launched() -> bool {
+    LAUNCHED_AT.lock().unwrap().is_some_and(|at| at.elapsed() < LAUNCH_SETTLE)
+}
+
+pub fn is_transient(stderr: &str, after_launch: bool) -> bool {
+    let stderr = stderr.to_lowercase();
+    TRANSIENT
+        .iter()
+        .any(|signature| (after_launch || !signature.after_launch_only) && stderr.contains(signature.text))
+}
+
+/// The daemon's complaint in an error that may be transient. Anything else, a
+/// compose file error or a failed build among them, is final as it is.
+pub fn error_stderr<T>(result: &Result<T, LauncherError>) -> Option<&str> {
+    match result {
+        Err(LauncherError::DaemonUnreachable { message }) => Some(message),
+        Err(LauncherError::CommandFailed { stderr, .. }) => Some(stderr),
+        _ => None,
+    }
+}
+
+/// Runs `attempt` again, with backoff, while `stderr` finds a transient docker
+/// error in what it returned. Returns the last result either way.
+pub async fn retried<T, F, Fut>(app: &AppHandle, what: &str, mut attempt: F, stderr: impl Fn(&T) -> Option<&str>) -> T
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = T>,
+{
+    let mut delay = FIRST_DELAY;
+    let mut tried = 1;
+    loop {
+        let result = attempt().await;
+        let transient = stderr(&result).is_some_and(|stderr| is_transient(stderr, recently_launched()));
+        if !transient || tried == MAX_ATTEMPTS {
+            return result;
+        }
+        // Debug level and from docker, so the console's level filter hides it by default
+        let params = json!({ "what": what, "ms": delay.as_millis() as u64, "attempt": tried + 1, "max": MAX_ATTEMPTS });
+        let message = MessageKey::TransientRetry.with(params);
+        emit_log_from(app, LogSource::Docker, None, "debug", message);
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        tried += 1;
+    }
+}
+
+/// [`ContainerRuntime::probe`] retried through a daemon that is still coming up.
+pub async fn docker_status(app: &AppHandle, runtime: &dyn ContainerRuntime, what: &str) -> DockerStatus {
+    let probe = retried(app, what, || runtime.probe(), |probe| {
+        (probe.status == DockerStatus::DaemonStopped).then_some(probe.stderr.as_str())
+    })
+    .await;
+    probe.status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Docker CLI stderr, whether it is transient on its own, and right after a launch.
+    const CASES: [(&str, bool, bool); 15] = [
+        // Windows
+        ("open //./pipe/docker_engine: All pipe instances are busy.", true, true),
+        ("error during connect: open //./pipe/dockerDesktopLinuxEngine: The pipe is being closed.", true, true),
+        ("error during connect: read //./pipe/docker_engine: The pipe has been ended.", true, true),
+        (
+            "error during connect: this error may indicate that the docker daemon is not running: \
+             open //./pipe/docker_engine: The system cannot find the file specified.",
+            false,
+            true,
+        ),
+        ("open //./pipe/dockerDesktopLinuxEngine: The system cannot find the file specified.", false, true),
+        // macOS
+        ("dial unix /Users/abel/.docker/run/docker.sock: connect: connection refused", true, true),
+        (
+            "Cannot connect to the Docker daemon at unix:///Users/abel/.docker/run/docker.sock. \
+             Is the docker daemon running?",
+            false,
+            true,
+        ),
+        // Linux
+        ("dial unix /var/run/docker.sock: connect: no such file or directory", false, true),
+        ("read unix @->/var/run/docker.sock: read: connection reset by peer", true, true),
+        // Failures that waiting does not fix
+        (
+            "permission denied while trying to connect to the Docker daemon socket at \
+             unix:///var/run/docker.sock: dial unix /var/run/docker.sock: connect: permission denied",
+            false,
+            false,
+        ),
+        ("Error response from daemon: pull access denied for abel-backend, repository does not exist", false, false),
+        ("Get \"https://registry-1.docker.io/v2/\": net/http: TLS handshake timeout", false, false),
+        ("dial tcp 10.0.0.5:2376: connect: connection refused", false, false),
+        ("yaml: line 3: mapping values are not allowed in this context", false, false),
+        ("", false, false),
+    ];
+
+    #[test]
+    fn transient_errors_by_platform() {
+        for (stderr, transient, after_launch) in CASES {
+            assert_eq!(is_transient(stderr, false), transient, "{stderr}");
+            assert_eq!(is_transient(stderr, true), after_launch, "after launch: {stderr}");
+        }
+    }
+
+    #[test]
+    fn signatures_are_lowercase() {
+        for signature in &TRANSIENT {
+            assert_eq!(signature.text, signature.text.to_lowercase());
+        }
+    }
+
+    #[test]
+    fn only_daemon_and_command_errors_are_retried() {
+        let unreachable: Result<(), _> = Err(LauncherError::DaemonUnreachable { message: "refused".to_string() });
+        assert_eq!(error_stderr(&unreachable), Some("refused"));
+        let failed: Result<(), _> = Err(LauncherError::from_failure(Some(1), "busy"));
+        assert_eq!(error_stderr(&failed), Some("busy"));
+        assert_eq!(error_stderr(&Err::<(), _>(LauncherError::ComposeMissing)), None);
+        assert_eq!(error_stderr(&Ok::<_, LauncherError>(())), None);
+    }
+}
@@ -0,0 +1,574 @@
+use crate::compose::{self, ComposeInfo, Project, ServiceStatus, StartOptions};
+use crate::containers::{self, ContainerInfo};
+use crate::docker::{self, DaemonProbe, DockerStatus};
+use crate::error::LauncherError;
+use crate::process::{run_with, RunOptions, StreamedExit};
+use crate::stats::{self, StatsEvent};
+use crate::{ansi, engine, events, AppHandle};
+use futures_util::future::{ready, BoxFuture};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io;
+use std::process::{Output, Stdio};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+/// The CLI every container command goes through. Podman's mirrors Docker's closely
+/// enough that the same arguments work for both.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeKind {
+    Docker,
+    Podman,
+}
+
+/// `container_runtime` in the settings.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimePreference {
+    /// Docker when its CLI is installed, else Podman.
+    #[default]
+    Auto,
+    Docker,
+    Podman,
+}
+
+impl RuntimeKind {
+    pub fn program(self) -> &'static str {
+        match self {
+            RuntimeKind::Docker => "docker",
+            RuntimeKind::Podman => "podman",
+        }
+    }
+
+    /// `info --format` template printing the engine version; Podman nests it.
+    pub fn version_template(self) -> &'static str {
+        match self {
+            RuntimeKind::Docker => "{{.ServerVersion}}",
+            RuntimeKind::Podman => "{{.Version.Version}}",
+        }
+    }
+
+    /// What the launcher starts when the engine is down, for the log.
+    pub fn engine_name(self) -> &'static str {
+        match self {
+            RuntimeKind::Docker => "DOCKER DESKTOP",
+            RuntimeKind::Podman => "THE PODMAN MACHINE",
+        }
+    }
+}
+
+/// Result of `check_runtime`.
+#[derive(Clone, Serialize)]
+pub struct RuntimeReport {
+    pub runtime: RuntimeKind,
+    pub preference: RuntimePreference,
+    pub status: DockerStatus,
+    /// None when no compose implementation for the runtime was found.
+    pub compose: Option<ComposeInfo>,
+}
+
+static PREFERENCE: Mutex<RuntimePreference> = Mutex::new(RuntimePreference::Auto);
+// Only a runtime that was found is cached, so installing one later is picked up without a restart.
+static DETECTED: Mutex<Option<RuntimeKind>> = Mutex::new(None);
+
+/// Applies the setting to every command started afterwards.
+pub fn configure(app: &AppHandle, preference: RuntimePreference) {
+    *PREFERENCE.lock().unwrap() = preference;
+    *DETECTED.lock().unwrap() = None;
+    app.state::<ActiveRuntime>().set(for_preference(preference));
+}
+
+pub fn preference() -> RuntimePreference {
+    *PREFERENCE.lock().unwrap()
+}
+
+/// The runtime to run commands with: the configured one, or what [`detect`] found.
+/// Docker until a detection has run.
+pub fn current() -> RuntimeKind {
+    match preference() {
+        RuntimePreference::Docker => RuntimeKind::Docker,
+        RuntimePreference::Podman => RuntimeKind::Podman,
+        RuntimePreference::Auto => DETECTED.lock().unwrap().unwrap_or(RuntimeKind::Docker),
+    }
+}
+
+async fn installed(runtime: RuntimeKind) -> bool {
+    Command::new(runtime.program())
+        .arg("--version")
+        .kill_on_drop(true)
+        .output()
+        .await
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Resolves `auto` by looking for the CLIs, preferring Docker. With neither
+/// installed it answers Docker, whose absence is then what gets reported.
+pub async fn detect() -> RuntimeKind {
+    if preference() != RuntimePreference::Auto {
+        return current();
+    }
+    if let Some(runtime) = *DETECTED.lock().unwrap() {
+        return runtime;
+    }
+    for runtime in [RuntimeKind::Docker, RuntimeKind::Podman] {
+        if installed(runtime).await {
+            *DETECTED.lock().unwrap() = Some(runtime);
+            return runtime;
+        }
+    }
+    RuntimeKind::Docker
+}
+
+pub type RuntimeFuture<'a, T> = BoxFuture<'a, Result<T, LauncherError>>;
+
+/// A container engine the stack runs on. The first methods are the command layer,
+/// which Docker and Podman share and spawn their CLIs for; everything after is built
+/// on it, so a runtime answering those in-process stands in for a real engine.
+pub trait ContainerRuntime: Send + Sync {
+    /// The engine behind this runtime.
+    fn kind(&self) -> BoxFuture<'_, RuntimeKind>;
+
+    /// Runs the engine's own CLI, e.g. `docker info`, and captures its output.
+    fn cli<'a>(&'a self, args: &'a [&'a str]) -> BoxFuture<'a, io::Result<Output>> {
+        Box::pin(async move {
+            let mut command = Command::new(self.kind().await.program());
+            docker::apply_environment(&mut command).args(args).kill_on_drop(true).output().await
+        })
+    }
+
+    /// Runs the engine's own CLI and streams its stdout line by line until it exits.
+    /// Dropping the stream kills the process.
+    fn cli_lines<'a>(&'a self, args: &'a [&'a str]) -> BoxFuture<'a, io::Result<BoxStream<'static, String>>> {
+        Box::pin(async move {
+            let mut command = Command::new(self.kind().await.program());
+            docker::apply_environment(&mut command)
+                .args(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .kill_on_drop(true);
+            let mut child = command.spawn()?;
+            let stdout = child.stdout.take().ok_or_else(|| io::Error::other("stdout was not captured"))?;
+            // The child rides along so it lives exactly as long as the stream
+            let lines = stream::unfold((BufReader::new(stdout).lines(), child), |(mut lines, child)| async move {
+                let line = lines.next_line().await.ok()??;
+                Some((line, (lines, child)))
+            });
+            Ok(lines.boxed())
+        })
+    }
+
+    /// A compose process for `project` with `args`, not started yet.
+    fn compose_command<'a>(&'a self, project: &'a Project, args: &'a [&'a str]) -> RuntimeFuture<'a, Command> {
+        Box::pin(async move { compose::command_for(self.kind().await, project, args).await })
+    }
+
+    /// Runs compose and captures its output.
+    fn compose<'a>(&'a self, project: &'a Project, args: &'a [&'a str]) -> RuntimeFuture<'a, Output> {
+        Box::pin(async move {
+            let mut command = self.compose_command(project, args).await?;
+            command
+                .output()
+                .await
+                .map_err(|e| LauncherError::spawn(command.as_std().get_program(), e))
+        })
+    }
+
+    /// Runs compose, streaming its output to the console.
+    fn compose_streamed<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        project: &'a Project,
+        args: &'a [&'a str],
+        options: RunOptions<'a>,
+    ) -> RuntimeFuture<'a, StreamedExit> {
+        Box::pin(async move { run_with(app, self.compose_command(project, args).await?, options).await })
+    }
+
+    /// Asks the engine for its version, which only a usable daemon answers.
+    fn probe(&self) -> BoxFuture<'_, DaemonProbe> {
+        Box::pin(async move {
+            let template = self.kind().await.version_template();
+            DaemonProbe::read(self.cli(&["info", "--format", template]).await)
+        })
+    }
+
+    /// `up` as `start` asks for it, after a separate `build` when `up` can't do the build.
+    fn compose_up<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        project: &'a Project,
+        start: &'a StartOptions,
+        options: RunOptions<'a>,
+    ) -> RuntimeFuture<'a, StreamedExit> {
+        Box::pin(async move {
+            if start.separate_build() {
+                let build = self.compose_streamed(app, project, &start.build_args(), options).await?;
+                if !build.success {
+                    return Ok(build);
+                }
+            }
+            self.compose_streamed(app, project, &start.up_args(), options).await
+        })
+    }
+
+    /// `down`, giving each container `timeout_secs` to stop before it is killed.
+    fn compose_down<'a>(
+        &'a self,
+        app: &'a AppHandle,
+        project: &'a Project,
+        timeout_secs: u64,
+        options: RunOptions<'a>,
+    ) -> RuntimeFuture<'a, StreamedExit> {
+        Box::pin(async move {
+            let secs = timeout_secs.to_string();
+            self.compose_streamed(app, project, &["down", "-t", &secs], options).await
+        })
+    }
+
+    /// Services declared in the compose file.
+    fn services<'a>(&'a self, project: &'a Project) -> RuntimeFuture<'a, Vec<String>> {
+        Box::pin(async move {
+            let stdout = stdout_of(self.compose(project, &["config", "--services"]).await?)?;
+            Ok(stdout
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect())
+        })
+    }
+
+    /// Containers of the project, including stopped ones.
+    fn ps<'a>(&'a self, project: &'a Project) -> RuntimeFuture<'a, Vec<ServiceStatus>> {
+        Box::pin(compose::ps_cli(self, project))
+    }
+
+    /// Every container of the project with what `container inspect` tells about it.
+    fn inspect<'a>(&'a self, project: &'a Project) -> RuntimeFuture<'a, Vec<ContainerInfo>> {
+        Box::pin(containers::inspect_cli(self, project))
+    }
+
+    /// The last `lines` lines `service` logged, without compose's prefix.
+    fn logs<'a>(&'a self, project: &'a Project, service: &'a str, lines: usize) -> RuntimeFuture<'a, Vec<String>> {
+        Box::pin(async move {
+            let tail = lines.to_string();
+            let args = ["logs", "--no-color", "--no-log-prefix", "--tail", &tail, service];
+            let stdout = stdout_of(self.compose(project, &args).await?)?;
+            Ok(stdout
+                .lines()
+                .map(|line| ansi::sanitize(line).text)
+                .filter(|line| !line.trim().is_empty())
+                .collect())
+        })
+    }
+
+    /// One resource sample per container. Containers that answer nothing are left out.
+    fn stats<'a>(&'a self, container_ids: &'a [String]) -> BoxFuture<'a, Vec<StatsEvent>> {
+        Box::pin(stats::sample_cli(self, container_ids))
+    }
+
+    /// The project's container events in the shape of `docker events --format '{{json .}}'`
+    /// until the daemon goes away, or None when no subscription could be opened.
+    fn events<'a>(&'a self, project: &'a Project) -> BoxFuture<'a, Option<BoxStream<'static, Value>>> {
+        Box::pin(events::subscribe_cli(self, project))
+    }
+
+    /// Kills the containers outright, for when compose itself hung.
+    fn kill<'a>(&'a self, container_ids: &'a [String]) -> RuntimeFuture<'a, ()> {
+        Box::pin(async move {
+            let mut args = vec!["kill"];
+            args.extend(container_ids.iter().map(String::as_str));
+            let program = self.kind().await.program();
+            let output = self.cli(&args).await.map_err(|e| LauncherError::spawn(program, e))?;
+            stdout_of(output).map(drop)
+        })
+    }
+}
+
+/// stdout of a command that succeeded, or the failure its stderr describes.
+pub fn stdout_of(output: Output) -> Result<String, LauncherError> {
+    if !output.status.success() {
+        return Err(LauncherError::from_failure(output.status.code(), &String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// `container_runtime: "docker"`. Reads go to the Engine API where it can be
+/// reached, which answers without a process per call, and to the CLI otherwise.
+pub struct DockerRuntime;
+
+impl ContainerRuntime for DockerRuntime {
+    fn kind(&self) -> BoxFuture<'_, RuntimeKind> {
+        Box::pin(ready(RuntimeKind::Docker))
+    }
+
+    fn ps<'a>(&'a self, project: &'a Project) -> RuntimeFuture<'a, Vec<ServiceStatus>> {
+        Box::pin(async move {
+            match engine::service_status(project).await {
+                Some(statuses) => Ok(statuses),
+                None => compose::ps_cli(self, project).await,
+            }
+        })
+    }
+
+    fn inspect<'a>(&'a self, project: &'a Project) -> RuntimeFuture<'a, Vec<ContainerInfo>> {
+        Box::pin(async move {
+            match engine::inspect_project(project).await {
+                Some(containers) => Ok(containers),
+                None => containers::inspect_cli(self, project).await,
+            }
+        })
+    }
+
+    fn stats<'a>(&'a self, container_ids: &'a [String]) -> BoxFuture<'a, Vec<StatsEvent>> {
+        Box::pin(async move {
+            match engine::stats(container_ids).await {
+                Some(samples) => samples,
+                None => stats::sample_cli(self, container_ids).await,
+            }
+        })
+    }
+
+    fn events<'a>(&'a self, project: &'a Project) -> BoxFuture<'a, Option<BoxStream<'static, Value>>> {
+        Box::pin(async move {
+            match engine::events(project).await {
+                Some(messages) => Some(messages.boxed()),
+                None => events::subscribe_cli(self, project).await,
+            }
+        })
+    }
+}
+
+/// `container_runtime: "podman"`.
+pub struct PodmanRuntime;
+
+impl ContainerRuntime for PodmanRuntime {
+    fn kind(&self) -> BoxFuture<'_, RuntimeKind> {
+        Box::pin(ready(RuntimeKind::Podman))
+    }
+}
+
+/// `container_runtime: "auto"`: whichever [`detect`] finds.
+pub struct DetectedRuntime;
+
+impl DetectedRuntime {
+    async fn resolved(&self) -> &'static dyn ContainerRuntime {
+        match detect().await {
+            RuntimeKind::Docker => &DockerRuntime,
+            RuntimeKind::Podman => &PodmanRuntime,
+        }
+    }
+}
+
+impl ContainerRuntime for DetectedRuntime {
+    fn kind(&self) -> BoxFuture<'_, RuntimeKind> {
+        Box::pin(detect())
+    }
+
+    fn ps<'a>(&'a self, project: &'a Project) -> RuntimeFuture<'a, Vec<ServiceStatus>> {
+        Box::pin(async move { self.resolved().await.ps(project).await })
+    }
+
+    fn inspect<'a>(&'a self, project: &'a Project) -> RuntimeFuture<'a, Vec<ContainerInfo>> {
+        Box::pin(async move { self.resolved().await.inspect(project).await })
+    }
+
+    fn stats<'a>(&'a self, container_ids: &'a [String]) -> BoxFuture<'a, Vec<StatsEvent>> {
+        Box::pin(async move { self.resolved().await.stats(container_ids).await })
+    }
+
+    fn events<'a>(&'a self, project: &'a Project) -> BoxFuture<'a, Option<BoxStream<'static, Value>>> {
+        Box::pin(async move { self.resolved().await.events(project).await })
+    }
+}
+
+pub fn for_preference(preference: RuntimePreference) -> Arc<dyn ContainerRuntime> {
+    match preference {
+        RuntimePreference::Auto => Arc::new(DetectedRuntime),
+        RuntimePreference::Docker => Arc::new(DockerRuntime),
+        RuntimePreference::Podman => Arc::new(PodmanRuntime),
+    }
+}
+
+/// The runtime the app's projects run on. Managed, so tests can put in their own.
+pub struct ActiveRuntime(Mutex<Arc<dyn ContainerRuntime>>);
+
+impl ActiveRuntime {
+    pub fn new(runtime: Arc<dyn ContainerRuntime>) -> Self {
+        ActiveRuntime(Mutex::new(runtime))
+    }
+
+    pub fn get(&self) -> Arc<dyn ContainerRuntime> {
+        self.0.lock().unwrap().clone()
+    }
+
+    fn set(&self, runtime: Arc<dyn ContainerRuntime>) {
+        *self.0.lock().unwrap() = runtime;
+    }
+}
+
+/// The runtime `app` runs its projects on.
+pub fn active(app: &AppHandle) -> Arc<dyn ContainerRuntime> {
+    app.state::<ActiveRuntime>().get()
+}
+
+impl Default for ActiveRuntime {
+    fn default() -> Self {
+        ActiveRuntime::new(Arc::new(DetectedRuntime))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Answers the command layer in-process for a stack of `services`, keeping their
+    /// container state the way the commands leave it. Each command is recorded as
+    /// its program and arguments, e.g. `compose up -d --build`.
+    pub struct MockRuntime {
+        services: Vec<&'static str>,
+        /// The state of every container, None while there are none.
+        state: Mutex<Option<&'static str>>,
+        daemon_up: bool,
+        /// Run for streamed compose commands before answering them, so the real
+        /// process handling is what gets exercised.
+        script: Option<PathBuf>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl MockRuntime {
+        pub fn new(services: &[&'static str]) -> Self {
+            MockRuntime {
+                services: services.to_vec(),
+                state: Mutex::new(None),
+                daemon_up: true,
+                script: None,
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+
+        /// A runtime whose daemon refuses every connection.
+        pub fn daemon_down(self) -> Self {
+            MockRuntime { daemon_up: false, ..self }
+        }
+
+        /// A runtime that runs `script` with the arguments of streamed compose commands.
+        pub fn streaming_through(self, script: PathBuf) -> Self {
+            MockRuntime { script: Some(script), ..self }
+        }
+
+        /// The commands recorded since the last call.
+        pub fn take_calls(&self) -> Vec<String> {
+            std::mem::take(&mut *self.calls.lock().unwrap())
+        }
+
+        fn record(&self, program: &str, args: &[&str]) {
+            self.calls.lock().unwrap().push(format!("{} {}", program, args.join(" ")));
+        }
+
+        fn answer(&self, args: &[&str]) -> Output {
+            let services = &self.services;
+            match args {
+                ["config", "--quiet"] => exited(0, String::new(), ""),
+                ["config", "--services"] => {
+                    exited(0, services.iter().map(|service| format!("{service}\n")).collect(), "")
+                }
+                ["config", "--format", "json"] => {
+                    let services: serde_json::Map<_, _> =
+                        services.iter().map(|service| (service.to_string(), serde_json::json!({}))).collect();
+                    exited(0, serde_json::json!({ "services": services }).to_string(), "")
+                }
+                ["ps", "--all", "--format", "json"] => {
+                    let state = *self.state.lock().unwrap();
+                    let lines = state.into_iter().flat_map(|state| {
+                        services.iter().map(move |service| {
+                            let id = format!("abel-{service}-1");
+                            format!("{}\n", serde_json::json!({ "Service": service, "ID": id, "State": state }))
+                        })
+                    });
+                    exited(0, lines.collect(), "")
+                }
+                args => exited(1, String::new(), &format!("unknown command: {}", args.join(" "))),
+            }
+        }
+    }
+
+    fn exited(code: i32, stdout: String, stderr: &str) -> Output {
+        #[cfg(unix)]
+        let status = std::os::unix::process::ExitStatusExt::from_raw(code << 8);
+        #[cfg(windows)]
+        let status = std::os::windows::process::ExitStatusExt::from_raw(code as u32);
+        Output { status, stdout: stdout.into_bytes(), stderr: stderr.as_bytes().to_vec() }
+    }
+
+    impl ContainerRuntime for MockRuntime {
+        fn kind(&self) -> BoxFuture<'_, RuntimeKind> {
+            Box::pin(ready(RuntimeKind::Docker))
+        }
+
+        fn cli<'a>(&'a self, args: &'a [&'a str]) -> BoxFuture<'a, io::Result<Output>> {
+            self.record("docker", args);
+            let output = match args {
+                ["info", ..] if self.daemon_up => exited(0, "27.3.1\n".to_string(), ""),
+                ["info", ..] => {
+                    exited(1, String::new(), "Cannot connect to the Docker daemon. Is the docker daemon running?")
+                }
+                ["kill", ..] => exited(0, String::new(), ""),
+                args => exited(1, String::new(), &format!("unknown command: {}", args.join(" "))),
+            };
+            Box::pin(ready(Ok(output)))
+        }
+
+        fn cli_lines<'a>(&'a self, args: &'a [&'a str]) -> BoxFuture<'a, io::Result<BoxStream<'static, String>>> {
+            self.record("docker", args);
+            Box::pin(ready(Err(io::ErrorKind::Unsupported.into())))
+        }
+
+        fn compose_command<'a>(&'a self, _: &'a Project, args: &'a [&'a str]) -> RuntimeFuture<'a, Command> {
+            let command = match &self.script {
+                Some(script) => {
+                    let mut command = Command::new(script);
+                    command.args(args).kill_on_drop(true);
+                    Ok(command)
+                }
+                None => Err(LauncherError::NotSupported { message: "the mock runtime spawns nothing".to_string() }),
+            };
+            Box::pin(ready(command))
+        }
+
+        fn compose<'a>(&'a self, _: &'a Project, args: &'a [&'a str]) -> RuntimeFuture<'a, Output> {
+            self.record("compose", args);
+            Box::pin(ready(Ok(self.answer(args))))
+        }
+
+        fn compose_streamed<'a>(
+            &'a self,
+            app: &'a AppHandle,
+            project: &'a Project,
+            args: &'a [&'a str],
+            options: RunOptions<'a>,
+        ) -> RuntimeFuture<'a, StreamedExit> {
+            self.record("compose", args);
+            Box::pin(async move {
+                let exit = match self.script {
+                    Some(_) => run_with(app, self.compose_command(project, args).await?, options).await?,
+                    None => StreamedExit { success: true, code: Some(0), last_stderr: None, timed_out: None },
+                };
+                let state = match args.first() {
+                    Some(&"up") => Some("running"),
+                    Some(&"stop") => Some("exited"),
+                    Some(&"down") => None,
+                    _ => *self.state.lock().unwrap(),
+                };
+                *self.state.lock().unwrap() = state;
+                Ok(exit)
+            })
+        }
+    }
+}
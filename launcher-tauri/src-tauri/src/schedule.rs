@@ -0,0 +1,156 @@
+use crate::error::LauncherError;
+use crate::messages::MessageKey;
+use crate::notify::{self, Notice};
+use crate::state::LauncherState;
+use crate::{emit_log, observe_status, resolve_project, start_services, stop_services, SettingsStore};
+use chrono::{DateTime, Datelike, Duration as TimeDelta, Local, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::AppHandle;
+use tauri::Manager;
+
+const TICK: Duration = Duration::from_secs(60);
+/// A tick this late means the machine slept, so boundaries may have been missed.
+const WAKE_GAP_SECS: i64 = 180;
+const TIME_FORMAT: &str = "%H:%M";
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Daily hours the stack should be up. A `stop` before `start` runs past midnight,
+/// and the weekday is the one the window opens on.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    pub enabled: bool,
+    /// `HH:MM`, local time.
+    pub start: String,
+    pub stop: String,
+    /// `mon` to `sun`.
+    pub weekdays: Vec<String>,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, LauncherError> {
+    NaiveTime::parse_from_str(value.trim(), TIME_FORMAT)
+        .map_err(|_| LauncherError::invalid(format!("not an HH:MM time: {}", value)))
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    WEEKDAYS[day.num_days_from_monday() as usize]
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<(), LauncherError> {
+        if parse_time(&self.start)? == parse_time(&self.stop)? {
+            return Err(LauncherError::invalid("start and stop times must differ"));
+        }
+        if self.enabled && self.weekdays.is_empty() {
+            return Err(LauncherError::invalid("an enabled schedule needs at least one weekday"));
+        }
+        if let Some(day) = self.weekdays.iter().find(|day| !WEEKDAYS.contains(&day.as_str())) {
+            return Err(LauncherError::invalid(format!("unknown weekday: {} (use mon to sun)", day)));
+        }
+        Ok(())
+    }
+
+    fn active_on(&self, day: Weekday) -> bool {
+        self.weekdays.iter().any(|active| active == weekday_name(day))
+    }
+
+    /// Whether the stack should be running at `now`.
+    fn wants_running(&self, now: DateTime<Local>) -> bool {
+        let (Ok(start), Ok(stop)) = (parse_time(&self.start), parse_time(&self.stop)) else {
+            return false;
+        };
+        let time = now.time();
+        let today = now.weekday();
+        if start < stop {
+            self.active_on(today) && start <= time && time < stop
+        } else {
+            (self.active_on(today) && time >= start) || (self.active_on(today.pred()) && time < stop)
+        }
+    }
+}
+
+/// The boundary that wants the stack `running`, for log lines.
+/// The boundary being acted on and how its failure reads, with the time it is at.
+fn boundary_messages(schedule: &Schedule, running: bool) -> (MessageKey, MessageKey, &str) {
+    if running {
+        (MessageKey::ScheduledStart, MessageKey::ScheduledStartFailed, &schedule.start)
+    } else {
+        (MessageKey::ScheduledShutdown, MessageKey::ScheduledShutdownFailed, &schedule.stop)
+    }
+}
+
+fn busy(app: &AppHandle) -> bool {
+    app.state::<Mutex<LauncherState>>().lock().unwrap().is_busy()
+}
+
+/// Drives the stack toward `running`. False when an operation was in flight and the
+/// attempt should be repeated next tick.
+async fn apply(app: &AppHandle, schedule: &Schedule, running: bool) -> bool {
+    if busy(app) {
+        return false;
+    }
+    let Ok(status) = observe_status(&resolve_project(app)).await else {
+        return false;
+    };
+    let up = status.running || status.services_up > 0;
+    if up == running {
+        return true;
+    }
+
+    let (started, failed, time) = boundary_messages(schedule, running);
+    emit_log(app, "info", started.with(json!({ "time": time })));
+    let (result, done) = if running {
+        (start_services(app.clone(), None).await, "A.B.E.L. was started on schedule.")
+    } else {
+        (stop_services(app.clone()).await, "A.B.E.L. was shut down on schedule.")
+    };
+    match result {
+        Ok(()) => notify::send(app, Notice::Unexpected, "A.B.E.L. schedule", done),
+        Err(LauncherError::Busy { .. }) => return false,
+        Err(error) => {
+            let params = json!({ "time": time, "error": error.to_string() });
+            emit_log(app, "error", failed.with(params));
+        }
+    }
+    true
+}
+
+/// Checks the schedule once a minute. Only a crossed boundary acts, so a stack the
+/// user starts or stops by hand is left alone until the next one; after a sleep the
+/// stack is brought to whatever the schedule says now.
+async fn run(app: AppHandle) {
+    let mut last_tick = Local::now();
+    // Baseline from launch: auto-start and state restore cover the launch itself
+    let mut settled: Option<bool> = None;
+    let mut reconcile = false;
+    loop {
+        tokio::time::sleep(TICK).await;
+        let now = Local::now();
+        if now - last_tick > TimeDelta::seconds(WAKE_GAP_SECS) {
+            reconcile = true;
+        }
+        last_tick = now;
+
+        let Some(schedule) = app.state::<SettingsStore>().get().schedule.filter(|schedule| schedule.enabled) else {
+            settled = None;
+            continue;
+        };
+        let wanted = schedule.wants_running(now);
+        if settled.is_none() && !reconcile {
+            settled = Some(wanted);
+            continue;
+        }
+        // An attempt skipped for a busy launcher stays due, and is retried next tick
+        let crossed = settled != Some(wanted);
+        if (crossed || reconcile) && apply(&app, &schedule, wanted).await {
+            settled = Some(wanted);
+            reconcile = false;
+        }
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn(run(app.clone()));
+}
@@ -0,0 +1,98 @@
+use crate::env::{self, EnvLine};
+use crate::error::LauncherError;
+
+/// Service name the credentials are filed under in the OS store.
+const SERVICE: &str = "abel-launcher";
+/// A `.env` value that stands in for the secret stored under the same key.
+pub const PLACEHOLDER: &str = "@keyring";
+
+fn unavailable(e: keyring::Error) -> LauncherError {
+    LauncherError::NotSupported { message: format!("the OS credential store is unavailable: {}", e) }
+}
+
+#[cfg(any(windows, target_os = "macos", target_os = "linux"))]
+fn entry(key: &str) -> Result<keyring::Entry, LauncherError> {
+    keyring::Entry::new(SERVICE, key).map_err(|e| match e {
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_) => unavailable(e),
+        e => LauncherError::invalid(format!("{} can't be stored in the credential store: {}", key, e)),
+    })
+}
+
+/// No credential store backend is built for this platform.
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn entry(_key: &str) -> Result<keyring::Entry, LauncherError> {
+    Err(LauncherError::NotSupported { message: "no OS credential store is supported on this platform".to_string() })
+}
+
+fn store_error(key: &str, e: keyring::Error) -> LauncherError {
+    match e {
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_) => unavailable(e),
+        keyring::Error::NoEntry => LauncherError::invalid(format!("no secret is stored for {}", key)),
+        e => LauncherError::Other { message: format!("secret {} could not be accessed: {}", key, e) },
+    }
+}
+
+pub fn set(key: &str, value: &str) -> Result<(), LauncherError> {
+    entry(key)?.set_password(value).map_err(|e| store_error(key, e))
+}
+
+pub fn get(key: &str) -> Result<String, LauncherError> {
+    entry(key)?.get_password().map_err(|e| store_error(key, e))
+}
+
+/// A secret that is already gone counts as deleted.
+pub fn delete(key: &str) -> Result<(), LauncherError> {
+    match entry(key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(store_error(key, e)),
+    }
+}
+
+/// Keys whose `.env` value is the placeholder.
+pub fn placeholders(project_dir: &str) -> Result<Vec<String>, LauncherError> {
+    Ok(env::read(project_dir)?
+        .into_iter()
+        .filter_map(|line| match line {
+            EnvLine::Entry { key, value } if value == PLACEHOLDER => Some(key),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Values for every placeholder in the project's `.env`, to hand compose through its
+/// process environment. Compose prefers those over `.env` when interpolating, so
+/// `${KEY}` in the compose file sees the secret; an `env_file:` passes the file's
+/// literal text into the container and is not covered.
+pub fn resolve(project_dir: &str) -> Result<Vec<(String, String)>, LauncherError> {
+    placeholders(project_dir)?
+        .into_iter()
+        .map(|key| {
+            let value = get(&key).map_err(|e| match e {
+                LauncherError::InvalidInput { .. } => LauncherError::invalid(format!(
+                    "{} is set to {} in .env but no secret is stored for it",
+                    key, PLACEHOLDER
+                )),
+                e => e,
+            })?;
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Puts `value` back into the `.env` line that points at the keyring for `key`.
+pub fn write_plaintext(project_dir: &str, key: &str, value: &str) -> Result<bool, LauncherError> {
+    let mut lines = env::read(project_dir)?;
+    let mut replaced = false;
+    for line in &mut lines {
+        if let EnvLine::Entry { key: entry_key, value: entry_value } = line {
+            if entry_key == key && entry_value == PLACEHOLDER {
+                *entry_value = value.to_string();
+                replaced = true;
+            }
+        }
+    }
+    if replaced {
+        env::write(project_dir, &lines)?;
+    }
+    Ok(replaced)
+}
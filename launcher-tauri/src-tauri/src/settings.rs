@@ -0,0 +1,843 @@
+use crate::compose::StartOptions;
+use crate::error::LauncherError;
+use crate::logs::LogLevel;
+use crate::proxy::ProxySettings;
+use crate::runtime::RuntimePreference;
+use crate::schedule::Schedule;
+use crate::tray::CloseAction;
+use crate::update::UpdateCheck;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use crate::AppHandle;
+use tauri::Manager;
+
+const SETTINGS_FILE: &str = "settings.json";
+/// Bumped whenever a stored field changes shape or meaning, with a step added to
+/// [`MIGRATIONS`] that carries older files forward.
+pub const SCHEMA_VERSION: u32 = 1;
+/// `MIGRATIONS[n]` turns a version `n` file into version `n + 1`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); SCHEMA_VERSION as usize] = [migrate_unversioned];
+/// Written by the launcher, or owned by commands whose side effects a bare write
+/// would skip, so `update_settings` leaves them alone.
+const NOT_PATCHABLE: [&str; 9] = [
+    "schema_version",
+    "secret_keys",
+    "wanted_running",
+    "last_update_check",
+    "online_since",
+    "environments",
+    "active_environment",
+    "docker_context",
+    "docker_host",
+];
+/// Overrides the project directory for this process, ahead of the saved setting.
+pub const PROJECT_DIR_ENV: &str = "ABEL_PROJECT_DIR";
+
+/// File names docker-compose picks up on its own, in its lookup order.
+pub const COMPOSE_FILE_NAMES: [&str; 4] = [
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// A named stack, e.g. a dev checkout next to a stable install. While one is active
+/// it stands in for `project_dir`, `compose_files` and `active_profiles`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    pub project_dir: String,
+    #[serde(default)]
+    pub compose_files: Vec<String>,
+    #[serde(default)]
+    pub profiles: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Layout version of the file. Written by the launcher.
+    pub schema_version: u32,
+    pub project_dir: Option<String>,
+    /// Named stacks to switch between. Empty runs everything off the settings above.
+    pub environments: Vec<Environment>,
+    /// Name of the environment compose commands run against. None uses `project_dir`.
+    pub active_environment: Option<String>,
+    /// How long to wait for healthchecks after boot before warning.
+    pub health_timeout_secs: u64,
+    /// How long `up` may go without printing anything before the boot is killed.
+    pub boot_timeout_secs: u64,
+    /// Free space a build needs on Docker's data root and the project's volume.
+    pub min_free_disk_gb: u64,
+    /// Refuse to boot below `min_free_disk_gb` instead of only warning.
+    pub refuse_low_disk: bool,
+    /// Refuse to boot when images need pulling and their registry can't be reached,
+    /// instead of only warning.
+    pub refuse_offline_pull: bool,
+    /// Passed to `down -t`: how long containers get to exit before compose kills them.
+    pub stop_timeout_secs: u64,
+    /// What `start_services` boots with when it is given no options.
+    pub start_options: StartOptions,
+    /// Boot the stack as soon as the launcher opens.
+    pub auto_start: bool,
+    /// Launch Docker Desktop when a boot finds its daemon stopped.
+    pub auto_launch_docker: bool,
+    /// Show native notifications for boots, shutdowns and crashes.
+    pub notifications_enabled: bool,
+    /// Also notify about what the user just did while the window has focus.
+    pub notify_when_focused: bool,
+    /// Bring a service that crashed outside any operation back with `up -d`.
+    pub auto_restart: bool,
+    /// Take the stack down before the launcher quits.
+    pub stop_on_exit: bool,
+    /// What closing the window does while services are running.
+    pub on_close: CloseAction,
+    /// Proxy for the docker and compose processes the launcher runs.
+    pub proxy: ProxySettings,
+    /// Docker or Podman, or `auto` to use whichever is installed.
+    pub container_runtime: RuntimePreference,
+    /// `docker context` every docker and compose command uses. None leaves it to the CLI.
+    pub docker_context: Option<String>,
+    /// Remote daemon as `ssh://user@host` or `tcp://host:2376`. None uses the local one.
+    pub docker_host: Option<String>,
+    /// Read status, containers, stats and events from the daemon's socket. Off uses
+    /// the CLI for those too, for a socket the launcher can't open.
+    pub engine_api: bool,
+    /// Global shortcut that boots or stops the stack, e.g. `CmdOrCtrl+Shift+A`.
+    pub toggle_hotkey: Option<String>,
+    /// Loopback port of the local status API for scripts. None keeps it off.
+    pub status_api_port: Option<u16>,
+    /// Bearer token `POST /start` and `POST /stop` require. None refuses them.
+    pub status_api_token: Option<String>,
+    /// Daily hours to keep the stack up. None leaves it to the user.
+    pub schedule: Option<Schedule>,
+    /// Boot the stack at launch when it was running as the launcher last left it.
+    pub restore_state: bool,
+    /// Whether the last boot or shutdown the user asked for left the stack running.
+    /// Written by the launcher, not a preference.
+    pub wanted_running: bool,
+    /// Interval of the background status poller.
+    pub poll_interval_secs: u64,
+    /// Compose files passed as `-f`, relative to the project dir. Empty uses compose's lookup.
+    pub compose_files: Vec<String>,
+    /// Compose profiles enabled with `--profile`.
+    pub active_profiles: Vec<String>,
+    /// Service whose published port serves the web UI.
+    pub web_ui_service: String,
+    /// Compose service running Ollama, for the model commands.
+    pub model_service: String,
+    /// Open the web UI in the browser once a boot passes its healthchecks.
+    pub auto_open: bool,
+    /// Endpoint of the A.B.E.L. core that answers 200 once the API can serve requests.
+    /// Empty skips the readiness wait.
+    pub ready_url: String,
+    /// How long to wait for `ready_url` after the healthchecks pass.
+    pub ready_timeout_secs: u64,
+    /// Delay between readiness probes.
+    pub ready_interval_secs: u64,
+    /// Pass the colours child processes print through to the console as a hint,
+    /// instead of dropping them with the rest of the escape codes.
+    pub ansi_colors: bool,
+    /// Least severe level the console is sent. Everything is still kept in history.
+    pub min_log_level: LogLevel,
+    /// Size at which the launcher log file is rotated. Read at startup.
+    pub log_file_max_kb: u64,
+    /// Names of the secrets kept in the OS keyring, which can't list them itself.
+    /// Written by the launcher.
+    pub secret_keys: Vec<String>,
+    /// Send anonymous boot outcomes to `telemetry_endpoint`. Opt-in.
+    pub telemetry_enabled: bool,
+    /// Where telemetry batches are posted. Empty records nothing.
+    pub telemetry_endpoint: String,
+    /// Ask GitHub for a newer launcher release at startup.
+    pub update_check_on_startup: bool,
+    /// Last answer from the release check, reused for a day. Written by the launcher.
+    pub last_update_check: Option<UpdateCheck>,
+    /// RFC 3339 instant the stack last finished booting, while it is still up.
+    /// Written by the launcher, not a preference.
+    pub online_since: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            project_dir: None,
+            environments: Vec::new(),
+            active_environment: None,
+            health_timeout_secs: 180,
+            boot_timeout_secs: 900,
+            min_free_disk_gb: 5,
+            refuse_low_disk: false,
+            refuse_offline_pull: false,
+            stop_timeout_secs: 30,
+            start_options: StartOptions::default(),
+            auto_start: false,
+            auto_launch_docker: false,
+            notifications_enabled: true,
+            notify_when_focused: false,
+            auto_restart: false,
+            stop_on_exit: false,
+            on_close: CloseAction::default(),
+            proxy: ProxySettings::default(),
+            container_runtime: RuntimePreference::default(),
+            docker_context: None,
+            docker_host: None,
+            engine_api: true,
+            toggle_hotkey: None,
+            status_api_port: None,
+            status_api_token: None,
+            schedule: None,
+            restore_state: false,
+            wanted_running: false,
+            poll_interval_secs: 5,
+            compose_files: Vec::new(),
+            active_profiles: Vec::new(),
+            web_ui_service: "backend".to_string(),
+            model_service: "ollama".to_string(),
+            auto_open: false,
+            ready_url: "http://localhost:8000/health".to_string(),
+            ready_timeout_secs: 120,
+            ready_interval_secs: 3,
+            ansi_colors: false,
+            min_log_level: LogLevel::Info,
+            log_file_max_kb: 5 * 1024,
+            secret_keys: Vec::new(),
+            telemetry_enabled: false,
+            telemetry_endpoint: String::new(),
+            update_check_on_startup: true,
+            last_update_check: None,
+            online_since: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn active_environment(&self) -> Option<&Environment> {
+        let name = self.active_environment.as_deref()?;
+        self.environments.iter().find(|environment| environment.name == name)
+    }
+
+    fn active_environment_mut(&mut self) -> Option<&mut Environment> {
+        let name = self.active_environment.clone()?;
+        self.environments.iter_mut().find(|environment| environment.name == name)
+    }
+
+    /// The project directory setting, from the active environment when there is one.
+    pub fn current_project_dir(&self) -> Option<String> {
+        match self.active_environment() {
+            Some(environment) => Some(environment.project_dir.clone()),
+            None => self.project_dir.clone(),
+        }
+    }
+
+    pub fn current_compose_files(&self) -> Vec<String> {
+        match self.active_environment() {
+            Some(environment) => environment.compose_files.clone(),
+            None => self.compose_files.clone(),
+        }
+    }
+
+    pub fn current_profiles(&self) -> Vec<String> {
+        match self.active_environment() {
+            Some(environment) => environment.profiles.clone(),
+            None => self.active_profiles.clone(),
+        }
+    }
+
+    pub fn set_current_project_dir(&mut self, dir: String) {
+        match self.active_environment_mut() {
+            Some(environment) => environment.project_dir = dir,
+            None => self.project_dir = Some(dir),
+        }
+    }
+
+    pub fn set_current_compose_files(&mut self, files: Vec<String>) {
+        match self.active_environment_mut() {
+            Some(environment) => environment.compose_files = files,
+            None => self.compose_files = files,
+        }
+    }
+
+    pub fn set_current_profiles(&mut self, profiles: Vec<String>) {
+        match self.active_environment_mut() {
+            Some(environment) => environment.profiles = profiles,
+            None => self.active_profiles = profiles,
+        }
+    }
+}
+
+/// Files from before the version field. Every field they can hold still means the same.
+fn migrate_unversioned(_: &mut Map<String, Value>) {}
+
+/// Settings read from a file, and anything about it worth a warning.
+struct Loaded {
+    settings: Settings,
+    warning: Option<String>,
+}
+
+/// Migrates `contents` to [`SCHEMA_VERSION`] and fills in missing fields with their
+/// defaults. Errors mean the file can't be used at all.
+fn parse(contents: &str) -> Result<Loaded, String> {
+    let Value::Object(mut fields) = serde_json::from_str(contents).map_err(|e| e.to_string())? else {
+        return Err("not a JSON object".to_string());
+    };
+    let version = match fields.get("schema_version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or("schema_version is not a number")?,
+    };
+
+    let mut warning = None;
+    if version > SCHEMA_VERSION {
+        warning = Some(format!(
+            "settings were written by a newer launcher (schema {}), fields this version doesn't know are dropped on the next save",
+            version
+        ));
+    }
+    for migrate in MIGRATIONS.iter().skip(version as usize) {
+        migrate(&mut fields);
+    }
+    fields.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+    let settings = serde_json::from_value(Value::Object(fields)).map_err(|e| e.to_string())?;
+    Ok(Loaded { settings, warning })
+}
+
+/// Moves an unusable settings file out of the way, so defaults can replace it
+/// without losing what the user had.
+fn set_aside(path: &Path, reason: &str) -> String {
+    let backup = path.with_file_name(format!("settings.corrupt-{}.json", chrono::Utc::now().format("%Y%m%dT%H%M%SZ")));
+    match fs::rename(path, &backup) {
+        Ok(()) => format!("settings file was unreadable ({}), moved to {} and defaults loaded", reason, backup.display()),
+        Err(e) => format!(
+            "settings file was unreadable ({}) and could not be moved aside ({}), defaults loaded",
+            reason, e
+        ),
+    }
+}
+
+/// Launcher settings persisted as JSON in the app config directory.
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    settings: Mutex<Settings>,
+    /// Why the file wasn't loaded as-is, for setup to log once logging is up.
+    load_warning: Option<String>,
+}
+
+impl SettingsStore {
+    /// Never fails: a missing file gives defaults, and a corrupted one is backed up
+    /// and replaced with them.
+    pub fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(SETTINGS_FILE));
+        Self::load_from(path)
+    }
+
+    fn load_from(path: Option<PathBuf>) -> Self {
+        let (settings, load_warning) = match path.as_ref().map(|path| (path, fs::read_to_string(path))) {
+            Some((path, Ok(contents))) => match parse(&contents) {
+                Ok(loaded) => (loaded.settings, loaded.warning),
+                Err(reason) => (Settings::default(), Some(set_aside(path, &reason))),
+            },
+            Some((_, Err(e))) if e.kind() != std::io::ErrorKind::NotFound => {
+                (Settings::default(), Some(format!("settings file could not be read ({}), defaults loaded", e)))
+            }
+            _ => (Settings::default(), None),
+        };
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+            load_warning,
+        }
+    }
+
+    /// A store keeping `settings` in memory only, which every update fails to save.
+    #[cfg(test)]
+    pub fn in_memory(settings: Settings) -> Self {
+        Self { path: None, settings: Mutex::new(settings), load_warning: None }
+    }
+
+    pub fn load_warning(&self) -> Option<&str> {
+        self.load_warning.as_deref()
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    /// Applies `change` and writes the result to disk.
+    pub fn update(&self, change: impl FnOnce(&mut Settings)) -> Result<(), LauncherError> {
+        let mut settings = self.settings.lock().unwrap();
+        change(&mut settings);
+
+        let path = self
+            .path
+            .as_ref()
+            .ok_or("app config directory is unavailable")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(&*settings).map_err(|e| e.to_string())?;
+        // Written aside and renamed over, so a crash mid-write can't truncate the file
+        let staged = path.with_extension("json.tmp");
+        fs::write(&staged, contents)?;
+        Ok(fs::rename(&staged, path)?)
+    }
+}
+
+fn to_fields(settings: &Settings) -> Map<String, Value> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+fn from_fields(fields: Map<String, Value>) -> Result<Settings, String> {
+    serde_json::from_value(Value::Object(fields)).map_err(|e| e.to_string())
+}
+
+/// Longest any of the timeouts may be set to. Past a day a value is a typo, and
+/// durations that large overflow the deadlines built from them.
+pub const MAX_TIMEOUT_SECS: u64 = 24 * 60 * 60;
+
+/// Most free space a boot can be asked to need, in GB. A petabyte is past any disk
+/// and still far from overflowing in bytes.
+pub const MAX_FREE_DISK_GB: u64 = 1_000_000;
+
+fn positive(key: &str, value: u64) -> Result<(), LauncherError> {
+    if value == 0 {
+        return Err(LauncherError::invalid(format!("{} must be at least 1", key)));
+    }
+    Ok(())
+}
+
+fn at_most(key: &str, value: u64, max: u64) -> Result<(), LauncherError> {
+    if value > max {
+        return Err(LauncherError::invalid(format!("{} must be at most {}", key, max)));
+    }
+    Ok(())
+}
+
+fn timeout(key: &str, value: u64) -> Result<(), LauncherError> {
+    positive(key, value)?;
+    at_most(key, value, MAX_TIMEOUT_SECS)
+}
+
+fn trimmed(value: &Option<String>) -> Option<String> {
+    value.as_deref().map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+impl Settings {
+    /// Checks one field after a patch, normalizing it where the dedicated command
+    /// would, e.g. canonicalizing the project directory. Compose files are checked
+    /// against `project_dir`, the directory actually in use.
+    fn check_field(&mut self, key: &str, project_dir: &str) -> Result<(), LauncherError> {
+        match key {
+            "poll_interval_secs" => positive(key, self.poll_interval_secs),
+            "ready_interval_secs" => positive(key, self.ready_interval_secs),
+            "ready_timeout_secs" => timeout(key, self.ready_timeout_secs),
+            "boot_timeout_secs" => positive(key, self.boot_timeout_secs),
+            "log_file_max_kb" => positive(key, self.log_file_max_kb),
+            "health_timeout_secs" => timeout(key, self.health_timeout_secs),
+            "min_free_disk_gb" => at_most(key, self.min_free_disk_gb, MAX_FREE_DISK_GB),
+            "stop_timeout_secs" => at_most(key, self.stop_timeout_secs, MAX_TIMEOUT_SECS),
+            "project_dir" => {
+                if let Some(dir) = trimmed(&self.project_dir) {
+                    self.project_dir = Some(validate_project_dir(&dir)?.to_string_lossy().to_string());
+                }
+                Ok(())
+            }
+            "compose_files" => match self.compose_files.iter().find(|file| !Path::new(project_dir).join(file).is_file()) {
+                Some(file) => Err(LauncherError::invalid(format!("compose file does not exist: {}", file))),
+                None => Ok(()),
+            },
+            "active_profiles" if self.active_profiles.iter().any(|profile| profile.trim().is_empty()) => {
+                Err(LauncherError::invalid("profile names can't be empty"))
+            }
+            "web_ui_service" if self.web_ui_service.trim().is_empty() => {
+                Err(LauncherError::invalid("web_ui_service can't be empty"))
+            }
+            "model_service" if self.model_service.trim().is_empty() => {
+                Err(LauncherError::invalid("model_service can't be empty"))
+            }
+            "ready_url" => {
+                self.ready_url = self.ready_url.trim().to_string();
+                let url = &self.ready_url;
+                if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(LauncherError::invalid(format!("not an http(s) URL: {}", url)));
+                }
+                Ok(())
+            }
+            "telemetry_endpoint" => {
+                self.telemetry_endpoint = self.telemetry_endpoint.trim().to_string();
+                let url = &self.telemetry_endpoint;
+                if !url.is_empty() && !url.starts_with("https://") {
+                    return Err(LauncherError::invalid(format!("telemetry needs an https URL: {}", url)));
+                }
+                Ok(())
+            }
+            "proxy" => self.proxy.validate(),
+            "start_options" => self.start_options.validate(),
+            "schedule" => self.schedule.as_ref().map_or(Ok(()), Schedule::validate),
+            "status_api_port" if self.status_api_port == Some(0) => {
+                Err(LauncherError::invalid("the status API needs a fixed port"))
+            }
+            "status_api_token" => {
+                self.status_api_token = trimmed(&self.status_api_token);
+                Ok(())
+            }
+            "toggle_hotkey" => {
+                self.toggle_hotkey = trimmed(&self.toggle_hotkey);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `self` with `patch` applied, each field type-checked and validated in turn so
+    /// an error names the field that caused it. Nothing is applied if any field fails.
+    pub fn patched(&self, patch: &Map<String, Value>, project_dir: &str) -> Result<Settings, LauncherError> {
+        let mut fields = to_fields(self);
+        for (key, value) in patch {
+            if !fields.contains_key(key) {
+                return Err(LauncherError::invalid(format!("unknown setting: {}", key)));
+            }
+            if NOT_PATCHABLE.contains(&key.as_str()) {
+                return Err(LauncherError::invalid(format!("{} can't be changed through update_settings", key)));
+            }
+            fields.insert(key.clone(), value.clone());
+            from_fields(fields.clone()).map_err(|e| LauncherError::invalid(format!("{}: {}", key, e)))?;
+        }
+
+        let mut settings = from_fields(fields).map_err(LauncherError::invalid)?;
+        for key in patch.keys() {
+            settings.check_field(key, project_dir)?;
+        }
+        Ok(settings)
+    }
+
+    /// Copies the fields named in `keys` over from `other`, leaving the rest as they are.
+    pub fn take_fields(&mut self, other: &Settings, keys: &[String]) {
+        let source = to_fields(other);
+        let mut fields = to_fields(self);
+        for key in keys {
+            if let Some(value) = source.get(key) {
+                fields.insert(key.clone(), value.clone());
+            }
+        }
+        // Both sides are valid settings, so the mix of their fields is too
+        if let Ok(settings) = from_fields(fields) {
+            *self = settings;
+        }
+    }
+}
+
+pub fn has_compose_file(dir: &Path) -> bool {
+    COMPOSE_FILE_NAMES.iter().any(|name| dir.join(name).is_file())
+}
+
+/// Canonicalizes `path` and checks it holds a compose file.
+pub fn validate_project_dir(path: &str) -> Result<PathBuf, LauncherError> {
+    let dir = fs::canonicalize(path).map_err(|_| LauncherError::invalid(format!("directory does not exist: {}", path)))?;
+    if !dir.is_dir() {
+        return Err(LauncherError::invalid(format!("not a directory: {}", path)));
+    }
+    if !has_compose_file(&dir) {
+        return Err(LauncherError::ComposeFileNotFound { searched_dir: dir.display().to_string() });
+    }
+    Ok(dir)
+}
+
+/// `subpath` under `project_dir`. Only plain relative components are accepted, so
+/// neither `..` nor an absolute path can reach outside the project.
+pub fn project_subpath(project_dir: &str, subpath: Option<&str>) -> Result<PathBuf, LauncherError> {
+    let mut path = PathBuf::from(project_dir);
+    let Some(subpath) = subpath.filter(|subpath| !subpath.trim().is_empty()) else {
+        return Ok(path);
+    };
+    for component in Path::new(subpath).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return Err(LauncherError::invalid(format!("path must stay inside the project directory: {}", subpath))),
+        }
+    }
+    Ok(path)
+}
+
+/// Where the project directory came from, in order of precedence.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectDirSource {
+    /// `--project-dir` on the command line.
+    Cli,
+    /// The `ABEL_PROJECT_DIR` environment variable.
+    Env,
+    /// `project_dir` in the settings file.
+    Setting,
+    /// Three levels above the launcher executable, when the launcher runs from a
+    /// checkout of the project.
+    Heuristic,
+    /// The project files the launcher installed into its app data directory.
+    Installed,
+}
+
+/// An override that was set but could not be used.
+#[derive(Clone, Serialize)]
+pub struct IgnoredProjectDir {
+    pub source: ProjectDirSource,
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ProjectDirInfo {
+    pub source: ProjectDirSource,
+    /// Absolute path when it could be canonicalized, as given otherwise.
+    pub path: String,
+    pub exists: bool,
+    pub compose_found: bool,
+    /// Higher-precedence sources that were skipped, and why.
+    pub ignored: Vec<IgnoredProjectDir>,
+}
+
+/// `--project-dir <path>` or `--project-dir=<path>`; the last one wins.
+pub fn project_dir_arg(args: &[String]) -> Option<String> {
+    let mut found = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--project-dir" {
+            found = args.next().cloned();
+        } else if let Some(value) = arg.strip_prefix("--project-dir=") {
+            found = Some(value.to_string());
+        }
+    }
+    found.filter(|dir| !dir.trim().is_empty())
+}
+
+fn heuristic_project_dir() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|| ".".to_string())
+}
+
+/// Picks the project directory: the first of `cli`, `ABEL_PROJECT_DIR` and `setting`
+/// that is an existing directory, else a checkout around the launcher, else the
+/// copy in `installed`.
+pub fn resolve_project_dir(cli: Option<&str>, setting: Option<&str>, installed: Option<&Path>) -> ProjectDirInfo {
+    let env = std::env::var(PROJECT_DIR_ENV).ok().filter(|dir| !dir.trim().is_empty());
+    let overrides = [
+        (ProjectDirSource::Cli, cli.map(str::to_string)),
+        (ProjectDirSource::Env, env),
+        (ProjectDirSource::Setting, setting.map(str::to_string)),
+    ];
+
+    let mut ignored = Vec::new();
+    for (source, path) in overrides {
+        let Some(path) = path else {
+            continue;
+        };
+        match fs::canonicalize(&path) {
+            Ok(dir) if dir.is_dir() => {
+                return ProjectDirInfo {
+                    source,
+                    compose_found: has_compose_file(&dir),
+                    path: dir.to_string_lossy().to_string(),
+                    exists: true,
+                    ignored,
+                };
+            }
+            Ok(_) => ignored.push(IgnoredProjectDir { source, path, reason: "not a directory".to_string() }),
+            Err(e) => ignored.push(IgnoredProjectDir { source, path, reason: e.to_string() }),
+        }
+    }
+
+    let path = heuristic_project_dir();
+    let dir = fs::canonicalize(&path).ok().filter(|dir| dir.is_dir());
+    let installed = installed.and_then(|dir| fs::canonicalize(dir).ok()).filter(|dir| has_compose_file(dir));
+    if let (false, Some(installed)) = (dir.as_deref().is_some_and(has_compose_file), installed) {
+        return ProjectDirInfo {
+            source: ProjectDirSource::Installed,
+            compose_found: true,
+            path: installed.to_string_lossy().to_string(),
+            exists: true,
+            ignored,
+        };
+    }
+    ProjectDirInfo {
+        source: ProjectDirSource::Heuristic,
+        compose_found: dir.as_deref().is_some_and(has_compose_file),
+        exists: dir.is_some(),
+        path: dir.map_or(path, |dir| dir.to_string_lossy().to_string()),
+        ignored,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("abel-settings-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn patch(settings: &Settings, patch: Value) -> Result<Settings, String> {
+        let Value::Object(patch) = patch else {
+            unreachable!("patches are objects");
+        };
+        settings.patched(&patch, ".").map_err(|error| match error {
+            LauncherError::InvalidInput { message } => message,
+            other => other.to_string(),
+        })
+    }
+
+    #[test]
+    fn unversioned_file_is_migrated() {
+        let loaded = parse(r#"{"auto_start": true, "poll_interval_secs": 9}"#).ok().unwrap();
+        assert!(loaded.warning.is_none());
+        assert_eq!(loaded.settings.schema_version, SCHEMA_VERSION);
+        assert!(loaded.settings.auto_start);
+        assert_eq!(loaded.settings.poll_interval_secs, 9);
+        // Fields the old file didn't have come from the defaults
+        assert_eq!(loaded.settings.ready_url, Settings::default().ready_url);
+    }
+
+    #[test]
+    fn current_file_loads_as_is() {
+        let contents = serde_json::to_string(&Settings { auto_start: true, ..Settings::default() }).unwrap();
+        let loaded = parse(&contents).ok().unwrap();
+        assert!(loaded.warning.is_none());
+        assert!(loaded.settings.auto_start);
+    }
+
+    #[test]
+    fn newer_schema_loads_with_a_warning() {
+        let loaded = parse(r#"{"schema_version": 99, "auto_start": true, "from_the_future": 1}"#).ok().unwrap();
+        assert!(loaded.warning.unwrap().contains("schema 99"));
+        assert_eq!(loaded.settings.schema_version, SCHEMA_VERSION);
+        assert!(loaded.settings.auto_start);
+    }
+
+    #[test]
+    fn unusable_files_are_errors() {
+        assert!(parse("{ not json").is_err());
+        assert_eq!(parse("[1, 2]").err().unwrap(), "not a JSON object");
+        assert!(parse(r#"{"schema_version": "one"}"#).is_err());
+        assert!(parse(r#"{"poll_interval_secs": "often"}"#).is_err());
+    }
+
+    #[test]
+    fn corrupt_file_is_moved_aside_and_defaults_loaded() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join(SETTINGS_FILE);
+        fs::write(&path, "{\"auto_start\": tru").unwrap();
+
+        let store = SettingsStore::load_from(Some(path.clone()));
+        assert!(!store.get().auto_start);
+        assert!(store.load_warning().unwrap().contains("moved to"));
+        assert!(!path.exists());
+        let aside: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(aside.len(), 1);
+        assert!(aside[0].starts_with("settings.corrupt-"));
+        assert_eq!(fs::read_to_string(dir.join(&aside[0])).unwrap(), "{\"auto_start\": tru");
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn missing_file_loads_defaults_silently() {
+        let dir = temp_dir("missing");
+        let store = SettingsStore::load_from(Some(dir.join(SETTINGS_FILE)));
+        assert!(store.load_warning().is_none());
+        assert_eq!(store.get().schema_version, SCHEMA_VERSION);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn patch_applies_valid_fields() {
+        let settings = Settings::default();
+        let next = patch(&settings, json!({ "auto_start": true, "poll_interval_secs": 30 })).unwrap();
+        assert!(next.auto_start);
+        assert_eq!(next.poll_interval_secs, 30);
+        assert!(!settings.auto_start);
+    }
+
+    #[test]
+    fn patch_rejects_unknown_and_not_patchable_fields() {
+        let settings = Settings::default();
+        let error = patch(&settings, json!({ "no_such_setting": 1 })).err().unwrap();
+        assert_eq!(error, "unknown setting: no_such_setting");
+        let current = to_fields(&settings);
+        for key in NOT_PATCHABLE {
+            let error = patch(&settings, json!({ key: current[key] })).err().unwrap();
+            assert_eq!(error, format!("{} can't be changed through update_settings", key));
+        }
+    }
+
+    #[test]
+    fn patch_names_the_field_of_a_type_error() {
+        let error = patch(&Settings::default(), json!({ "auto_start": "yes" })).err().unwrap();
+        assert!(error.starts_with("auto_start: "), "{}", error);
+    }
+
+    #[test]
+    fn patch_checks_each_field() {
+        let settings = Settings::default();
+        for key in ["poll_interval_secs", "ready_interval_secs", "boot_timeout_secs", "log_file_max_kb"] {
+            assert_eq!(patch(&settings, json!({ key: 0 })).err().unwrap(), format!("{} must be at least 1", key));
+        }
+        let error = patch(&settings, json!({ "stop_timeout_secs": u64::MAX })).err().unwrap();
+        assert_eq!(error, format!("stop_timeout_secs must be at most {}", MAX_TIMEOUT_SECS));
+        assert!(patch(&settings, json!({ "stop_timeout_secs": 0 })).is_ok());
+        for value in [0, MAX_TIMEOUT_SECS + 1] {
+            assert!(patch(&settings, json!({ "health_timeout_secs": value })).is_err(), "{}", value);
+            assert!(patch(&settings, json!({ "ready_timeout_secs": value })).is_err(), "{}", value);
+        }
+        let error = patch(&settings, json!({ "min_free_disk_gb": 20_000_000_000u64 })).err().unwrap();
+        assert_eq!(error, format!("min_free_disk_gb must be at most {}", MAX_FREE_DISK_GB));
+        assert!(patch(&settings, json!({ "ready_url": "ftp://localhost/health" })).is_err());
+        assert!(patch(&settings, json!({ "telemetry_endpoint": "http://stats.example" })).is_err());
+        assert!(patch(&settings, json!({ "web_ui_service": "  " })).is_err());
+        assert!(patch(&settings, json!({ "model_service": "" })).is_err());
+        assert!(patch(&settings, json!({ "active_profiles": ["gpu", " "] })).is_err());
+        assert!(patch(&settings, json!({ "status_api_port": 0 })).is_err());
+        assert!(patch(&settings, json!({ "compose_files": ["no-such-compose.yml"] })).is_err());
+        assert!(patch(&settings, json!({ "project_dir": "/no/such/abel/dir" })).is_err());
+    }
+
+    #[test]
+    fn patch_normalizes_fields() {
+        let settings = Settings::default();
+        let next = patch(&settings, json!({ "ready_url": "  http://localhost:8000/health " })).unwrap();
+        assert_eq!(next.ready_url, "http://localhost:8000/health");
+        let next = patch(&settings, json!({ "status_api_token": "   ", "toggle_hotkey": " Ctrl+Shift+A " })).unwrap();
+        assert_eq!(next.status_api_token, None);
+        assert_eq!(next.toggle_hotkey.as_deref(), Some("Ctrl+Shift+A"));
+    }
+
+    #[test]
+    fn failed_patch_applies_nothing() {
+        let settings = Settings::default();
+        assert!(patch(&settings, json!({ "auto_start": true, "poll_interval_secs": 0 })).is_err());
+        assert!(!settings.auto_start);
+    }
+}
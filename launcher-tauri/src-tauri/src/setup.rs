@@ -0,0 +1,305 @@
+use crate::docker::DockerStatus;
+use crate::env::{self, EnvLine};
+use crate::error::LauncherError;
+use crate::messages::MessageKey;
+use crate::runtime;
+use crate::{emit_log, install, models, project_dir_info, resolve_project, secrets, SettingsStore};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::AppHandle;
+use tauri::Manager;
+
+/// A `.env` variable the backend refuses to start without.
+struct RequiredKey {
+    key: &'static str,
+    min_len: usize,
+    /// Known stand-ins that pass the length check but must not be kept.
+    defaults: &'static [&'static str],
+}
+
+const REQUIRED_KEYS: [RequiredKey; 1] = [RequiredKey {
+    key: "SECRET_KEY",
+    min_len: 32,
+    defaults: &["dev-secret-key-CHANGE-IN-PRODUCTION-min32chars"],
+}];
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepId {
+    DockerInstalled,
+    DaemonRunning,
+    ProjectFiles,
+    EnvConfigured,
+    ModelPulled,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Done,
+    Todo,
+    /// Can't be checked until an earlier step is done.
+    Blocked,
+    /// Doesn't apply to this project, e.g. it has no model service.
+    Skipped,
+}
+
+#[derive(Serialize)]
+pub struct SetupStep {
+    pub id: StepId,
+    pub status: StepStatus,
+    pub message: String,
+    /// What went wrong checking or completing the step, when something did.
+    pub error: Option<LauncherError>,
+    /// For `env_configured`: required keys that are unset, too short or still the example.
+    pub missing_keys: Vec<String>,
+}
+
+impl SetupStep {
+    fn new(id: StepId, status: StepStatus, message: impl Into<String>) -> Self {
+        SetupStep { id, status, message: message.into(), error: None, missing_keys: Vec::new() }
+    }
+
+    fn failed(id: StepId, error: LauncherError) -> Self {
+        SetupStep { message: error.to_string(), error: Some(error), ..SetupStep::new(id, StepStatus::Todo, "") }
+    }
+
+    fn blocked(id: StepId, by: &str) -> Self {
+        SetupStep::new(id, StepStatus::Blocked, format!("waiting for {}", by))
+    }
+}
+
+/// Result of `get_setup_state`, checked afresh on every call, so a setup that broke
+/// later shows the same steps again.
+#[derive(Serialize)]
+pub struct SetupState {
+    pub project_dir: String,
+    pub complete: bool,
+    pub steps: Vec<SetupStep>,
+}
+
+/// `.env.example` values, the stand-ins a copied `.env` starts out with.
+fn example_values(project_dir: &str) -> BTreeMap<String, String> {
+    let contents = fs::read_to_string(Path::new(project_dir).join(env::EXAMPLE_FILE)).unwrap_or_default();
+    env::parse(&contents)
+        .into_iter()
+        .filter_map(|line| match line {
+            EnvLine::Entry { key, value } => Some((key, value)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Why `value` can't be used for `required`, if it can't.
+fn key_problem(required: &RequiredKey, value: &str, example: Option<&String>) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Some(format!("{} is not set", required.key));
+    }
+    if example.is_some_and(|example| example == value) || required.defaults.contains(&value) {
+        return Some(format!("{} is still the example value", required.key));
+    }
+    (value.chars().count() < required.min_len)
+        .then(|| format!("{} needs at least {} characters", required.key, required.min_len))
+}
+
+fn env_step(project_dir: &str) -> SetupStep {
+    let lines = match env::read(project_dir) {
+        Ok(lines) => lines,
+        Err(error) => return SetupStep::failed(StepId::EnvConfigured, error),
+    };
+    let examples = example_values(project_dir);
+    let values: BTreeMap<String, String> = lines
+        .into_iter()
+        .filter_map(|line| match line {
+            // Kept in the keyring: the stored value is what compose hands the backend
+            EnvLine::Entry { key, value } if value == secrets::PLACEHOLDER => {
+                Some((key.clone(), secrets::get(&key).unwrap_or_default()))
+            }
+            EnvLine::Entry { key, value } => Some((key, value)),
+            _ => None,
+        })
+        .collect();
+
+    let missing_keys: Vec<String> = REQUIRED_KEYS
+        .iter()
+        .filter(|required| {
+            let value = values.get(required.key).map(String::as_str).unwrap_or_default();
+            key_problem(required, value, examples.get(required.key)).is_some()
+        })
+        .map(|required| required.key.to_string())
+        .collect();
+    if missing_keys.is_empty() {
+        return SetupStep::new(StepId::EnvConfigured, StepStatus::Done, "required settings are in .env");
+    }
+    SetupStep {
+        missing_keys,
+        ..SetupStep::new(StepId::EnvConfigured, StepStatus::Todo, "required settings are missing from .env")
+    }
+}
+
+async fn model_step(app: &AppHandle) -> SetupStep {
+    let service = app.state::<SettingsStore>().get().model_service;
+    match models::list(&resolve_project(app), &service).await {
+        Ok(models) if models.is_empty() => {
+            SetupStep::new(StepId::ModelPulled, StepStatus::Todo, "no model pulled yet")
+        }
+        Ok(models) => {
+            SetupStep::new(StepId::ModelPulled, StepStatus::Done, format!("{} models pulled", models.len()))
+        }
+        Err(LauncherError::UnknownService { .. }) => {
+            let message = format!("the project has no {} service", service);
+            SetupStep::new(StepId::ModelPulled, StepStatus::Skipped, message)
+        }
+        Err(LauncherError::ServiceNotRunning { .. }) => SetupStep::blocked(StepId::ModelPulled, "the stack to start"),
+        Err(error) => SetupStep::failed(StepId::ModelPulled, error),
+    }
+}
+
+pub async fn state(app: &AppHandle) -> SetupState {
+    let mut steps = Vec::new();
+    let docker = runtime::active(app).probe().await.status;
+    steps.push(match docker {
+        DockerStatus::NotInstalled => {
+            SetupStep::new(StepId::DockerInstalled, StepStatus::Todo, "install Docker Desktop, then check again")
+        }
+        _ => SetupStep::new(StepId::DockerInstalled, StepStatus::Done, "the docker CLI is installed"),
+    });
+    steps.push(match &docker {
+        DockerStatus::NotInstalled => SetupStep::blocked(StepId::DaemonRunning, "Docker to be installed"),
+        DockerStatus::Ready { version } => {
+            SetupStep::new(StepId::DaemonRunning, StepStatus::Done, format!("Docker {} is running", version))
+        }
+        DockerStatus::DaemonStopped => {
+            SetupStep::new(StepId::DaemonRunning, StepStatus::Todo, "the Docker daemon is not running")
+        }
+        DockerStatus::PermissionDenied => SetupStep::failed(StepId::DaemonRunning, LauncherError::PermissionDenied),
+    });
+
+    let info = project_dir_info(app);
+    let files_ready = info.compose_found;
+    steps.push(if files_ready {
+        SetupStep::new(StepId::ProjectFiles, StepStatus::Done, format!("compose file found in {}", info.path))
+    } else {
+        SetupStep::new(StepId::ProjectFiles, StepStatus::Todo, format!("no compose file in {}", info.path))
+    });
+    steps.push(if files_ready {
+        env_step(&info.path)
+    } else {
+        SetupStep::blocked(StepId::EnvConfigured, "the project files")
+    });
+    steps.push(match (&docker, files_ready) {
+        (DockerStatus::Ready { .. }, true) => model_step(app).await,
+        (_, false) => SetupStep::blocked(StepId::ModelPulled, "the project files"),
+        _ => SetupStep::blocked(StepId::ModelPulled, "the Docker daemon"),
+    });
+
+    SetupState {
+        project_dir: info.path,
+        complete: steps.iter().all(|step| matches!(step.status, StepStatus::Done | StepStatus::Skipped)),
+        steps,
+    }
+}
+
+/// `project_files`: a directory to use, or the bundled files installed.
+#[derive(Deserialize)]
+struct ProjectFilesInput {
+    path: Option<String>,
+    #[serde(default)]
+    force: bool,
+}
+
+/// `env_configured`: values for the required keys.
+#[derive(Deserialize)]
+struct EnvInput {
+    values: BTreeMap<String, String>,
+}
+
+/// `model_pulled`: the model to pull.
+#[derive(Deserialize)]
+struct ModelInput {
+    model: String,
+}
+
+fn input<T: DeserializeOwned>(step: StepId, payload: Value) -> Result<T, LauncherError> {
+    // A step without fields may be sent with no payload at all
+    let payload = if payload.is_null() { Value::Object(Default::default()) } else { payload };
+    serde_json::from_value(payload).map_err(|e| {
+        let step = serde_json::to_value(step).ok().and_then(|step| step.as_str().map(str::to_string));
+        LauncherError::invalid(format!("invalid input for {}: {}", step.unwrap_or_default(), e))
+    })
+}
+
+/// Writes the required keys into `.env`, made from the example when there is none.
+fn write_required(project_dir: &str, values: BTreeMap<String, String>) -> Result<(), LauncherError> {
+    let examples = example_values(project_dir);
+    for (key, value) in &values {
+        let required = REQUIRED_KEYS
+            .iter()
+            .find(|required| required.key == key)
+            .ok_or_else(|| LauncherError::invalid(format!("{} is not a setting the setup asks for", key)))?;
+        if let Some(problem) = key_problem(required, value, examples.get(key)) {
+            return Err(LauncherError::invalid(problem));
+        }
+    }
+
+    let mut lines = env::read(project_dir)?;
+    if lines.is_empty() {
+        let example = fs::read_to_string(Path::new(project_dir).join(env::EXAMPLE_FILE)).unwrap_or_default();
+        lines = env::parse(&example);
+    }
+    for (key, value) in values {
+        let value = value.trim().to_string();
+        let existing = lines.iter_mut().find_map(|line| match line {
+            EnvLine::Entry { key: entry_key, value } if *entry_key == key => Some(value),
+            _ => None,
+        });
+        match existing {
+            Some(existing) => *existing = value,
+            None => lines.push(EnvLine::Entry { key, value }),
+        }
+    }
+    env::write(project_dir, &lines)
+}
+
+/// Does what `step` needs, with `payload` as its input, and returns the state after.
+pub async fn complete(app: &AppHandle, step: StepId, payload: Value) -> Result<SetupState, LauncherError> {
+    match step {
+        StepId::DockerInstalled => {
+            return Err(LauncherError::invalid(
+                "Docker has to be installed by hand: https://docs.docker.com/get-docker/",
+            ));
+        }
+        StepId::DaemonRunning => crate::launch_docker_desktop(app, runtime::active(app).as_ref()).await?,
+        StepId::ProjectFiles => {
+            let input: ProjectFilesInput = input(step, payload)?;
+            match input.path {
+                Some(path) => {
+                    crate::set_project_dir(app.clone(), path)?;
+                }
+                None => {
+                    let _guard = crate::OperationGuard::acquire(app, crate::Operation::Updating)?;
+                    install::log_report(app, &install::install(app, input.force)?);
+                }
+            }
+        }
+        StepId::EnvConfigured => {
+            let input: EnvInput = input(step, payload)?;
+            if input.values.is_empty() {
+                return Err(LauncherError::invalid("no values given"));
+            }
+            write_required(&project_dir_info(app).path, input.values)?;
+            // Values may be secrets, so only the fact of the change is logged
+            emit_log(app, "info", MessageKey::SetupEnvSaved);
+        }
+        StepId::ModelPulled => {
+            let input: ModelInput = input(step, payload)?;
+            crate::pull_model(app.clone(), input.model).await?;
+        }
+    }
+    Ok(state(app).await)
+}
@@ -0,0 +1,88 @@
+use crate::messages::MessageKey;
+use crate::notify::{self, Notice};
+use crate::state::LauncherState;
+use crate::{emit_log, is_running, stop_sequence, SettingsStore};
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::AppHandle;
+use tauri::{ExitRequestApi, Manager};
+
+/// Added to `stop_timeout_secs` for compose itself, so a hung daemon can't hold the
+/// exit forever.
+const EXIT_STOP_MARGIN: Duration = Duration::from_secs(30);
+
+/// Set while a final stop runs, by the window's stop-and-exit or by the exit hook,
+/// so the two never both take the stack down.
+static STOPPING: AtomicBool = AtomicBool::new(false);
+/// Set once the exit hook is done, so the exit it hands back goes straight through.
+static FINISHED: AtomicBool = AtomicBool::new(false);
+
+/// Whether anything would be left behind by exiting now. An operation in flight
+/// counts too: a boot is about to leave containers behind.
+pub fn services_active(app: &AppHandle) -> bool {
+    is_running(app) || app.state::<Mutex<LauncherState>>().lock().unwrap().is_busy()
+}
+
+pub fn in_progress() -> bool {
+    STOPPING.load(Ordering::SeqCst)
+}
+
+/// Claims the final stop. False when one is already under way.
+pub fn begin() -> bool {
+    !STOPPING.swap(true, Ordering::SeqCst)
+}
+
+/// Gives the claim back after a stop that failed and didn't exit.
+pub fn abandon() {
+    STOPPING.store(false, Ordering::SeqCst);
+}
+
+/// Exits after a final stop, past the exit hook.
+pub fn exit(app: &AppHandle, code: i32) {
+    FINISHED.store(true, Ordering::SeqCst);
+    app.exit(code);
+}
+
+/// `RunEvent::ExitRequested`: with `stop_on_exit`, holds the exit until the stack
+/// is down or the timeout passes, then exits with the requested code.
+pub fn exit_requested(app: &AppHandle, api: &ExitRequestApi, code: Option<i32>) {
+    if FINISHED.load(Ordering::SeqCst) {
+        return;
+    }
+    // Unset if setup never got as far as loading the settings
+    let stop_on_exit = app.try_state::<SettingsStore>().is_some_and(|store| store.get().stop_on_exit);
+    let holds_up = in_progress() || (stop_on_exit && services_active(app));
+    if !holds_up {
+        return;
+    }
+
+    api.prevent_exit();
+    // A stop-and-exit from the window is already running and exits on its own
+    if !begin() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let stop_timeout = Duration::from_secs(app.state::<SettingsStore>().get().stop_timeout_secs);
+        let timeout = stop_timeout.saturating_add(EXIT_STOP_MARGIN);
+        emit_log(&app, "warning", MessageKey::ExitStopping);
+        let failure = match tokio::time::timeout(timeout, stop_sequence(&app)).await {
+            Ok(Ok(true)) => None,
+            Ok(Ok(false)) => Some(MessageKey::ExitStopIncomplete.into()),
+            Ok(Err(error)) => {
+                Some(MessageKey::ExitStopFailed.with(json!({ "error": error.to_string() })))
+            }
+            Err(_) => Some(MessageKey::ExitStopTimedOut.with(json!({ "secs": timeout.as_secs() }))),
+        };
+        match failure {
+            None => emit_log(&app, "info", MessageKey::ExitStopped),
+            Some(failure) => {
+                emit_log(&app, "error", failure);
+                notify::send(&app, Notice::Unexpected, "A.B.E.L. exited", "Some services may still be running.");
+            }
+        }
+        exit(&app, code.unwrap_or(0));
+    });
+}
@@ -0,0 +1,150 @@
+use crate::error::LauncherError;
+use serde::Serialize;
+
+/// What the launcher is doing with the stack. `Idle` and `Running` are resting states
+/// that follow the containers; the others are operations in flight.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    #[default]
+    Idle,
+    Starting,
+    Running,
+    Stopping,
+    /// Pulling, pruning or changing a single service. The stack as a whole keeps
+    /// whatever state it had.
+    Updating,
+}
+
+impl Operation {
+    pub fn is_busy(self) -> bool {
+        matches!(self, Operation::Starting | Operation::Stopping | Operation::Updating)
+    }
+}
+
+/// Managed as `Mutex<LauncherState>`. Every command that drives compose begins an
+/// operation here first, so two of them never run against the project at once.
+#[derive(Default)]
+pub struct LauncherState {
+    operation: Operation,
+    /// Whether every service was up when last observed.
+    running: bool,
+}
+
+impl LauncherState {
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.operation.is_busy()
+    }
+
+    /// Enters `next` if nothing else is in flight and it makes sense from here.
+    pub fn begin(&mut self, next: Operation) -> Result<(), LauncherError> {
+        match (self.operation, next) {
+            (current, _) if current.is_busy() => Err(LauncherError::Busy { current_operation: current }),
+            (_, Operation::Idle | Operation::Running) => {
+                Err(LauncherError::invalid("resting states are entered by observing the stack"))
+            }
+            (Operation::Running, Operation::Starting) => Err(LauncherError::invalid("A.B.E.L. is already running")),
+            _ => {
+                self.operation = next;
+                Ok(())
+            }
+        }
+    }
+
+    /// Moves an operation that is already held into its next phase, e.g. a restart
+    /// from stopping to starting.
+    pub fn advance(&mut self, next: Operation) {
+        debug_assert!(self.operation.is_busy() && next.is_busy());
+        self.operation = next;
+    }
+
+    /// Records what the containers look like. At rest this also picks the resting state.
+    pub fn observe(&mut self, running: bool) {
+        self.running = running;
+        if !self.operation.is_busy() {
+            self.operation = self.resting();
+        }
+    }
+
+    /// Ends whatever operation was in flight, settling on the last observation.
+    pub fn finish(&mut self) {
+        self.operation = self.resting();
+    }
+
+    fn resting(&self) -> Operation {
+        if self.running {
+            Operation::Running
+        } else {
+            Operation::Idle
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_rejects_another_operation() {
+        let mut state = LauncherState::default();
+        state.begin(Operation::Starting).unwrap();
+        for next in [Operation::Starting, Operation::Stopping, Operation::Updating] {
+            assert!(matches!(
+                state.begin(next),
+                Err(LauncherError::Busy { current_operation: Operation::Starting })
+            ));
+        }
+        assert_eq!(state.operation(), Operation::Starting);
+    }
+
+    #[test]
+    fn start_refused_while_running() {
+        let mut state = LauncherState::default();
+        state.observe(true);
+        assert_eq!(state.operation(), Operation::Running);
+        assert!(matches!(state.begin(Operation::Starting), Err(LauncherError::InvalidInput { .. })));
+        assert!(state.begin(Operation::Stopping).is_ok());
+    }
+
+    #[test]
+    fn resting_states_cannot_be_begun() {
+        let mut state = LauncherState::default();
+        assert!(state.begin(Operation::Idle).is_err());
+        assert!(state.begin(Operation::Running).is_err());
+    }
+
+    #[test]
+    fn finish_leaves_busy_on_the_last_observation() {
+        let mut state = LauncherState::default();
+        state.begin(Operation::Starting).unwrap();
+        // Observed mid-boot: the operation holds until it finishes
+        state.observe(true);
+        assert!(state.is_busy());
+        state.finish();
+        assert!(!state.is_busy());
+        assert_eq!(state.operation(), Operation::Running);
+
+        state.begin(Operation::Stopping).unwrap();
+        state.observe(false);
+        state.finish();
+        assert_eq!(state.operation(), Operation::Idle);
+        assert!(state.begin(Operation::Starting).is_ok());
+    }
+
+    #[test]
+    fn advance_moves_between_phases() {
+        let mut state = LauncherState::default();
+        state.begin(Operation::Stopping).unwrap();
+        state.advance(Operation::Starting);
+        assert_eq!(state.operation(), Operation::Starting);
+        assert!(state.is_busy());
+    }
+}
@@ -0,0 +1,159 @@
+use crate::messages::MessageKey;
+use crate::runtime::ContainerRuntime;
+use crate::{compose, docker, emit_log, resolve_project};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::AppHandle;
+use tauri::{Emitter, Manager};
+
+const STATS_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Default, Serialize)]
+pub struct NetIo {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct StatsEvent {
+    pub name: String,
+    pub cpu_percent: f64,
+    pub mem_used: u64,
+    pub mem_limit: u64,
+    pub net_io: NetIo,
+}
+
+/// Task sampling `docker stats`, if one is running.
+#[derive(Default)]
+pub struct StatsStream(Mutex<Option<tauri::async_runtime::JoinHandle<()>>>);
+
+/// Splits `used / limit` style pairs.
+fn parse_pair(value: &str) -> (u64, u64) {
+    let mut parts = value.split('/').map(|part| docker::parse_size(part).unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+/// Field names and value formats drift between Docker releases, so every field is optional.
+fn parse_stats_line(line: &str) -> Option<StatsEvent> {
+    let entry: Value = serde_json::from_str(line.trim()).ok()?;
+    let field = |key: &str| entry.get(key).and_then(Value::as_str).unwrap_or_default();
+
+    let name = [field("Name"), field("Container"), field("ID")]
+        .into_iter()
+        .find(|name| !name.is_empty())?
+        .to_string();
+    let cpu_percent = field("CPUPerc").trim_end_matches('%').trim().parse().unwrap_or(0.0);
+    let (mem_used, mem_limit) = parse_pair(field("MemUsage"));
+    let (rx_bytes, tx_bytes) = parse_pair(field("NetIO"));
+
+    Some(StatsEvent {
+        name,
+        cpu_percent,
+        mem_used,
+        mem_limit,
+        net_io: NetIo { rx_bytes, tx_bytes },
+    })
+}
+
+fn counter(entry: &Value, pointer: &str) -> u64 {
+    entry.pointer(pointer).and_then(Value::as_u64).unwrap_or(0)
+}
+
+/// An Engine API sample, worked out the way `docker stats` does: CPU over the
+/// interval since the previous reading, memory without the reclaimable page cache.
+pub fn from_engine(entry: &Value) -> Option<StatsEvent> {
+    let name = entry.get("name").and_then(Value::as_str)?.trim_start_matches('/').to_string();
+    let cpu_delta = counter(entry, "/cpu_stats/cpu_usage/total_usage")
+        .saturating_sub(counter(entry, "/precpu_stats/cpu_usage/total_usage"));
+    let system_delta =
+        counter(entry, "/cpu_stats/system_cpu_usage").saturating_sub(counter(entry, "/precpu_stats/system_cpu_usage"));
+    let cpus = match counter(entry, "/cpu_stats/online_cpus") {
+        0 => entry.pointer("/cpu_stats/cpu_usage/percpu_usage").and_then(Value::as_array).map_or(1, Vec::len) as u64,
+        cpus => cpus,
+    };
+    let cpu_percent = match system_delta {
+        0 => 0.0,
+        _ => cpu_delta as f64 / system_delta as f64 * cpus as f64 * 100.0,
+    };
+    // `inactive_file` on cgroup v2, `total_inactive_file` on v1
+    let cache = match counter(entry, "/memory_stats/stats/inactive_file") {
+        0 => counter(entry, "/memory_stats/stats/total_inactive_file"),
+        cache => cache,
+    };
+    let mut net_io = NetIo::default();
+    let networks = entry.get("networks").and_then(Value::as_object);
+    for interface in networks.into_iter().flat_map(|networks| networks.values()) {
+        net_io.rx_bytes += counter(interface, "/rx_bytes");
+        net_io.tx_bytes += counter(interface, "/tx_bytes");
+    }
+
+    Some(StatsEvent {
+        name,
+        cpu_percent,
+        mem_used: counter(entry, "/memory_stats/usage").saturating_sub(cache),
+        mem_limit: counter(entry, "/memory_stats/limit"),
+        net_io,
+    })
+}
+
+/// One `stats --no-stream` sample per container through the runtime's CLI.
+pub async fn sample_cli<R>(runtime: &R, container_ids: &[String]) -> Vec<StatsEvent>
+where
+    R: ContainerRuntime + ?Sized,
+{
+    // `{{json .}}` is understood by every release, unlike the newer `--format json`
+    let mut args = vec!["stats", "--no-stream", "--format", "{{json .}}"];
+    args.extend(container_ids.iter().map(String::as_str));
+    let output = runtime.cli(&args).await;
+
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(parse_stats_line)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+async fn stats_loop(app: AppHandle) {
+    loop {
+        let project = resolve_project(&app);
+        let running: Vec<String> = compose::service_status(&project)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|status| status.is_running())
+            .map(|status| status.container_id)
+            .collect();
+
+        // The stream only lives as long as the stack does
+        if running.is_empty() {
+            emit_log(&app, "info", MessageKey::StatsStopped);
+            return;
+        }
+        for event in project.runtime.stats(&running).await {
+            app.emit("stats", event).ok();
+        }
+
+        tokio::time::sleep(STATS_INTERVAL).await;
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    let task = tauri::async_runtime::spawn(stats_loop(app.clone()));
+    if let Some(previous) = app.state::<StatsStream>().0.lock().unwrap().replace(task) {
+        previous.abort();
+    }
+}
+
+pub fn stop(app: &AppHandle) -> bool {
+    match app.state::<StatsStream>().0.lock().unwrap().take() {
+        Some(task) => {
+            task.abort();
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,283 @@
+use crate::backend;
+use crate::error::LauncherError;
+use crate::state::LauncherState;
+use crate::messages::MessageKey;
+use crate::{current_status, emit_log, start_services, stop_services, SettingsStore};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::AppHandle;
+use tauri::Manager;
+
+/// A client gets this long to send its request and read the answer.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// Request line and headers together; no endpoint takes a body.
+const HEAD_LIMIT: u64 = 8 * 1024;
+const BODY_LIMIT: u64 = 8 * 1024;
+
+struct Listening {
+    address: SocketAddr,
+    stop: Arc<AtomicBool>,
+}
+
+impl Listening {
+    /// Flags the accept loop and wakes it with a connection of its own.
+    fn halt(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        TcpStream::connect_timeout(&self.address, Duration::from_secs(1)).ok();
+    }
+}
+
+/// The server currently bound, managed as state.
+#[derive(Default)]
+pub struct StatusApi(Mutex<Option<Listening>>);
+
+/// Returned by `get_status_api`.
+#[derive(Clone, Serialize)]
+pub struct StatusApiInfo {
+    /// None while the server is off.
+    pub port: Option<u16>,
+    /// Guards `POST /start` and `POST /stop`, sent as `Authorization: Bearer <token>`.
+    pub token: Option<String>,
+    /// Base URL while the server is listening.
+    pub url: Option<String>,
+}
+
+pub fn info(app: &AppHandle) -> StatusApiInfo {
+    let settings = app.state::<SettingsStore>().get();
+    let url = app
+        .state::<StatusApi>()
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|listening| format!("http://{}", listening.address));
+    StatusApiInfo { port: settings.status_api_port, token: settings.status_api_token, url }
+}
+
+/// Moves the server to `port`, or shuts it down with None. The new port is bound
+/// before the old one is released, so a port that is taken leaves things as they were.
+pub fn apply(app: &AppHandle, port: Option<u16>) -> Result<(), LauncherError> {
+    let state = app.state::<StatusApi>();
+    let mut current = state.0.lock().unwrap();
+    if current.as_ref().map(|listening| listening.address.port()) == port {
+        return Ok(());
+    }
+
+    let next = port.map(|port| bind(app, port)).transpose()?;
+    if let Some(previous) = current.take() {
+        let address = previous.address;
+        previous.halt();
+        emit_log(app, "info", MessageKey::StatusApiStopped.with(json!({ "address": address.to_string() })));
+    }
+    if let Some(listening) = &next {
+        emit_log(app, "info", MessageKey::StatusApiListening.with(json!({ "address": listening.address.to_string() })));
+    }
+    *current = next;
+    Ok(())
+}
+
+fn bind(app: &AppHandle, port: u16) -> Result<Listening, LauncherError> {
+    let unavailable = |e: std::io::Error| LauncherError::StatusApiUnavailable { port, message: e.to_string() };
+    // Loopback only: nothing off this machine may reach the stack controls
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = TcpListener::bind(address).map_err(unavailable)?;
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let flag = stop.clone();
+    let app = app.clone();
+    // A plain thread rather than a runtime task: it never holds the app open, and
+    // the process takes it down on exit.
+    std::thread::Builder::new()
+        .name("status-api".to_string())
+        .spawn(move || serve(&app, listener, &flag))
+        .map_err(unavailable)?;
+    Ok(Listening { address, stop })
+}
+
+/// Starts the server if a port is saved. A taken port is logged, not fatal.
+pub fn init(app: &AppHandle) {
+    let port = app.state::<SettingsStore>().get().status_api_port;
+    if let Err(e) = apply(app, port) {
+        let params = json!({ "error": e.to_string() });
+        emit_log(app, "warning", MessageKey::StatusApiNotStarted.with(params));
+    }
+}
+
+pub fn shutdown(app: &AppHandle) {
+    if let Some(state) = app.try_state::<StatusApi>() {
+        if let Some(listening) = state.0.lock().unwrap().take() {
+            listening.halt();
+        }
+    }
+}
+
+fn serve(app: &AppHandle, listener: TcpListener, stop: &AtomicBool) {
+    for stream in listener.incoming() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        // One client at a time is plenty for a monitoring script
+        if let Ok(stream) = stream {
+            handle(app, stream);
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    token: Option<String>,
+}
+
+/// Request line and headers of an HTTP/1.x request. The body, if any, is drained so
+/// the client sees the response rather than a reset.
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream.take(HEAD_LIMIT));
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_uppercase();
+    let target = parts.next()?;
+    // Query strings carry nothing any endpoint reads
+    let path = target.split('?').next().unwrap_or(target).trim_end_matches('/').to_string();
+
+    let mut token = None;
+    let mut body_length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_lowercase().as_str() {
+            "authorization" => token = value.strip_prefix("Bearer ").map(|token| token.trim().to_string()),
+            "content-length" => body_length = value.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    let body_length = body_length.min(BODY_LIMIT);
+    reader.get_mut().set_limit(body_length);
+    std::io::copy(&mut (&mut reader).take(body_length), &mut std::io::sink()).ok();
+    Some(Request { method, path, token })
+}
+
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+fn respond(status: u16, body: Value) -> Response {
+    Response { status, body }
+}
+
+fn failure(status: u16, error: &LauncherError) -> Response {
+    respond(status, serde_json::to_value(error).unwrap_or_default())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+fn handle(app: &AppHandle, stream: TcpStream) {
+    stream.set_read_timeout(Some(IO_TIMEOUT)).ok();
+    stream.set_write_timeout(Some(IO_TIMEOUT)).ok();
+    let response = match read_request(&stream) {
+        Some(request) => route(app, &request),
+        None => failure(400, &LauncherError::invalid("malformed HTTP request")),
+    };
+    let body = response.body.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason(response.status),
+        body.len()
+    );
+    let mut stream = stream;
+    stream.write_all(head.as_bytes()).ok();
+    stream.write_all(body.as_bytes()).ok();
+}
+
+fn route(app: &AppHandle, request: &Request) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => match tauri::async_runtime::block_on(current_status(app)) {
+            Ok(status) => respond(200, serde_json::to_value(status).unwrap_or_default()),
+            Err(error) => failure(503, &error),
+        },
+        ("GET", "/health") => health(app),
+        ("POST", "/start") => control(app, request, true),
+        ("POST", "/stop") => control(app, request, false),
+        (_, "/status" | "/health" | "/start" | "/stop") => {
+            failure(405, &LauncherError::invalid(format!("{} is not allowed on {}", request.method, request.path)))
+        }
+        _ => failure(404, &LauncherError::invalid(format!("no endpoint at {}", request.path))),
+    }
+}
+
+/// The core's answer as `check_backend_health` reports it; 503 unless it answered 2xx.
+fn health(app: &AppHandle) -> Response {
+    let url = app.state::<SettingsStore>().get().ready_url;
+    if url.is_empty() {
+        return failure(404, &LauncherError::invalid("no health endpoint is configured"));
+    }
+    let health = tauri::async_runtime::block_on(backend::ping(&url));
+    let status = if health.outcome == backend::Outcome::Ok { 200 } else { 503 };
+    respond(status, serde_json::to_value(health).unwrap_or_default())
+}
+
+/// Compares in constant time, so response timing doesn't leak how much of a guess matched.
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len() && expected.bytes().zip(given.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Answers once the boot or shutdown is underway; `GET /status` follows it from there.
+fn control(app: &AppHandle, request: &Request, start: bool) -> Response {
+    let Some(expected) = app.state::<SettingsStore>().get().status_api_token else {
+        return failure(403, &LauncherError::invalid("set a status API token to allow start and stop"));
+    };
+    if !request.token.as_deref().is_some_and(|given| token_matches(&expected, given)) {
+        return failure(401, &LauncherError::invalid("missing or wrong bearer token"));
+    }
+    let busy = {
+        let state = app.state::<Mutex<LauncherState>>();
+        let state = state.lock().unwrap();
+        state.is_busy().then(|| state.operation())
+    };
+    if let Some(operation) = busy {
+        return failure(409, &LauncherError::Busy { current_operation: operation });
+    }
+
+    let key = if start { MessageKey::StatusApiStarting } else { MessageKey::StatusApiStopping };
+    emit_log(app, "info", key.with(json!({ "method": request.method, "path": request.path })));
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if start {
+            start_services(app, None).await.ok();
+        } else {
+            stop_services(app).await.ok();
+        }
+    });
+    respond(202, json!({ "accepted": if start { "start" } else { "stop" } }))
+}
@@ -0,0 +1,263 @@
+use crate::update::CURRENT_VERSION;
+use crate::SettingsStore;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use crate::AppHandle;
+use tauri::Manager;
+
+/// Every field an event can carry. Anything else is stripped before a batch leaves
+/// the machine, so log lines, paths and env values have no way in.
+pub const COLLECTED_FIELDS: [&str; 5] = ["event", "error_category", "duration_bucket", "os", "app_version"];
+/// Oldest events are dropped past this, e.g. while the endpoint stays unreachable.
+const QUEUE_LIMIT: usize = 100;
+/// A full batch goes out right away; smaller ones wait for the next flush.
+const BATCH_SIZE: usize = 20;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventName {
+    BootSucceeded,
+    BootFailed,
+}
+
+/// One recorded event. Every field is a fixed name or a coarse bucket, never free text.
+#[derive(Clone, Serialize)]
+pub struct TelemetryEvent {
+    pub event: EventName,
+    /// An error code such as `port_conflict`, for failed boots.
+    pub error_category: Option<&'static str>,
+    pub duration_bucket: Option<&'static str>,
+    pub os: &'static str,
+    pub app_version: &'static str,
+}
+
+/// Returned by `get_telemetry_status`: what is collected and what is waiting to be sent.
+#[derive(Clone, Serialize)]
+pub struct TelemetryStatus {
+    pub enabled: bool,
+    /// Empty while no endpoint is configured, in which case nothing is recorded.
+    pub endpoint: String,
+    pub collected_fields: Vec<&'static str>,
+    pub queued: Vec<TelemetryEvent>,
+}
+
+/// Events waiting for the next batch, managed as state.
+#[derive(Default)]
+pub struct Telemetry {
+    queue: Mutex<VecDeque<TelemetryEvent>>,
+    sending: AtomicBool,
+}
+
+/// Boot durations rounded into ranges wide enough not to identify anyone.
+fn duration_bucket(elapsed: Duration) -> &'static str {
+    match elapsed.as_secs() {
+        0..30 => "lt_30s",
+        30..60 => "30s_1m",
+        60..120 => "1m_2m",
+        120..300 => "2m_5m",
+        300..900 => "5m_15m",
+        _ => "gt_15m",
+    }
+}
+
+fn collecting(app: &AppHandle) -> bool {
+    let settings = app.state::<SettingsStore>().get();
+    settings.telemetry_enabled && !settings.telemetry_endpoint.is_empty()
+}
+
+fn record(app: &AppHandle, event: EventName, error_category: Option<&'static str>, started: Instant) {
+    if !collecting(app) {
+        return;
+    }
+    let event = TelemetryEvent {
+        event,
+        error_category,
+        duration_bucket: Some(duration_bucket(started.elapsed())),
+        os: std::env::consts::OS,
+        app_version: CURRENT_VERSION,
+    };
+    let telemetry = app.state::<Telemetry>();
+    let full = {
+        let mut queue = telemetry.queue.lock().unwrap();
+        if queue.len() == QUEUE_LIMIT {
+            queue.pop_front();
+        }
+        queue.push_back(event);
+        queue.len() >= BATCH_SIZE
+    };
+    if full {
+        tauri::async_runtime::spawn(flush(app.clone()));
+    }
+}
+
+pub fn boot_succeeded(app: &AppHandle, started: Instant) {
+    record(app, EventName::BootSucceeded, None, started);
+}
+
+pub fn boot_failed(app: &AppHandle, error_category: &'static str, started: Instant) {
+    record(app, EventName::BootFailed, Some(error_category), started);
+}
+
+/// Clears anything queued, e.g. once the user opts out.
+pub fn discard(app: &AppHandle) {
+    app.state::<Telemetry>().queue.lock().unwrap().clear();
+}
+
+pub fn status(app: &AppHandle) -> TelemetryStatus {
+    let settings = app.state::<SettingsStore>().get();
+    TelemetryStatus {
+        enabled: settings.telemetry_enabled,
+        endpoint: settings.telemetry_endpoint,
+        collected_fields: COLLECTED_FIELDS.to_vec(),
+        queued: app.state::<Telemetry>().queue.lock().unwrap().iter().cloned().collect(),
+    }
+}
+
+/// `event` with every field outside [`COLLECTED_FIELDS`] stripped, or None if it
+/// isn't an object.
+fn allowlisted(event: Value) -> Option<Value> {
+    match event {
+        Value::Object(mut fields) => {
+            fields.retain(|key, _| COLLECTED_FIELDS.contains(&key.as_str()));
+            Some(Value::Object(fields))
+        }
+        _ => None,
+    }
+}
+
+/// The request body for `events`, keeping only [`COLLECTED_FIELDS`].
+fn payload(events: &[TelemetryEvent]) -> Value {
+    let events: Vec<Value> = events
+        .iter()
+        .filter_map(|event| serde_json::to_value(event).ok())
+        .filter_map(allowlisted)
+        .collect();
+    json!({ "events": events })
+}
+
+fn client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(format!("abel-launcher/{}", CURRENT_VERSION))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// Posts everything queued as one batch. A batch that doesn't get through is dropped,
+/// not retried: losing a few counts beats piling them up while offline.
+async fn flush(app: AppHandle) {
+    let telemetry = app.state::<Telemetry>();
+    if telemetry.sending.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let batch: Vec<TelemetryEvent> = telemetry.queue.lock().unwrap().drain(..).collect();
+    let endpoint = app.state::<SettingsStore>().get().telemetry_endpoint;
+    if !batch.is_empty() && collecting(&app) {
+        client()
+            .post(&endpoint)
+            .header(CONTENT_TYPE, "application/json")
+            .body(payload(&batch).to_string())
+            .send()
+            .await
+            .ok();
+    }
+    telemetry.sending.store(false, Ordering::SeqCst);
+}
+
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            flush(app.clone()).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> TelemetryEvent {
+        TelemetryEvent {
+            event: EventName::BootFailed,
+            error_category: Some("port_conflict"),
+            duration_bucket: Some(duration_bucket(Duration::from_secs(75))),
+            os: "linux",
+            app_version: "1.2.3",
+        }
+    }
+
+    #[test]
+    fn extra_and_personal_fields_are_stripped() {
+        let event = json!({
+            "event": "boot_failed",
+            "error_category": "command_failed",
+            "duration_bucket": "lt_30s",
+            "os": "windows",
+            "app_version": "1.2.3",
+            "message": "port 8000 is taken by C:\\Users\\jane\\server.exe",
+            "project_dir": "/home/jane/abel",
+            "hostname": "janes-laptop",
+            "env": { "SECRET_KEY": "abc" },
+        });
+        let allowed = allowlisted(event).unwrap();
+        let mut keys: Vec<&str> = allowed.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort();
+        let mut expected = COLLECTED_FIELDS.to_vec();
+        expected.sort();
+        assert_eq!(keys, expected);
+        assert!(!allowed.to_string().contains("jane"));
+    }
+
+    #[test]
+    fn non_objects_are_dropped() {
+        assert!(allowlisted(json!("boot_failed")).is_none());
+        assert!(allowlisted(json!(["boot_failed"])).is_none());
+    }
+
+    #[test]
+    fn payload_carries_only_collected_fields() {
+        let body = payload(&[event(), event()]);
+        let events = body["events"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0],
+            json!({
+                "event": "boot_failed",
+                "error_category": "port_conflict",
+                "duration_bucket": "1m_2m",
+                "os": "linux",
+                "app_version": "1.2.3",
+            })
+        );
+        assert_eq!(body.as_object().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn every_serialized_field_is_collected() {
+        let fields = serde_json::to_value(event()).unwrap();
+        for key in fields.as_object().unwrap().keys() {
+            assert!(COLLECTED_FIELDS.contains(&key.as_str()), "{} is serialized but not allowlisted", key);
+        }
+    }
+
+    #[test]
+    fn durations_are_bucketed() {
+        assert_eq!(duration_bucket(Duration::from_secs(0)), "lt_30s");
+        assert_eq!(duration_bucket(Duration::from_secs(30)), "30s_1m");
+        assert_eq!(duration_bucket(Duration::from_secs(299)), "2m_5m");
+        assert_eq!(duration_bucket(Duration::from_secs(3600)), "gt_15m");
+    }
+}
@@ -0,0 +1,84 @@
+use crate::error::LauncherError;
+use std::process::Command;
+
+/// A program plus arguments that opens a terminal window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Launch {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl Launch {
+    fn new(program: &str, prefix: &[&str], command: &[String]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: prefix.iter().map(|arg| arg.to_string()).chain(command.iter().cloned()).collect(),
+        }
+    }
+}
+
+/// `docker exec` into `container`, preferring bash where the image has it.
+pub fn exec_command(container: &str) -> Vec<String> {
+    ["docker", "exec", "-it", container, "sh", "-c", "bash || sh"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+/// Single-quotes `arg` for a POSIX shell command line.
+fn shell_quote(arg: &str) -> String {
+    if arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:".contains(c)) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Ways to open a terminal running `command` on `os`, most preferred first. `terminal`
+/// is the user's `$TERMINAL`, which wins on Linux when set. Kept free of any spawning
+/// so each platform's command line can be checked anywhere.
+pub fn candidates(os: &str, terminal: Option<&str>, command: &[String]) -> Vec<Launch> {
+    match os {
+        "windows" => vec![
+            Launch::new("wt.exe", &["new-tab"], command),
+            // `start` opens a new console window; its first quoted argument is the title
+            Launch::new("cmd", &["/C", "start", "A.B.E.L. shell"], command),
+        ],
+        "macos" => {
+            let line: Vec<String> = command.iter().map(|arg| shell_quote(arg)).collect();
+            let script = format!(
+                "tell application \"Terminal\" to do script \"{}\"",
+                line.join(" ").replace('\\', "\\\\").replace('"', "\\\"")
+            );
+            vec![Launch::new(
+                "osascript",
+                &["-e", &script, "-e", "tell application \"Terminal\" to activate"],
+                &[],
+            )]
+        }
+        _ => {
+            let mut launches = Vec::new();
+            if let Some(terminal) = terminal.filter(|t| !t.trim().is_empty()) {
+                launches.push(Launch::new(terminal.trim(), &["-e"], command));
+            }
+            launches.extend([
+                Launch::new("x-terminal-emulator", &["-e"], command),
+                Launch::new("gnome-terminal", &["--"], command),
+                Launch::new("konsole", &["-e"], command),
+                Launch::new("xfce4-terminal", &["-x"], command),
+                Launch::new("xterm", &["-e"], command),
+            ]);
+            launches
+        }
+    }
+}
+
+/// Spawns the first candidate that exists on this machine, without waiting for it.
+pub fn open(command: &[String]) -> Result<Launch, LauncherError> {
+    let terminal = std::env::var("TERMINAL").ok();
+    for launch in candidates(std::env::consts::OS, terminal.as_deref(), command) {
+        if Command::new(&launch.program).args(&launch.args).spawn().is_ok() {
+            return Ok(launch);
+        }
+    }
+    Err("no terminal emulator found to open the shell in".into())
+}
@@ -0,0 +1,189 @@
+use crate::shutdown;
+use crate::messages::MessageKey;
+use crate::{emit_log, is_running, start_services, stop_sequence, stop_services, AppHandle, AppRuntime, SettingsStore};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{CloseRequestApi, Manager, Window};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult};
+
+const TRAY_ID: &str = "abel";
+
+const STOP_AND_EXIT: &str = "Stop and exit";
+const KEEP_RUNNING: &str = "Keep running";
+
+/// What closing the main window does while services are up. With nothing running
+/// the window closes and the launcher exits either way.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseAction {
+    /// Ask whether to stop the services, keep them running in the tray, or cancel.
+    Ask,
+    /// Hide the window; the tray icon brings it back.
+    #[default]
+    Minimize,
+    /// Take the stack down and wait for it, then exit.
+    StopAndExit,
+    /// Exit the way the tray's Quit does, so `stop_on_exit` still applies.
+    Exit,
+}
+
+/// Menu items whose availability follows the stack state.
+pub struct TrayMenu {
+    start: MenuItem<AppRuntime>,
+    stop: MenuItem<AppRuntime>,
+}
+
+fn tooltip(app: &AppHandle, running: bool, starting: bool) -> String {
+    let state = if starting {
+        "A.B.E.L. // INITIALIZING..."
+    } else if running {
+        "A.B.E.L. // SYSTEM ONLINE"
+    } else {
+        "A.B.E.L. // SYSTEM OFFLINE"
+    };
+    // Unset until setup has loaded the settings
+    let environment = app.try_state::<SettingsStore>().and_then(|settings| settings.get().active_environment);
+    match environment {
+        Some(environment) => format!("{} [{}]", state, environment.to_uppercase()),
+        None => state.to_string(),
+    }
+}
+
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let running = is_running(app);
+    let start = MenuItem::with_id(app, "start", "Start", !running, None::<&str>)?;
+    let stop = MenuItem::with_id(app, "stop", "Stop", running, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let menu = Menu::with_items(app, &[&start, &stop, &separator, &show, &quit])?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(tooltip(app, running, false))
+        .menu(&menu)
+        .show_menu_on_left_click(true)
+        .on_menu_event(handle_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayMenu { start, stop });
+    Ok(())
+}
+
+/// Mirrors a status change into the tray; nothing can be triggered while an operation is in flight.
+pub fn update(app: &AppHandle, running: bool, starting: bool) {
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        menu.start.set_enabled(!running && !starting).ok();
+        menu.stop.set_enabled(running && !starting).ok();
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_tooltip(Some(tooltip(app, running, starting))).ok();
+    }
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        window.show().ok();
+        window.unminimize().ok();
+        window.set_focus().ok();
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        "start" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                start_services(app, None).await.ok();
+            });
+        }
+        "stop" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                stop_services(app).await.ok();
+            });
+        }
+        "show" => show_main_window(app),
+        "quit" => quit(app),
+        _ => {}
+    }
+}
+
+/// Handles the main window's close button according to `on_close`.
+pub fn close_requested(window: &Window<AppRuntime>, api: &CloseRequestApi) {
+    if window.label() != "main" {
+        return;
+    }
+    if shutdown::in_progress() {
+        api.prevent_close();
+        return;
+    }
+    let app = window.app_handle();
+    if !shutdown::services_active(app) {
+        return;
+    }
+
+    api.prevent_close();
+    match app.state::<SettingsStore>().get().on_close {
+        CloseAction::Ask => ask_on_close(app),
+        CloseAction::Minimize => hide_to_tray(app),
+        CloseAction::StopAndExit => stop_and_exit(app),
+        CloseAction::Exit => quit(app),
+    }
+}
+
+fn hide_to_tray(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        window.hide().ok();
+    }
+    emit_log(app, "info", MessageKey::WindowHidden);
+}
+
+fn ask_on_close(app: &AppHandle) {
+    let handle = app.clone();
+    app.dialog()
+        .message("A.B.E.L. services are still running. Stop them before exiting?")
+        .title("A.B.E.L.")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            STOP_AND_EXIT.to_string(),
+            KEEP_RUNNING.to_string(),
+            "Cancel".to_string(),
+        ))
+        .show_with_result(move |result| match result {
+            MessageDialogResult::Yes => stop_and_exit(&handle),
+            MessageDialogResult::Custom(label) if label == STOP_AND_EXIT => stop_and_exit(&handle),
+            MessageDialogResult::No => hide_to_tray(&handle),
+            MessageDialogResult::Custom(label) if label == KEEP_RUNNING => hide_to_tray(&handle),
+            _ => {}
+        });
+}
+
+/// Keeps the window up with the shutdown's progress, and exits only once the
+/// stack is down. A failed stop leaves the launcher open to show why.
+fn stop_and_exit(app: &AppHandle) {
+    if !shutdown::begin() {
+        return;
+    }
+    show_main_window(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match stop_sequence(&app).await {
+            Ok(true) => shutdown::exit(&app, 0),
+            Ok(false) => emit_log(&app, "error", MessageKey::ExitCancelled),
+            Err(error) => {
+                emit_log(&app, "error", MessageKey::ExitCancelledError.with(json!({ "error": error.to_string() })))
+            }
+        }
+        shutdown::abandon();
+    });
+}
+
+/// `stop_on_exit` is honored by the exit hook, which every way of quitting goes through.
+fn quit(app: &AppHandle) {
+    app.exit(0);
+}
@@ -0,0 +1,216 @@
+use crate::SettingsStore;
+use chrono::{DateTime, Utc};
+use reqwest::header::ACCEPT;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::sync::OnceLock;
+use std::time::Duration;
+use crate::AppHandle;
+use tauri::Manager;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/Adam-Blf/abel-assistant/releases/latest";
+/// A check younger than this is answered from the settings file. GitHub allows 60
+/// unauthenticated requests an hour, shared by everything on the user's address.
+const CACHE_HOURS: i64 = 24;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The version this launcher was built as, kept in step with `tauri.conf.json`.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateState {
+    Available,
+    UpToDate,
+    /// GitHub couldn't be reached or answered something unusable.
+    Unknown,
+}
+
+/// Result of `check_for_updates`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UpdateCheck {
+    pub state: UpdateState,
+    pub current_version: String,
+    pub latest_version: Option<String>,
+    /// The release body, markdown as written on GitHub.
+    pub release_notes: Option<String>,
+    /// An installer for this platform when the release has one, else the release page.
+    pub download_url: Option<String>,
+    /// RFC 3339 instant GitHub was asked.
+    pub checked_at: String,
+}
+
+impl UpdateCheck {
+    fn unknown() -> Self {
+        UpdateCheck {
+            state: UpdateState::Unknown,
+            current_version: CURRENT_VERSION.to_string(),
+            latest_version: None,
+            release_notes: None,
+            download_url: None,
+            checked_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Answered recently enough, by this very build.
+    fn is_fresh(&self) -> bool {
+        let age = DateTime::parse_from_rfc3339(&self.checked_at).map(|at| Utc::now() - at.with_timezone(&Utc));
+        self.current_version == CURRENT_VERSION
+            && age.is_ok_and(|age| age >= chrono::Duration::zero() && age < chrono::Duration::hours(CACHE_HOURS))
+    }
+}
+
+/// A semantic version. Build metadata is dropped, as it takes no part in precedence.
+#[derive(Debug, PartialEq, Eq)]
+struct Version {
+    core: (u64, u64, u64),
+    pre: Vec<String>,
+}
+
+/// Release tags are `v1.2.3` or `1.2.3`, optionally `-rc.1` and `+build`.
+fn parse_version(tag: &str) -> Option<Version> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+    let tag = tag.split('+').next()?;
+    let (core, pre) = match tag.split_once('-') {
+        Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+        None => (tag, Vec::new()),
+    };
+    let mut numbers = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = Version {
+        core: (numbers.next()??, numbers.next()??, numbers.next()??),
+        pre,
+    };
+    numbers.next().is_none().then_some(version)
+}
+
+/// Pre-release identifiers: numeric ones compare as numbers and sort before alphanumeric ones.
+fn compare_identifier(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core.cmp(&other.core).then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+            // A release outranks its own pre-releases
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self
+                .pre
+                .iter()
+                .zip(&other.pre)
+                .map(|(a, b)| compare_identifier(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| self.pre.len().cmp(&other.pre.len())),
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Installer suffixes, preferred first, that this platform can run.
+fn installer_suffixes() -> &'static [&'static str] {
+    if cfg!(windows) {
+        &[".msi", "-setup.exe", ".exe"]
+    } else if cfg!(target_os = "macos") {
+        &[".dmg"]
+    } else {
+        &[".AppImage", ".deb", ".rpm"]
+    }
+}
+
+fn download_url(release: &Value) -> Option<String> {
+    let assets: Vec<(&str, &str)> = release
+        .get("assets")
+        .and_then(Value::as_array)
+        .map(|assets| {
+            assets
+                .iter()
+                .filter_map(|asset| {
+                    let name = asset.get("name").and_then(Value::as_str)?;
+                    Some((name, asset.get("browser_download_url").and_then(Value::as_str)?))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    installer_suffixes()
+        .iter()
+        .find_map(|suffix| assets.iter().find(|(name, _)| name.ends_with(suffix)))
+        .map(|(_, url)| url.to_string())
+        .or_else(|| release.get("html_url").and_then(Value::as_str).map(str::to_string))
+}
+
+/// Reads the `releases/latest` answer, which never holds drafts or pre-releases.
+fn from_release(release: &Value) -> UpdateCheck {
+    let Some(tag) = release.get("tag_name").and_then(Value::as_str) else {
+        return UpdateCheck::unknown();
+    };
+    let (Some(latest), Some(current)) = (parse_version(tag), parse_version(CURRENT_VERSION)) else {
+        return UpdateCheck::unknown();
+    };
+    let newer = latest > current;
+    UpdateCheck {
+        state: if newer { UpdateState::Available } else { UpdateState::UpToDate },
+        latest_version: Some(tag.trim_start_matches(['v', 'V']).to_string()),
+        release_notes: release
+            .get("body")
+            .and_then(Value::as_str)
+            .filter(|body| newer && !body.trim().is_empty())
+            .map(str::to_string),
+        download_url: newer.then(|| download_url(release)).flatten(),
+        ..UpdateCheck::unknown()
+    }
+}
+
+/// GitHub turns away requests without a user agent. Unlike the core's client this
+/// one goes out to the internet, so system proxies apply.
+fn client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(format!("abel-launcher/{}", CURRENT_VERSION))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+async fn fetch_latest() -> UpdateCheck {
+    let response = match client().get(LATEST_RELEASE_URL).header(ACCEPT, "application/vnd.github+json").send().await {
+        Ok(response) if response.status().is_success() => response,
+        _ => return UpdateCheck::unknown(),
+    };
+    match response.text().await.map(|body| serde_json::from_str::<Value>(&body)) {
+        Ok(Ok(release)) => from_release(&release),
+        _ => UpdateCheck::unknown(),
+    }
+}
+
+/// The newest release compared with this build. Offline, or on any answer that
+/// can't be read, the state is `unknown`; those results aren't cached.
+pub async fn check(app: &AppHandle, force: bool) -> UpdateCheck {
+    let cached = app.state::<SettingsStore>().get().last_update_check;
+    if let Some(cached) = cached.filter(|cached| !force && cached.is_fresh()) {
+        return cached;
+    }
+    let result = fetch_latest().await;
+    if result.state != UpdateState::Unknown {
+        let saved = result.clone();
+        app.state::<SettingsStore>()
+            .update(|settings| settings.last_update_check = Some(saved))
+            .ok();
+    }
+    result
+}
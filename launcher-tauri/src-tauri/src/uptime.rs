@@ -0,0 +1,80 @@
+use crate::{docker, observe_services, resolve_project, SettingsStore};
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+use crate::AppHandle;
+use tauri::Manager;
+
+/// When the stack last finished booting, managed as state and mirrored into the
+/// settings file so a launcher restart doesn't reset it while containers stay up.
+#[derive(Default)]
+pub struct Uptime(Mutex<Option<DateTime<Utc>>>);
+
+fn parse(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|time| time.with_timezone(&Utc))
+}
+
+fn set(app: &AppHandle, since: Option<DateTime<Utc>>) {
+    let Some(uptime) = app.try_state::<Uptime>() else {
+        return;
+    };
+    let changed = {
+        let mut current = uptime.0.lock().unwrap();
+        std::mem::replace(&mut *current, since) != since
+    };
+    // Only on change, since every stopped status passes through here
+    if changed {
+        if let Some(settings) = app.try_state::<SettingsStore>() {
+            settings
+                .update(|settings| settings.online_since = since.map(|time| time.to_rfc3339()))
+                .ok();
+        }
+    }
+}
+
+/// A boot or restart just completed.
+pub fn mark_online(app: &AppHandle) {
+    set(app, Some(Utc::now()));
+}
+
+/// The stack stopped or lost a service.
+pub fn clear(app: &AppHandle) {
+    set(app, None);
+}
+
+/// Seconds since the stack came online, or None while it isn't.
+pub fn secs(app: &AppHandle) -> Option<u64> {
+    let since = (*app.try_state::<Uptime>()?.0.lock().unwrap())?;
+    Some((Utc::now() - since).num_seconds().max(0) as u64)
+}
+
+/// Picks up where the last launcher left off. The saved instant only stands while
+/// every service is still up, and never predates the newest container start: a
+/// container that was restarted outside the launcher restarted the clock too.
+pub async fn reconcile(app: AppHandle) {
+    let project = resolve_project(&app);
+    let Ok((status, services)) = observe_services(&project).await else {
+        return;
+    };
+    if !status.running {
+        clear(&app);
+        return;
+    }
+
+    let ids: Vec<String> = services
+        .iter()
+        .filter(|service| service.is_running())
+        .map(|service| service.container_id.clone())
+        .collect();
+    let latest_start = docker::started_at(&ids).await.iter().filter_map(|value| parse(value)).max();
+    let saved = app
+        .state::<SettingsStore>()
+        .get()
+        .online_since
+        .as_deref()
+        .and_then(parse);
+    let since = match (saved, latest_start) {
+        (Some(saved), Some(started)) => Some(saved.max(started)),
+        (saved, started) => saved.or(started),
+    };
+    set(&app, since);
+}
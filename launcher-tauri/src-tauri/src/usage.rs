@@ -0,0 +1,167 @@
+use crate::compose::{self, Project};
+use crate::docker::{self, parse_size};
+use crate::error::LauncherError;
+use serde::Serialize;
+use serde_json::Value;
+
+/// An image or volume and what it takes up.
+#[derive(Clone, Serialize)]
+pub struct ItemSize {
+    pub name: String,
+    pub size_bytes: u64,
+}
+
+/// Disk used by the stack, as returned by `get_disk_usage`.
+#[derive(Clone, Serialize)]
+pub struct DiskUsage {
+    pub images: Vec<ItemSize>,
+    pub volumes: Vec<ItemSize>,
+    /// Docker keeps one build cache for everything, so this isn't the stack's alone.
+    pub build_cache_bytes: u64,
+    pub images_bytes: u64,
+    pub volumes_bytes: u64,
+    pub total_bytes: u64,
+}
+
+impl DiskUsage {
+    pub fn summary(&self) -> String {
+        format!(
+            "IMAGES {}, VOLUMES {}, BUILD CACHE {}, TOTAL {}",
+            human(self.images_bytes),
+            human(self.volumes_bytes),
+            human(self.build_cache_bytes),
+            human(self.total_bytes)
+        )
+    }
+}
+
+fn human(bytes: u64) -> String {
+    if bytes >= 1_000_000_000 {
+        format!("{:.1} GB", bytes as f64 / 1e9)
+    } else {
+        format!("{:.1} MB", bytes as f64 / 1e6)
+    }
+}
+
+async fn docker_stdout(args: &[&str]) -> Result<String, LauncherError> {
+    let output = docker::command()
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| LauncherError::spawn("docker", e))?;
+    if !output.status.success() {
+        return Err(LauncherError::from_failure(output.status.code(), &String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Build cache size from `system df --format json`, one object per resource type.
+/// Older clients print the literal template instead, which parses as nothing.
+fn build_cache_from_json(stdout: &str) -> Option<u64> {
+    stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+        .find(|entry| entry.get("Type").and_then(Value::as_str) == Some("Build Cache"))
+        .and_then(|entry| entry.get("Size").and_then(Value::as_str).and_then(parse_size))
+}
+
+/// The same from the table: `Build Cache   12   0   1.2GB   1.2GB`.
+fn build_cache_from_table(stdout: &str) -> Option<u64> {
+    let line = stdout.lines().find_map(|line| line.trim().strip_prefix("Build Cache"))?;
+    // TOTAL ACTIVE SIZE RECLAIMABLE
+    line.split_whitespace().nth(2).and_then(parse_size)
+}
+
+/// Volume sizes from the `VOLUME NAME  LINKS  SIZE` section of `system df -v`, which
+/// is the only place docker reports them.
+pub fn volume_sizes(stdout: &str) -> Vec<ItemSize> {
+    stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("VOLUME NAME"))
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let name = columns.next()?.to_string();
+            let size_bytes = columns.last().and_then(parse_size)?;
+            Some(ItemSize { name, size_bytes })
+        })
+        .collect()
+}
+
+/// `name:tag` references the stack's images go by. Built services without an
+/// `image` are tagged `<project>-<service>`, or `<project>_<service>` by compose v1.
+fn image_references(config: &Value, project_name: &str) -> Vec<String> {
+    let Some(services) = config.get("services").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut references = Vec::new();
+    for (service, definition) in services {
+        match definition.get("image").and_then(Value::as_str) {
+            // A registry port (`localhost:5000/abel`) is not a tag
+            Some(image) if image.rsplit('/').next().is_some_and(|last| last.contains(':')) => {
+                references.push(image.to_string())
+            }
+            Some(image) => references.push(format!("{}:latest", image)),
+            None => {
+                references.push(format!("{}-{}:latest", project_name, service));
+                references.push(format!("{}_{}:latest", project_name, service));
+            }
+        }
+    }
+    references
+}
+
+/// `image ls --format '{{json .}}'` lines for the referenced images.
+fn image_sizes(stdout: &str, references: &[String]) -> Vec<ItemSize> {
+    let mut images: Vec<ItemSize> = stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+        .filter_map(|entry| {
+            let field = |key: &str| entry.get(key).and_then(Value::as_str).unwrap_or_default();
+            let name = format!("{}:{}", field("Repository"), field("Tag"));
+            let size_bytes = parse_size(field("Size"))?;
+            references.contains(&name).then_some(ItemSize { name, size_bytes })
+        })
+        .collect();
+    images.sort_by(|a, b| a.name.cmp(&b.name));
+    images
+}
+
+pub async fn disk_usage(project: &Project) -> Result<DiskUsage, LauncherError> {
+    let config = compose::config_json(project).await?;
+    let name = compose::project_name(&config, project);
+
+    let listed = docker_stdout(&["image", "ls", "--format", "{{json .}}"]).await?;
+    let images = image_sizes(&listed, &image_references(&config, &name));
+
+    let label = format!("label=com.docker.compose.project={}", name);
+    let labeled = docker_stdout(&["volume", "ls", "--filter", &label, "--format", "{{.Name}}"]).await?;
+    let labeled: Vec<&str> = labeled.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    let verbose = docker_stdout(&["system", "df", "-v"]).await?;
+    let volumes: Vec<ItemSize> = volume_sizes(&verbose)
+        .into_iter()
+        .filter(|volume| labeled.contains(&volume.name.as_str()))
+        .collect();
+
+    let from_json = docker_stdout(&["system", "df", "--format", "json"])
+        .await
+        .ok()
+        .and_then(|stdout| build_cache_from_json(&stdout));
+    let build_cache_bytes = match from_json {
+        Some(bytes) => bytes,
+        None => build_cache_from_table(&docker_stdout(&["system", "df"]).await?).unwrap_or(0),
+    };
+
+    let images_bytes = images.iter().map(|image| image.size_bytes).sum();
+    let volumes_bytes = volumes.iter().map(|volume| volume.size_bytes).sum();
+    Ok(DiskUsage {
+        images,
+        volumes,
+        build_cache_bytes,
+        images_bytes,
+        volumes_bytes,
+        total_bytes: images_bytes + volumes_bytes + build_cache_bytes,
+    })
+}
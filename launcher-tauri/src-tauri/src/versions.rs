@@ -0,0 +1,189 @@
+use crate::compose::{self, ComposeFlavor, Project};
+use crate::{docker, update};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tokio::process::Command;
+
+/// The image a compose service runs, or would run when it has no container.
+#[derive(Clone, Serialize)]
+pub struct ServiceImage {
+    pub service: String,
+    /// Reference as written in the compose file or recorded on the container.
+    pub image: Option<String>,
+    /// Local image id of the running container.
+    pub image_id: Option<String>,
+    /// Registry digest. Locally built images have none.
+    pub digest: Option<String>,
+}
+
+/// Everything a support request needs to know about the install, as returned by
+/// `get_versions`. Each part is probed on its own, so any of them may be missing.
+#[derive(Clone, Serialize)]
+pub struct Versions {
+    pub launcher: String,
+    pub docker_client: Option<String>,
+    pub docker_server: Option<String>,
+    pub compose_version: Option<String>,
+    pub compose_flavor: Option<ComposeFlavor>,
+    /// `git describe` of the project dir when it is a checkout.
+    pub project_git: Option<String>,
+    pub images: Vec<ServiceImage>,
+}
+
+fn or_unknown(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("unknown")
+}
+
+impl Versions {
+    /// A markdown block to paste into an issue.
+    pub fn to_markdown(&self) -> String {
+        let compose = match (&self.compose_version, self.compose_flavor) {
+            (Some(version), Some(ComposeFlavor::Plugin)) => format!("{} (docker compose)", version),
+            (Some(version), Some(ComposeFlavor::Standalone)) => format!("{} (docker-compose)", version),
+            (version, _) => or_unknown(version).to_string(),
+        };
+        let mut text = format!(
+            "### A.B.E.L. versions\n\n\
+             - Launcher: {}\n\
+             - Docker: client {}, server {}\n\
+             - Compose: {}\n\
+             - Project: {}\n",
+            self.launcher,
+            or_unknown(&self.docker_client),
+            or_unknown(&self.docker_server),
+            compose,
+            or_unknown(&self.project_git),
+        );
+        if !self.images.is_empty() {
+            text.push_str("\n| Service | Image | Digest |\n| --- | --- | --- |\n");
+            for image in &self.images {
+                let id = image.image_id.as_deref().map(|id| id.trim_start_matches("sha256:"));
+                let digest = image.digest.as_deref().or(id.map(|id| &id[..id.len().min(12)]));
+                text.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    image.service,
+                    image.image.as_deref().unwrap_or("-"),
+                    digest.unwrap_or("-")
+                ));
+            }
+        }
+        text
+    }
+}
+
+/// `git describe --tags --dirty`, falling back to the bare commit when nothing is tagged.
+/// None without git, outside a checkout, or in a repository with no commits.
+async fn git_describe(dir: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["-C", dir, "describe", "--tags", "--dirty", "--always"])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+    let described = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !described.is_empty()).then_some(described)
+}
+
+/// `docker <kind> inspect` prints a JSON array, one object per argument that exists.
+async fn inspect(kind: &str, ids: &[String]) -> Vec<Value> {
+    if ids.is_empty() {
+        return Vec::new();
+    }
+    let Ok(output) = docker::command()
+        .args([kind, "inspect"])
+        .args(ids)
+        .kill_on_drop(true)
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    match serde_json::from_slice(&output.stdout) {
+        Ok(Value::Array(entries)) => entries,
+        _ => Vec::new(),
+    }
+}
+
+/// The reference each service's config declares. Built services declare none.
+fn declared_images(config: &Value) -> BTreeMap<String, Option<String>> {
+    config
+        .get("services")
+        .and_then(Value::as_object)
+        .map(|services| {
+            services
+                .iter()
+                .map(|(service, definition)| {
+                    (service.clone(), definition.get("image").and_then(Value::as_str).map(str::to_string))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// First `RepoDigests` entry of each image, keyed by image id.
+fn digests(images: &[Value]) -> BTreeMap<String, String> {
+    images
+        .iter()
+        .filter_map(|image| {
+            let id = image.get("Id").and_then(Value::as_str)?;
+            let digest = image.pointer("/RepoDigests/0").and_then(Value::as_str)?;
+            Some((id.to_string(), digest.to_string()))
+        })
+        .collect()
+}
+
+async fn service_images(project: &Project) -> Vec<ServiceImage> {
+    let mut images: BTreeMap<String, ServiceImage> = match compose::config_json(project).await {
+        Ok(config) => declared_images(&config)
+            .into_iter()
+            .map(|(service, image)| (service.clone(), ServiceImage { service, image, image_id: None, digest: None }))
+            .collect(),
+        Err(_) => BTreeMap::new(),
+    };
+
+    let statuses = compose::service_status(project).await.unwrap_or_default();
+    let ids: Vec<String> = statuses
+        .iter()
+        .filter(|status| !status.container_id.is_empty())
+        .map(|status| status.container_id.clone())
+        .collect();
+    let containers = inspect("container", &ids).await;
+    for status in statuses.iter().filter(|status| !status.container_id.is_empty()) {
+        // ps may print short ids, inspect always prints full ones
+        let Some(container) = containers.iter().find(|container| {
+            container.get("Id").and_then(Value::as_str).is_some_and(|id| id.starts_with(&status.container_id))
+        }) else {
+            continue;
+        };
+        let entry = images.entry(status.service.clone()).or_insert_with(|| ServiceImage {
+            service: status.service.clone(),
+            image: None,
+            image_id: None,
+            digest: None,
+        });
+        entry.image = container.pointer("/Config/Image").and_then(Value::as_str).map(str::to_string).or(entry.image.take());
+        entry.image_id = container.get("Image").and_then(Value::as_str).map(str::to_string);
+    }
+
+    let image_ids: Vec<String> = images.values().filter_map(|image| image.image_id.clone()).collect();
+    let digests = digests(&inspect("image", &image_ids).await);
+    for image in images.values_mut() {
+        image.digest = image.image_id.as_ref().and_then(|id| digests.get(id).cloned());
+    }
+    images.into_values().collect()
+}
+
+pub async fn collect(project: &Project) -> Versions {
+    let docker = docker::get_info().await;
+    let compose = docker.compose;
+    Versions {
+        launcher: update::CURRENT_VERSION.to_string(),
+        docker_client: docker.client_version,
+        docker_server: docker.server_version,
+        compose_version: compose.as_ref().map(|info| info.version.clone()),
+        compose_flavor: compose.map(|info| info.flavor),
+        project_git: git_describe(&project.dir).await,
+        images: service_images(project).await,
+    }
+}
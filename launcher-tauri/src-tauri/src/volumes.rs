@@ -0,0 +1,79 @@
+use crate::compose::{self, Project};
+use crate::docker;
+use crate::error::LauncherError;
+use crate::usage;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A volume labelled as belonging to the project, as returned by `list_volumes`.
+#[derive(Clone, Serialize)]
+pub struct VolumeInfo {
+    pub name: String,
+    /// Key under `volumes:` in the compose file.
+    pub key: Option<String>,
+    pub driver: Option<String>,
+    pub mountpoint: Option<String>,
+    pub created_at: Option<String>,
+    /// Only local volumes have a size docker can report.
+    pub size_bytes: Option<u64>,
+}
+
+async fn docker_stdout(args: &[&str]) -> Result<String, LauncherError> {
+    let output = docker::command()
+        .args(args)
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| LauncherError::spawn("docker", e))?;
+    if !output.status.success() {
+        return Err(LauncherError::from_failure(output.status.code(), &String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn from_inspect(entry: &Value) -> Option<VolumeInfo> {
+    let field = |pointer: &str| {
+        entry
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    Some(VolumeInfo {
+        name: field("/Name")?,
+        key: field("/Labels/com.docker.compose.volume"),
+        driver: field("/Driver"),
+        mountpoint: field("/Mountpoint"),
+        created_at: field("/CreatedAt"),
+        size_bytes: None,
+    })
+}
+
+/// Volumes compose created for the project, found by label rather than by the compose
+/// file so ones left behind by services since removed are listed too. Containers
+/// needn't exist.
+pub async fn list(project: &Project) -> Result<Vec<VolumeInfo>, LauncherError> {
+    // A broken compose file shouldn't hide the volumes; the directory still names the project
+    let config = compose::config_json(project).await.unwrap_or(Value::Null);
+    let label = format!("label=com.docker.compose.project={}", compose::project_name(&config, project));
+    let listed = docker_stdout(&["volume", "ls", "--filter", &label, "--format", "{{.Name}}"]).await?;
+    let names: Vec<&str> = listed.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut args = vec!["volume", "inspect"];
+    args.extend(&names);
+    let inspected: Vec<Value> = serde_json::from_str(&docker_stdout(&args).await?).unwrap_or_default();
+    let mut volumes: Vec<VolumeInfo> = inspected.iter().filter_map(from_inspect).collect();
+
+    // Sizes are worth a slow `system df -v`, but not a failed listing
+    if let Ok(verbose) = docker_stdout(&["system", "df", "-v"]).await {
+        let sizes = usage::volume_sizes(&verbose);
+        for volume in &mut volumes {
+            volume.size_bytes = sizes.iter().find(|size| size.name == volume.name).map(|size| size.size_bytes);
+        }
+    }
+    volumes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(volumes)
+}
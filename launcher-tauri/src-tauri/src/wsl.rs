@@ -0,0 +1,137 @@
+use serde::Serialize;
+use std::process::Output;
+use tokio::process::Command;
+
+/// Docker Desktop's own distro. Releases before 4.30 also had `docker-desktop-data`.
+const DOCKER_DISTRO: &str = "docker-desktop";
+
+/// What the user has to do, in order, for the WSL 2 backend to work. The UI turns
+/// each into step-by-step instructions.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WslRemediation {
+    /// Turn on Intel VT-x or AMD-V in the BIOS/UEFI settings.
+    EnableVirtualization,
+    /// `wsl --install`, then reboot.
+    InstallWsl,
+    /// `wsl --update`.
+    UpdateWslKernel,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WslDistro {
+    pub name: String,
+    /// `Running` or `Stopped`, as `wsl -l -v` prints it.
+    pub state: String,
+    pub version: Option<u8>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WslCheck {
+    pub installed: bool,
+    pub default_version: Option<u8>,
+    pub docker_distro: Option<WslDistro>,
+    /// None when neither the processor nor the hypervisor could be queried.
+    pub virtualization_enabled: Option<bool>,
+    pub remediation: Vec<WslRemediation>,
+}
+
+/// Result of `check_wsl`.
+#[derive(Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum WslReport {
+    /// Not Windows; Docker has no WSL backend to check.
+    NotApplicable,
+    Checked(WslCheck),
+}
+
+/// `wsl.exe` writes UTF-16LE when its output is piped, unless `WSL_UTF8` is set.
+fn decode(bytes: &[u8]) -> String {
+    let utf16 = bytes.len() >= 2 && bytes.len().is_multiple_of(2) && bytes.iter().skip(1).step_by(2).any(|byte| *byte == 0);
+    if utf16 {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+        return String::from_utf16_lossy(&units).trim_start_matches('\u{feff}').to_string();
+    }
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+async fn run(program: &str, args: &[&str]) -> std::io::Result<Output> {
+    Command::new(program).args(args).env("WSL_UTF8", "1").kill_on_drop(true).output().await
+}
+
+fn text(output: &Output) -> String {
+    format!("{}\n{}", decode(&output.stdout), decode(&output.stderr)).to_lowercase()
+}
+
+/// Reads `wsl --status` as installed, default version and whether the kernel needs
+/// an update. Messages are localized, but the commands they point at are not.
+fn parse_status(success: bool, text: &str) -> (bool, Option<u8>, bool) {
+    let not_installed = !success && text.contains("--install");
+    let needs_update = text.contains("wsl --update") || text.contains("wsl.exe --update");
+    let default_version = text
+        .lines()
+        .filter(|line| line.contains("version"))
+        .find_map(|line| line.rsplit(':').next()?.trim().parse::<u8>().ok());
+    (!not_installed, default_version, needs_update)
+}
+
+/// Reads `wsl -l -v`: a header, then `[*] NAME STATE VERSION` per distro, the
+/// default one starred.
+fn parse_distros(text: &str) -> Vec<WslDistro> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.trim().trim_start_matches('*').split_whitespace();
+            let name = fields.next()?.to_string();
+            let state = fields.next()?.to_string();
+            let version = fields.next().and_then(|version| version.parse().ok());
+            Some(WslDistro { name, state, version })
+        })
+        .collect()
+}
+
+/// The firmware flag reads false once Hyper-V runs, since the hypervisor then owns
+/// the extensions, so a present hypervisor counts as enabled too.
+async fn virtualization_enabled() -> Option<bool> {
+    let script = "$cs = Get-CimInstance Win32_ComputerSystem; \
+                  $cpu = Get-CimInstance Win32_Processor | Select-Object -First 1; \
+                  \"$($cs.HypervisorPresent) $($cpu.VirtualizationFirmwareEnabled)\"";
+    let output = run("powershell", &["-NoProfile", "-NonInteractive", "-Command", script]).await.ok()?;
+    let text = decode(&output.stdout).to_lowercase();
+    let mut flags = text.split_whitespace().map(|flag| flag == "true");
+    let (hypervisor, firmware) = (flags.next()?, flags.next()?);
+    Some(hypervisor || firmware)
+}
+
+pub async fn check() -> WslReport {
+    if !cfg!(windows) {
+        return WslReport::NotApplicable;
+    }
+
+    let virtualization = virtualization_enabled().await;
+    let (installed, default_version, needs_update) = match run("wsl", &["--status"]).await {
+        Ok(output) => parse_status(output.status.success(), &text(&output)),
+        // No wsl.exe at all: WSL was never enabled, or Windows predates it
+        Err(_) => (false, None, false),
+    };
+    let listed = if installed { run("wsl", &["-l", "-v"]).await.ok() } else { None };
+    let distros = listed.map(|output| parse_distros(&decode(&output.stdout))).unwrap_or_default();
+    let docker_distro = distros.into_iter().find(|distro| distro.name.eq_ignore_ascii_case(DOCKER_DISTRO));
+
+    let mut remediation = Vec::new();
+    if virtualization == Some(false) {
+        remediation.push(WslRemediation::EnableVirtualization);
+    }
+    if !installed {
+        remediation.push(WslRemediation::InstallWsl);
+    } else if needs_update {
+        remediation.push(WslRemediation::UpdateWslKernel);
+    }
+    WslReport::Checked(WslCheck {
+        installed,
+        default_version,
+        docker_distro,
+        virtualization_enabled: virtualization,
+        remediation,
+    })
+}
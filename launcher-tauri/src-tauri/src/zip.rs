@@ -0,0 +1,100 @@
+use chrono::{Datelike, Local, Timelike};
+use std::io::{self, Write};
+
+/// Writes a zip archive of stored (uncompressed) entries. Diagnostic bundles are
+/// small text files, so a deflate dependency would buy little.
+pub struct ZipWriter<W: Write> {
+    writer: W,
+    offset: u64,
+    central: Vec<u8>,
+    entries: u16,
+    time: u16,
+    date: u16,
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Zip's MS-DOS time and date fields, which can't go before 1980.
+fn dos_time() -> (u16, u16) {
+    let now = Local::now();
+    let time = ((now.hour() << 11) | (now.minute() << 5) | (now.second() / 2)) as u16;
+    let date = ((((now.year().max(1980) - 1980) as u32) << 9) | (now.month() << 5) | now.day()) as u16;
+    (time, date)
+}
+
+fn too_large() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "entry too large for a zip without zip64")
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(writer: W) -> Self {
+        let (time, date) = dos_time();
+        ZipWriter { writer, offset: 0, central: Vec::new(), entries: 0, time, date }
+    }
+
+    pub fn add(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let size = u32::try_from(data.len()).map_err(|_| too_large())?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+        let name_len = u16::try_from(name.len()).map_err(|_| too_large())?;
+        let crc = crc32(data);
+
+        // Version 2.0, bit 11 for UTF-8 names, method 0 (stored)
+        let mut common = Vec::with_capacity(26);
+        common.extend_from_slice(&20u16.to_le_bytes());
+        common.extend_from_slice(&0x0800u16.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+        common.extend_from_slice(&self.time.to_le_bytes());
+        common.extend_from_slice(&self.date.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&size.to_le_bytes());
+        common.extend_from_slice(&name_len.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+
+        let mut local = Vec::with_capacity(30 + name.len());
+        local.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        local.extend_from_slice(&common);
+        local.extend_from_slice(name.as_bytes());
+        self.writer.write_all(&local)?;
+        self.writer.write_all(data)?;
+        self.offset += (local.len() + data.len()) as u64;
+
+        self.central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes());
+        self.central.extend_from_slice(&common);
+        // Comment length, disk number, internal and external attributes
+        self.central.extend_from_slice(&[0; 10]);
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries = self.entries.checked_add(1).ok_or_else(too_large)?;
+        Ok(())
+    }
+
+    /// Writes the central directory and hands the writer back.
+    pub fn finish(mut self) -> io::Result<W> {
+        let size = u32::try_from(self.central.len()).map_err(|_| too_large())?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+        self.writer.write_all(&self.central)?;
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        end.extend_from_slice(&[0; 4]);
+        end.extend_from_slice(&self.entries.to_le_bytes());
+        end.extend_from_slice(&self.entries.to_le_bytes());
+        end.extend_from_slice(&size.to_le_bytes());
+        end.extend_from_slice(&offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        self.writer.write_all(&end)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
@@ -25,13 +25,38 @@ document.addEventListener('DOMContentLoaded', async () => {
 
     startDataStream();
 
+    // Wired here rather than inline, which the content security policy forbids
+    document.getElementById('minimizeBtn').addEventListener('click', minimizeWindow);
+    document.getElementById('closeBtn').addEventListener('click', closeWindow);
+    startBtn.addEventListener('click', startServices);
+    stopBtn.addEventListener('click', stopServices);
+    document.querySelectorAll('.link-card[data-url]').forEach(link => {
+        link.addEventListener('click', (e) => {
+            e.preventDefault();
+            openLink(link.dataset.url);
+        });
+    });
+
     // Listen for events from Rust
     await listen('status', (event) => {
         updateStatus(event.payload.running, event.payload.starting);
     });
 
+    // Replay whatever was logged before the window loaded (e.g. a boot started from the tray)
+    try {
+        const history = await invoke('get_log_history', { limit: null });
+        history.forEach((entry) => addLog(entry.message, entry.level, entry.display_time, entry.color));
+    } catch (e) {
+        addLog('LOG HISTORY UNAVAILABLE', 'warning');
+    }
+
     await listen('log', (event) => {
-        addLog(event.payload.message, event.payload.level, event.payload.timestamp);
+        addLog(event.payload.message, event.payload.level, event.payload.display_time, event.payload.color);
+    });
+
+    // Child process output arrives in batches, at most every 50ms
+    await listen('log_batch', (event) => {
+        event.payload.forEach((entry) => addLog(entry.message, entry.level, entry.display_time, entry.color));
     });
 
     // Check initial status
@@ -44,7 +69,11 @@ document.addEventListener('DOMContentLoaded', async () => {
             addLog('SYSTEM READY FOR INITIALIZATION', 'info');
         }
     } catch (e) {
-        addLog('STATUS CHECK FAILED', 'warning');
+        if (e && e.code === 'compose_file_not_found') {
+            addLog(`NO COMPOSE FILE IN ${e.searched_dir} - SELECT THE A.B.E.L. PROJECT DIRECTORY`, 'error');
+        } else {
+            addLog('STATUS CHECK FAILED', 'warning');
+        }
     }
 });
 
@@ -98,7 +127,10 @@ function updateStatus(running, starting) {
 }
 
 // === LOGGING ===
-function addLog(message, level = 'info', timestamp = null) {
+const LOG_LEVELS = new Set(['info', 'success', 'warning', 'error', 'debug']);
+const ANSI_COLORS = new Set(['black', 'red', 'green', 'yellow', 'blue', 'magenta', 'cyan', 'white']);
+
+function addLog(message, level = 'info', timestamp = null, color = null) {
     if (!timestamp) {
         const now = new Date();
         timestamp = `${now.toTimeString().split(' ')[0]}.${String(now.getMilliseconds()).padStart(3, '0')}`;
@@ -106,10 +138,18 @@ function addLog(message, level = 'info', timestamp = null) {
 
     const entry = document.createElement('div');
     entry.className = 'log-entry';
-    entry.innerHTML = `
-        <span class="log-time">[${timestamp}]</span>
-        <span class="log-msg ${level}">${message}</span>
-    `;
+
+    // Lines come from docker and the containers, so they are only ever set as text
+    const time = document.createElement('span');
+    time.className = 'log-time';
+    time.textContent = `[${timestamp}]`;
+    const msg = document.createElement('span');
+    msg.className = LOG_LEVELS.has(level) ? `log-msg ${level}` : 'log-msg';
+    if (ANSI_COLORS.has(color)) {
+        msg.classList.add(`ansi-${color}`);
+    }
+    msg.textContent = message;
+    entry.append(time, msg);
 
     logContainer.appendChild(entry);
     logContainer.scrollTop = logContainer.scrollHeight;
@@ -121,11 +161,16 @@ function addLog(message, level = 'info', timestamp = null) {
 }
 
 // === COMMANDS ===
+// Commands reject with { code, message, ... }; anything else is shown as-is
+function errorMessage(e) {
+    return e && e.message ? e.message : String(e);
+}
+
 async function startServices() {
     try {
         await invoke('start_services');
     } catch (e) {
-        addLog(`ERROR: ${e}`, 'error');
+        addLog(`ERROR: ${errorMessage(e)}`, 'error');
         updateStatus(false, false);
     }
 }
@@ -134,7 +179,7 @@ async function stopServices() {
     try {
         await invoke('stop_services');
     } catch (e) {
-        addLog(`ERROR: ${e}`, 'error');
+        addLog(`ERROR: ${errorMessage(e)}`, 'error');
     }
 }
 